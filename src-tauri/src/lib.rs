@@ -1,304 +1,13534 @@
+use std::collections::HashMap;
 use std::process::Command as StdCommand;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::Emitter;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-/// Creates a scheduled task that runs a PowerShell script at the specified time.
-/// On Windows, uses schtasks.exe. On macOS/Linux, uses crontab.
+/// Debug builds only: when set, internal helper processes (schtasks, curl,
+/// taskkill) are spawned without CREATE_NO_WINDOW so their consoles are
+/// visible. Defaults from `AUI_DEBUG_SHOW_WINDOWS=1` at startup.
+static DEBUG_SHOW_WINDOWS: AtomicBool = AtomicBool::new(false);
+
+/// Builds a command for an internal helper process. On Windows its console
+/// window is hidden with CREATE_NO_WINDOW, unless this is a debug build with
+/// `DEBUG_SHOW_WINDOWS` enabled. Release builds always hide it.
+fn hidden_command(program: &str) -> StdCommand {
+    #[allow(unused_mut)]
+    let mut cmd = StdCommand::new(program);
+    #[cfg(target_os = "windows")]
+    if !(cfg!(debug_assertions) && DEBUG_SHOW_WINDOWS.load(Ordering::Relaxed)) {
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    cmd
+}
+
+/// Toggles visible consoles for internal helper processes (debug builds
+/// only). Returns the new setting.
 #[tauri::command]
-fn create_scheduled_task(
-    task_name: String,
+fn set_debug_show_windows(debug_show_windows: bool) -> Result<bool, String> {
+    if !cfg!(debug_assertions) {
+        return Err("Unsupported: debug_show_windows is only available in debug builds".into());
+    }
+    DEBUG_SHOW_WINDOWS.store(debug_show_windows, Ordering::Relaxed);
+    Ok(debug_show_windows)
+}
+
+/// How many times a crontab edit is retried when another process changes the
+/// crontab underneath us before giving up with a `Conflict` error.
+#[cfg(not(target_os = "windows"))]
+const CRONTAB_EDIT_ATTEMPTS: u32 = 3;
+
+/// Reads the current user's crontab. A missing crontab reads as empty.
+#[cfg(not(target_os = "windows"))]
+fn read_crontab() -> Result<String, String> {
+    let output = StdCommand::new("crontab")
+        .arg("-l")
+        .output()
+        .map_err(|e| format!("Failed to read crontab: {}", e))?;
+    if !output.status.success() {
+        // A user who never had a crontab just has no entries
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.contains("no crontab for") {
+            return Err(format!("Failed to read crontab: {}", stderr.trim()));
+        }
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Replaces the current user's crontab with `contents` via `crontab -`.
+#[cfg(not(target_os = "windows"))]
+fn write_crontab(contents: &str) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut child = StdCommand::new("crontab")
+        .arg("-")
+        .stdin(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to set crontab: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(contents.as_bytes())
+            .map_err(|e| format!("Failed to write crontab: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("crontab process error: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("crontab rejected the update: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn crontab_hash(contents: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(not(target_os = "windows"))]
+thread_local! {
+    /// While `preview_crontab_change` runs on this thread, the crontab as
+    /// the previewed operation would leave it. Edits land here instead of in
+    /// the real crontab and `at`/`atrm` aren't invoked.
+    static CRONTAB_PREVIEW: std::cell::RefCell<Option<String>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Whether scheduling changes are only being previewed on this thread.
+#[cfg(not(target_os = "windows"))]
+fn previewing_crontab() -> bool {
+    CRONTAB_PREVIEW.with(|p| p.borrow().is_some())
+}
+
+/// Applies `edit` to the current crontab with optimistic concurrency.
+/// The crontab is re-read right before writing; if it no longer matches what
+/// `edit` saw, the edit is retried with backoff so a concurrent change made by
+/// another process is never silently overwritten.
+#[cfg(not(target_os = "windows"))]
+fn modify_crontab<F>(edit: F) -> Result<String, String>
+where
+    F: Fn(&str) -> String,
+{
+    let previewed = CRONTAB_PREVIEW.with(|p| {
+        let mut preview = p.borrow_mut();
+        let updated = edit(preview.as_deref()?);
+        *preview = Some(updated.clone());
+        Some(updated)
+    });
+    if let Some(updated) = previewed {
+        return Ok(updated);
+    }
+
+    modify_crontab_with(edit, read_crontab, write_crontab)
+}
+
+/// The retry loop of `modify_crontab`, reading and writing the crontab
+/// through `read` and `write`.
+#[cfg(not(target_os = "windows"))]
+fn modify_crontab_with<F, R, W>(edit: F, mut read: R, mut write: W) -> Result<String, String>
+where
+    F: Fn(&str) -> String,
+    R: FnMut() -> Result<String, String>,
+    W: FnMut(&str) -> Result<(), String>,
+{
+    for attempt in 0..CRONTAB_EDIT_ATTEMPTS {
+        let before = read()?;
+        let updated = edit(&before);
+
+        if crontab_hash(&read()?) == crontab_hash(&before) {
+            write(&updated)?;
+            return Ok(updated);
+        }
+
+        if attempt + 1 < CRONTAB_EDIT_ATTEMPTS {
+            std::thread::sleep(std::time::Duration::from_millis(100 << attempt));
+        }
+    }
+
+    Err(
+        "Conflict: the crontab was modified by another process while AUI was editing it. \
+         Please try again."
+            .into(),
+    )
+}
+
+/// Ensures non-empty crontab text ends with a newline so appended entries
+/// don't get glued onto the previous line.
+#[cfg(not(target_os = "windows"))]
+fn with_trailing_newline(contents: &str) -> String {
+    if contents.is_empty() || contents.ends_with('\n') {
+        contents.to_string()
+    } else {
+        format!("{}\n", contents)
+    }
+}
+
+/// Result of creating a scheduled task. `schedule_spec` is the cron expression
+/// or the schtasks schedule flags, `raw_command` is the exact crontab line or
+/// schtasks invocation that was applied, and `script_path` is the script path
+/// after `~`/environment variable expansion. `timezone` is the zone its
+/// times are interpreted in (see `get_schedule_timezone`).
+#[derive(serde::Serialize)]
+struct CreateTaskResult {
+    message: String,
+    schedule_spec: String,
+    raw_command: String,
     script_path: String,
-    start_time: String,
-    start_date: String,
-    repeat: String,
-) -> Result<String, String> {
+    timezone: ScheduleTimezone,
+}
+
+/// The timezone the platform scheduler interprets task times in. `name` is
+/// the IANA zone (e.g. "Europe/Berlin") on macOS/Linux or the Windows zone
+/// name, "local" when it can't be determined; `utc_offset` is its current
+/// offset as "+02:00".
+#[derive(serde::Serialize, Clone)]
+struct ScheduleTimezone {
+    name: String,
+    utc_offset: String,
+}
+
+/// Both cron and Task Scheduler fire at wall-clock times in the system
+/// timezone, so that is what a "daily at 09:00" task follows, whatever `TZ`
+/// AUI itself was started with.
+fn schedule_timezone() -> ScheduleTimezone {
+    #[cfg(target_os = "windows")]
+    let (name, offset) = {
+        let name = hidden_command("tzutil.exe")
+            .arg("/g")
+            .output()
+            .ok()
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .filter(|n| !n.is_empty());
+        (name, None)
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let (name, offset) = {
+        // /etc/localtime links into the zoneinfo database on Linux and
+        // macOS; Debian also keeps the name in /etc/timezone
+        let name = std::fs::read_link("/etc/localtime")
+            .ok()
+            .and_then(|target| {
+                let target = target.to_string_lossy().to_string();
+                target
+                    .split_once("zoneinfo/")
+                    .map(|(_, zone)| zone.to_string())
+            })
+            .or_else(|| {
+                std::fs::read_to_string("/etc/timezone")
+                    .ok()
+                    .map(|n| n.trim().to_string())
+            })
+            .filter(|n| !n.is_empty());
+        // The system offset, not the one a TZ override gives AUI
+        let offset = StdCommand::new("date")
+            .env_remove("TZ")
+            .arg("+%z")
+            .output()
+            .ok()
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .filter(|z| z.len() == 5 && z.starts_with(['+', '-']))
+            .map(|z| format!("{}:{}", &z[..3], &z[3..]));
+        (name, offset)
+    };
+
+    ScheduleTimezone {
+        name: name.unwrap_or_else(|| "local".into()),
+        utc_offset: offset.unwrap_or_else(|| chrono::Local::now().offset().to_string()),
+    }
+}
+
+/// The timezone scheduled task times are interpreted in, so a "09:00" task
+/// can be shown as local rather than UTC time.
+#[tauri::command]
+fn get_schedule_timezone() -> ScheduleTimezone {
+    schedule_timezone()
+}
+
+/// Joins command-line arguments for display, quoting any that contain spaces.
+#[cfg(target_os = "windows")]
+fn display_command(program: &str, args: &[String]) -> String {
+    let mut parts = vec![program.to_string()];
+    for arg in args {
+        if arg.contains(' ') || arg.is_empty() {
+            parts.push(format!("\"{}\"", arg.replace('"', "\\\"")));
+        } else {
+            parts.push(arg.clone());
+        }
+    }
+    parts.join(" ")
+}
+
+/// Expands a leading `~` plus `$VAR`/`${VAR}` (and `%VAR%` on Windows) in a
+/// user-supplied path, maps Windows drive paths under WSL, then
+/// canonicalizes it when it exists. Unknown
+/// variables are left as typed so the error message shows them.
+fn expand_user_path(path: &str) -> String {
+    let path = path.trim();
+    let home = std::env::var(if cfg!(target_os = "windows") {
+        "USERPROFILE"
+    } else {
+        "HOME"
+    });
+
+    let mut expanded = match (path.strip_prefix('~'), &home) {
+        (Some(rest), Ok(home))
+            if rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\') =>
+        {
+            format!("{}{}", home, rest)
+        }
+        _ => path.to_string(),
+    };
+
+    expanded = expand_env_vars(&expanded, '$');
+    if cfg!(target_os = "windows") {
+        expanded = expand_env_vars(&expanded, '%');
+    }
+    if is_wsl() {
+        if let Some(translated) = windows_path_to_wsl(&expanded) {
+            expanded = translated;
+        }
+    }
+
+    match std::fs::canonicalize(&expanded) {
+        // Strip the verbatim prefix Windows adds; schtasks and cmd reject it
+        Ok(canonical) => {
+            let canonical = canonical.to_string_lossy().to_string();
+            canonical
+                .strip_prefix(r"\\?\")
+                .map(|p| p.to_string())
+                .unwrap_or(canonical)
+        }
+        Err(_) => expanded,
+    }
+}
+
+/// Replaces `$VAR`/`${VAR}` (sigil `$`) or `%VAR%` (sigil `%`) references
+/// with their environment values.
+fn expand_env_vars(input: &str, sigil: char) -> String {
+    let mut out = String::new();
+    let mut rest = input;
+
+    while let Some(pos) = rest.find(sigil) {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        let (name, consumed) = if sigil == '%' {
+            match after.find('%') {
+                Some(end) => (&after[..end], end + 1),
+                None => ("", 0),
+            }
+        } else if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+
+        match std::env::var(name).ok().filter(|_| !name.is_empty()) {
+            Some(value) => {
+                out.push_str(&value);
+                rest = &after[consumed..];
+            }
+            None => {
+                out.push(sigil);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Whether this Linux process runs under the Windows Subsystem for Linux,
+/// which reports a Microsoft kernel in `/proc/version`.
+fn is_wsl() -> bool {
+    static IS_WSL: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *IS_WSL.get_or_init(|| {
+        cfg!(target_os = "linux")
+            && std::fs::read_to_string("/proc/version")
+                .is_ok_and(|v| v.to_lowercase().contains("microsoft"))
+    })
+}
+
+/// Maps a Windows drive path (`C:\Users\me` or `C:/Users/me`) to its WSL
+/// mount (`/mnt/c/Users/me`).
+fn windows_path_to_wsl(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let is_drive_path = bytes.len() >= 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes.len() == 2 || matches!(bytes[2], b'\\' | b'/'));
+    is_drive_path.then(|| {
+        format!(
+            "/mnt/{}{}",
+            path[..1].to_ascii_lowercase(),
+            path[2..].replace('\\', "/")
+        )
+    })
+}
+
+#[derive(serde::Serialize)]
+struct RuntimeEnvironment {
+    os: String,
+    is_wsl: bool,
+    wsl_distro: Option<String>,
+}
+
+/// Reports whether AUI runs natively or inside WSL, where scheduling goes
+/// through the distro's cron and Windows paths are reached under `/mnt`.
+#[tauri::command]
+fn detect_runtime_environment() -> RuntimeEnvironment {
+    let is_wsl = is_wsl();
+    RuntimeEnvironment {
+        os: std::env::consts::OS.to_string(),
+        is_wsl,
+        wsl_distro: std::env::var("WSL_DISTRO_NAME")
+            .ok()
+            .filter(|d| is_wsl && !d.is_empty()),
+    }
+}
+
+/// Expands `~` and environment variables in a path and canonicalizes it, so
+/// the UI can show what a typed path resolves to.
+#[tauri::command]
+fn expand_path(path: String) -> Result<String, String> {
+    if path.trim().is_empty() {
+        return Err("Path is empty".into());
+    }
+    Ok(expand_user_path(&path))
+}
+
+/// Permission bits of a file. `mode` is the Unix octal mode (e.g. "0755");
+/// on Windows it is absent and `message` explains why.
+#[derive(serde::Serialize)]
+struct FilePermissions {
+    path: String,
+    mode: Option<String>,
+    readonly: bool,
+    message: Option<String>,
+}
+
+/// Parses an octal mode such as "755", "0755" or "0o755".
+#[cfg(not(target_os = "windows"))]
+fn parse_mode(mode: &str) -> Result<u32, String> {
+    let trimmed = mode.trim();
+    let digits = trimmed.strip_prefix("0o").unwrap_or(trimmed);
+    if digits.is_empty() || digits.len() > 4 || !digits.chars().all(|c| ('0'..='7').contains(&c)) {
+        return Err(format!(
+            "Invalid mode '{}': expected octal such as 755",
+            mode
+        ));
+    }
+    u32::from_str_radix(digits, 8).map_err(|e| format!("Invalid mode '{}': {}", mode, e))
+}
+
+fn read_permissions(path: &str) -> Result<FilePermissions, String> {
+    let metadata =
+        std::fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    #[cfg(not(target_os = "windows"))]
+    let (mode, message) = {
+        use std::os::unix::fs::PermissionsExt;
+        (
+            Some(format!("{:04o}", metadata.permissions().mode() & 0o7777)),
+            None,
+        )
+    };
     #[cfg(target_os = "windows")]
+    let (mode, message) = (
+        None,
+        Some("Unix permission modes are not used on Windows".to_string()),
+    );
+
+    Ok(FilePermissions {
+        path: path.to_string(),
+        mode,
+        readonly: metadata.permissions().readonly(),
+        message,
+    })
+}
+
+/// Returns the permission mode of a file, e.g. to check a deploy script is
+/// executable.
+#[tauri::command]
+fn get_permissions(path: String) -> Result<FilePermissions, String> {
+    read_permissions(&expand_user_path(&path))
+}
+
+/// Applies a Unix octal mode to a file. On Windows this changes nothing and
+/// says so in `message`.
+#[tauri::command]
+fn set_permissions(path: String, mode: String) -> Result<FilePermissions, String> {
+    let path = expand_user_path(&path);
+
+    #[cfg(not(target_os = "windows"))]
     {
-        let tn = format!("AUI\\{}", task_name);
-        let tr = format!(
-            "powershell.exe -ExecutionPolicy Bypass -File \"{}\"",
-            script_path
-        );
+        use std::os::unix::fs::PermissionsExt;
+        let mode = parse_mode(&mode)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))
+            .map_err(|e| format!("Failed to set permissions on {}: {}", path, e))?;
+    }
+    #[cfg(target_os = "windows")]
+    let _ = mode;
 
-        // Map repeat type to schtasks /SC value
-        let sc = match repeat.as_str() {
-            "hourly" => "HOURLY",
-            "daily" => "DAILY",
-            "weekly" => "WEEKLY",
-            "monthly" => "MONTHLY",
-            _ => "ONCE",
+    read_permissions(&path)
+}
+
+/// Line endings `update_script` writes: `"auto"` (the default), `"lf"` or
+/// `"crlf"`.
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+enum LineEnding {
+    #[default]
+    Auto,
+    Lf,
+    Crlf,
+}
+
+/// Converts `contents` to `ending`. `Auto` picks by script type: LF for
+/// anything with a shebang or a Unix shell/interpreter extension (even on
+/// Windows, where bash scripts usually run under WSL), CRLF for `.cmd` and
+/// `.bat`, and the platform's native ending otherwise.
+fn normalize_line_endings(path: &str, contents: &str, ending: LineEnding) -> String {
+    let crlf = match ending {
+        LineEnding::Lf => false,
+        LineEnding::Crlf => true,
+        LineEnding::Auto => {
+            let ext = std::path::Path::new(path)
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if contents.trim_start_matches('\u{feff}').starts_with("#!")
+                || matches!(
+                    ext.as_str(),
+                    "sh" | "bash" | "zsh" | "dash" | "py" | "rb" | "pl" | "js" | "mjs"
+                )
+            {
+                false
+            } else {
+                matches!(ext.as_str(), "cmd" | "bat") || cfg!(target_os = "windows")
+            }
+        }
+    };
+    let lf = contents.replace("\r\n", "\n");
+    if crlf {
+        lf.replace('\n', "\r\n")
+    } else {
+        lf
+    }
+}
+
+/// Where `scripts_dir` is, without creating it.
+fn scripts_dir_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))?
+        .join("scripts"))
+}
+
+/// `<app_data_dir>/scripts`, created if needed and restricted to the
+/// current user (see `private_dir`).
+fn scripts_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    private_dir(scripts_dir_path(app)?)
+}
+
+/// Where `wrapper_dir` is, without creating it.
+fn wrapper_dir_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))?
+        .join("schedules"))
+}
+
+/// `<app_data_dir>/schedules`, where every wrapper script AUI generates
+/// for a scheduled task goes, created if needed and restricted to the
+/// current user (see `private_dir`), as wrappers may carry secrets.
+fn wrapper_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    private_dir(wrapper_dir_path(app)?)
+}
+
+/// Creates `dir` if needed and restricts it to the current user: mode 0700
+/// on Unix, and on Windows an ACL granting only the user and SYSTEM (best
+/// effort, since `icacls` may be unavailable). A symlink in its place is
+/// refused rather than followed.
+fn private_dir(dir: std::path::PathBuf) -> Result<std::path::PathBuf, String> {
+    if std::fs::symlink_metadata(&dir).is_ok_and(|m| !m.is_dir()) {
+        return Err(format!("{} exists but is not a directory", dir.display()));
+    }
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to restrict {}: {}", dir.display(), e))?;
+    }
+    #[cfg(target_os = "windows")]
+    if let Ok(user) = std::env::var("USERNAME") {
+        let user = match std::env::var("USERDOMAIN") {
+            Ok(domain) => format!("{}\\{}", domain, user),
+            Err(_) => user,
         };
+        let _ = hidden_command("icacls.exe")
+            .arg(&dir)
+            .args(["/inheritance:r", "/grant:r"])
+            .arg(format!("{}:(OI)(CI)F", user))
+            .args(["/grant:r", "*S-1-5-18:(OI)(CI)F", "/Q"])
+            .output();
+    }
+    Ok(dir)
+}
 
-        let mut args = vec![
-            "/Create".to_string(),
-            "/TN".to_string(),
-            tn.clone(),
-            "/TR".to_string(),
-            tr,
-            "/SC".to_string(),
-            sc.to_string(),
-            "/ST".to_string(),
-            start_time.clone(),
-            "/F".to_string(), // Force overwrite if exists
-        ];
+/// Creates (or re-restricts) the directory AUI keeps scripts in and returns
+/// its path (see `scripts_dir`). Relative script paths given to
+/// `update_script` and `run_script` are resolved against it.
+#[tauri::command]
+fn ensure_scripts_dir(app: tauri::AppHandle) -> Result<String, String> {
+    scripts_dir(&app).map(|dir| dir.to_string_lossy().to_string())
+}
 
-        // Add start date for non-hourly schedules
-        if sc != "HOURLY" && !start_date.is_empty() {
-            args.push("/SD".to_string());
-            args.push(start_date.clone());
+/// A script path after `~`/environment variable expansion, with relative
+/// paths taken to be inside the scripts directory.
+fn resolve_script_path(app: &tauri::AppHandle, path: &str) -> Result<String, String> {
+    let path = expand_user_path(path);
+    if std::path::Path::new(&path).is_absolute() {
+        return Ok(path);
+    }
+    Ok(scripts_dir(app)?.join(path).to_string_lossy().to_string())
+}
+
+/// How many backups `update_script` keeps: `.bak`, `.bak.1` and `.bak.2`.
+const SCRIPT_BACKUPS: usize = 3;
+
+/// Replaces a script's contents, first copying the current version to
+/// `<path>.bak` (older backups shift to `.bak.1`, `.bak.2`, and the oldest
+/// is dropped). The new contents go to a temporary file next to the script
+/// that is renamed over it, so the script is never half-written, and the
+/// old file's permissions carry over. Line endings are converted to
+/// `line_ending` (see `normalize_line_endings`) so a script edited on one
+/// platform doesn't fail with "^M: bad interpreter" on another. Returns the
+/// backup path, or `None` when there was no previous version. A relative
+/// `path` is inside the scripts directory (see `ensure_scripts_dir`).
+#[tauri::command]
+fn update_script(
+    app: tauri::AppHandle,
+    path: String,
+    contents: String,
+    line_ending: Option<LineEnding>,
+) -> Result<Option<String>, String> {
+    let path = resolve_script_path(&app, &path)?;
+    let contents = normalize_line_endings(&path, &contents, line_ending.unwrap_or_default());
+    let backup = |n: usize| match n {
+        0 => format!("{}.bak", path),
+        n => format!("{}.bak.{}", path, n),
+    };
+
+    let existing = std::fs::metadata(&path).ok();
+    if existing.as_ref().is_some_and(|m| m.is_dir()) {
+        return Err(format!("{} is a directory", path));
+    }
+    let backup_path = match &existing {
+        Some(_) => {
+            let _ = std::fs::remove_file(backup(SCRIPT_BACKUPS - 1));
+            for n in (0..SCRIPT_BACKUPS - 1).rev() {
+                if std::path::Path::new(&backup(n)).exists() {
+                    std::fs::rename(backup(n), backup(n + 1))
+                        .map_err(|e| format!("Failed to rotate {}: {}", backup(n), e))?;
+                }
+            }
+            std::fs::copy(&path, backup(0))
+                .map_err(|e| format!("Failed to back up {}: {}", path, e))?;
+            Some(backup(0))
+        }
+        None => None,
+    };
+
+    let staged = format!("{}.tmp-{}", path, std::process::id());
+    std::fs::write(&staged, &contents).map_err(|e| format!("Failed to write {}: {}", staged, e))?;
+    if let Some(metadata) = &existing {
+        let _ = std::fs::set_permissions(&staged, metadata.permissions());
+    }
+    if let Err(e) = std::fs::rename(&staged, &path) {
+        let _ = std::fs::remove_file(&staged);
+        return Err(format!("Failed to replace {}: {}", path, e));
+    }
+    Ok(backup_path)
+}
+
+/// How a script is run: the interpreter program with its leading arguments,
+/// and whether that came from the script's `shebang`, its `extension`, or
+/// the platform `default`.
+#[derive(serde::Serialize, Clone)]
+struct Interpreter {
+    program: String,
+    args: Vec<String>,
+    source: String,
+}
+
+fn interpreter_for(script_path: &str) -> Interpreter {
+    let make = |program: &str, args: &[&str], source: &str| Interpreter {
+        program: program.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+        source: source.to_string(),
+    };
+    let windows = cfg!(target_os = "windows");
+
+    // A shebang wins over the extension
+    let first_line = std::fs::File::open(script_path).ok().and_then(|f| {
+        use std::io::{BufRead, Read};
+        let mut line = String::new();
+        std::io::BufReader::new(f)
+            .take(512)
+            .read_line(&mut line)
+            .ok()?;
+        Some(line)
+    });
+    if let Some(shebang) = first_line
+        .as_deref()
+        .map(|l| l.trim_start_matches('\u{feff}'))
+        .and_then(|l| l.strip_prefix("#!"))
+    {
+        let mut words: Vec<&str> = shebang.split_whitespace().collect();
+        if !words.is_empty() {
+            // Windows has no /usr/bin/env or /bin; run the named program from PATH
+            if windows {
+                if words[0].ends_with("/env") && words.len() > 1 {
+                    words.remove(0);
+                }
+                words[0] = words[0].rsplit('/').next().unwrap_or(words[0]);
+            }
+            return make(words[0], &words[1..], "shebang");
         }
+    }
+
+    let extension = std::path::Path::new(script_path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let by_extension = match extension.as_str() {
+        "ps1" if windows => Some(make("powershell.exe", &[], "extension")),
+        "ps1" => Some(make("pwsh", &["-File"], "extension")),
+        "bat" | "cmd" if windows => Some(make("cmd.exe", &["/c"], "extension")),
+        "py" if windows => Some(make("python", &[], "extension")),
+        "py" => Some(make("python3", &[], "extension")),
+        "sh" | "bash" if windows => Some(make("bash", &[], "extension")),
+        "sh" | "bash" => Some(make("/bin/bash", &[], "extension")),
+        "zsh" => Some(make("zsh", &[], "extension")),
+        "fish" => Some(make("fish", &[], "extension")),
+        "js" | "mjs" | "cjs" => Some(make("node", &[], "extension")),
+        "rb" => Some(make("ruby", &[], "extension")),
+        "pl" => Some(make("perl", &[], "extension")),
+        "php" => Some(make("php", &[], "extension")),
+        _ => None,
+    };
+
+    by_extension.unwrap_or_else(|| {
+        if windows {
+            make("powershell.exe", &[], "default")
+        } else {
+            make("/bin/bash", &[], "default")
+        }
+    })
+}
+
+/// Resolves the interpreter a script will run under, from its shebang line
+/// or file extension, falling back to PowerShell on Windows and bash
+/// elsewhere.
+#[tauri::command]
+fn detect_interpreter(script_path: String) -> Result<Interpreter, String> {
+    let script_path = expand_user_path(&script_path);
+    if !std::path::Path::new(&script_path).is_file() {
+        return Err(format!("Script not found: {}", script_path));
+    }
+    Ok(interpreter_for(&script_path))
+}
+
+/// The argv that runs `script_path` on macOS/Linux: under `shell` when one
+/// was chosen explicitly, otherwise under its detected interpreter.
+#[cfg(not(target_os = "windows"))]
+fn unix_script_argv(script_path: &str, shell: Option<&str>) -> Vec<String> {
+    let mut argv = match shell {
+        Some(shell) => vec![shell.to_string()],
+        None => {
+            let interpreter = interpreter_for(script_path);
+            std::iter::once(interpreter.program)
+                .chain(interpreter.args)
+                .collect()
+        }
+    };
+    argv.push(script_path.to_string());
+    argv
+}
+
+/// `unix_script_argv` as a shell command line, with the script path quoted.
+#[cfg(not(target_os = "windows"))]
+fn unix_script_command(script_path: &str, shell: Option<&str>) -> String {
+    let argv = unix_script_argv(script_path, shell);
+    let (path, program) = argv
+        .split_last()
+        .expect("argv always holds the script path");
+    let mut parts: Vec<String> = program
+        .iter()
+        .map(|arg| {
+            let plain = arg.chars().all(|c| {
+                c.is_ascii_alphanumeric() || matches!(c, '/' | '.' | '_' | '-' | '=' | ':')
+            });
+            if plain {
+                arg.clone()
+            } else {
+                shell_quote(arg)
+            }
+        })
+        .collect();
+    parts.push(shell_quote(path));
+    parts.join(" ")
+}
+
+/// Single-quotes a value for a POSIX shell command line.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Parses a time of day into 24-hour (hour, minute): either 24-hour
+/// `HH:MM`, or 12-hour with an AM/PM suffix (`9:00 PM`, `9pm`, `12:30 a.m.`),
+/// which is normalized to the 24-hour form schtasks and cron need. A 12-hour
+/// hour outside 1-12 (`13:00 PM`, `0:15 AM`) is rejected as ambiguous.
+fn parse_time(time: &str) -> Result<(u32, u32), String> {
+    let lower = time.trim().to_lowercase().replace('.', "");
+    let (clock, meridiem) = match lower
+        .strip_suffix("am")
+        .map(|c| (c, false))
+        .or_else(|| lower.strip_suffix("pm").map(|c| (c, true)))
+    {
+        Some((clock, pm)) => (clock.trim_end(), Some(pm)),
+        None => (lower.as_str(), None),
+    };
+    let (hour, minute) = match (clock.split_once(':'), meridiem) {
+        (Some(parts), _) => parts,
+        // "9 PM" has no minutes
+        (None, Some(_)) => (clock, "00"),
+        (None, None) => {
+            return Err(format!(
+                "Invalid time '{}': expected HH:MM or H:MM AM/PM",
+                time
+            ))
+        }
+    };
+    let hour: u32 = hour
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid hour in time '{}'", time))?;
+    let minute: u32 = minute
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid minute in time '{}'", time))?;
+    if minute > 59 {
+        return Err(format!("Time '{}' is out of range", time));
+    }
+    let hour = match meridiem {
+        None if hour > 23 => return Err(format!("Time '{}' is out of range", time)),
+        None => hour,
+        Some(_) if !(1..=12).contains(&hour) => {
+            return Err(format!(
+                "Ambiguous time '{}': a 12-hour time needs an hour from 1 to 12",
+                time
+            ))
+        }
+        Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+    };
+    Ok((hour, minute))
+}
+
+/// Parses a full local datetime for one-shot tasks. Accepts ISO-style
+/// `YYYY-MM-DD HH:MM` / `YYYY-MM-DDTHH:MM[:SS]` and the `MM/DD/YYYY HH:MM`
+/// form the scheduler UI uses for schtasks start dates.
+fn parse_run_at(run_at: &str) -> Result<chrono::NaiveDateTime, String> {
+    const FORMATS: [&str; 5] = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%m/%d/%Y %H:%M",
+    ];
+    FORMATS
+        .iter()
+        .find_map(|f| chrono::NaiveDateTime::parse_from_str(run_at.trim(), f).ok())
+        .ok_or_else(|| {
+            format!(
+                "Invalid datetime '{}': expected YYYY-MM-DD HH:MM or MM/DD/YYYY HH:MM",
+                run_at
+            )
+        })
+}
+
+/// Resolves a naive local datetime and checks that it lies in the future.
+fn future_local_datetime(
+    run_at: chrono::NaiveDateTime,
+) -> Result<chrono::DateTime<chrono::Local>, String> {
+    use chrono::TimeZone;
+
+    let local = chrono::Local
+        .from_local_datetime(&run_at)
+        .earliest()
+        .ok_or_else(|| format!("{} does not exist in the local timezone", run_at))?;
+    if local <= chrono::Local::now() {
+        return Err(format!("Run time {} is in the past", run_at));
+    }
+    Ok(local)
+}
+
+/// Looks up an executable on PATH, honoring PATHEXT on Windows.
+fn find_in_path(name: &str) -> Option<std::path::PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    let extensions: Vec<String> = if cfg!(target_os = "windows") {
+        let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| ".EXE;.CMD;.BAT".into());
+        std::iter::once(String::new())
+            .chain(pathext.split(';').map(|e| e.to_string()))
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+
+    std::env::split_paths(&path).find_map(|dir| {
+        extensions.iter().find_map(|ext| {
+            let candidate = dir.join(format!("{}{}", name, ext));
+            candidate.is_file().then_some(candidate)
+        })
+    })
+}
+
+/// Finds a PowerShell to run scripts with: Windows PowerShell first for
+/// compatibility, then PowerShell 7 (`pwsh`).
+#[cfg(target_os = "windows")]
+fn resolve_powershell() -> Result<String, String> {
+    ["powershell.exe", "pwsh.exe"]
+        .iter()
+        .find(|name| find_in_path(name).is_some())
+        .map(|name| name.to_string())
+        .ok_or_else(|| {
+            "DependencyMissing: neither powershell nor pwsh was found on PATH. \
+             Install PowerShell, or use a .bat/.cmd script instead."
+                .to_string()
+        })
+}
+
+/// Builds the command line that runs `script_path` on Windows from its
+/// detected interpreter: batch files go through cmd.exe, PowerShell scripts
+/// (and anything unrecognized) through PowerShell, and others such as `.py`
+/// through their interpreter. `interactive` keeps the console open after the
+/// script finishes.
+#[cfg(target_os = "windows")]
+fn windows_script_command(script_path: &str, interactive: bool) -> Result<String, String> {
+    let interpreter = interpreter_for(script_path);
+    let program = std::path::Path::new(&interpreter.program)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match program.as_str() {
+        "cmd" => {
+            let flag = if interactive { "/k" } else { "/c" };
+            Ok(format!("cmd.exe {} \"{}\"", flag, script_path))
+        }
+        "powershell" | "pwsh" => {
+            let powershell = resolve_powershell()?;
+            let no_exit = if interactive { " -NoExit" } else { "" };
+            Ok(format!(
+                "{}{} -ExecutionPolicy Bypass -File \"{}\"",
+                powershell, no_exit, script_path
+            ))
+        }
+        _ => {
+            let mut parts = vec![format!("\"{}\"", interpreter.program)];
+            parts.extend(interpreter.args.iter().cloned());
+            parts.push(format!("\"{}\"", script_path));
+            let command = parts.join(" ");
+            Ok(if interactive {
+                format!("cmd.exe /k {}", command)
+            } else {
+                command
+            })
+        }
+    }
+}
+
+/// Set once `ensure_task_folder` has seen the `\AUI` folder this session.
+#[cfg(target_os = "windows")]
+static TASK_FOLDER_READY: AtomicBool = AtomicBool::new(false);
+
+/// Runs a PowerShell snippet against the Task Scheduler COM API with the
+/// service connected as `$s`, returning its trimmed output.
+#[cfg(target_os = "windows")]
+fn task_service_script(body: &str) -> Result<String, String> {
+    let output = hidden_command(&resolve_powershell()?)
+        .args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "$s = New-Object -ComObject Schedule.Service; $s.Connect(); {}",
+                body
+            ),
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run PowerShell: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Task Scheduler query failed: {}", stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// How many tasks the `\AUI` task folder holds, hidden ones included: 0
+/// when it doesn't exist.
+#[cfg(target_os = "windows")]
+fn task_folder_task_count() -> Result<usize, String> {
+    task_service_script("try { $s.GetFolder('\\AUI').GetTasks(1).Count } catch { 0 }")?
+        .parse()
+        .map_err(|_| "Task Scheduler query failed: unexpected task count".to_string())
+}
+
+/// Creates the `\AUI` Task Scheduler folder if it doesn't exist yet, so
+/// listing it works before the first task is created. A no-op elsewhere.
+#[tauri::command]
+fn ensure_task_folder() -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if TASK_FOLDER_READY.load(Ordering::Relaxed) {
+            return Ok("Task folder \\AUI exists".into());
+        }
+        let outcome = task_service_script(
+            "try { [void]$s.GetFolder('\\AUI'); 'exists' } \
+             catch { [void]$s.GetFolder('\\').CreateFolder('AUI'); 'created' }",
+        )?;
+        TASK_FOLDER_READY.store(true, Ordering::Relaxed);
+        Ok(if outcome == "created" {
+            "Created task folder \\AUI".into()
+        } else {
+            "Task folder \\AUI exists".into()
+        })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    Ok("Nothing to do: tasks are kept in the crontab".into())
+}
+
+/// Queries the tasks in the `\AUI` folder as verbose CSV rows. A folder
+/// that is empty or doesn't exist yet lists as empty.
+#[cfg(target_os = "windows")]
+fn query_task_folder() -> Result<String, String> {
+    let output = hidden_command("schtasks.exe")
+        .args(["/Query", "/FO", "CSV", "/NH", "/V", "/TN", "AUI\\*"])
+        .output()
+        .map_err(|e| format!("Failed to query schtasks: {}", e))?;
+    task_folder_listing(
+        output.status.success(),
+        &decode_schtasks_output(&output.stdout),
+        &decode_schtasks_output(&output.stderr),
+        task_folder_task_count,
+    )
+}
+
+/// The listing a `schtasks /Query` of the `\AUI` folder amounts to. schtasks
+/// fails when nothing matches, whether the folder is missing or just empty;
+/// only a folder that does hold tasks (as `folder_task_count` tells) means
+/// a real error.
+#[cfg(target_os = "windows")]
+fn task_folder_listing(
+    success: bool,
+    stdout: &str,
+    stderr: &str,
+    folder_task_count: impl FnOnce() -> Result<usize, String>,
+) -> Result<String, String> {
+    if success {
+        return Ok(stdout.to_string());
+    }
+    if folder_task_count()? == 0 {
+        return Ok(String::new());
+    }
+    Err(format!("Failed to query schtasks: {}", stderr.trim()))
+}
+
+/// Checks a task name on every platform before it is used: it ends up in
+/// file names (`task_log_file`, `task_metadata_path`), crontab markers and
+/// email headers, so path separators, `..` and control characters (line
+/// breaks included) are refused.
+fn validate_task_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Task name is empty".into());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| matches!(c, '/' | '\\') || c.is_control())
+    {
+        return Err(format!(
+            "Task name '{}' can't contain '{}'",
+            name.escape_default(),
+            c.escape_default()
+        ));
+    }
+    if name.contains("..") {
+        return Err(format!("Task name '{}' can't contain '..'", name));
+    }
+    Ok(())
+}
+
+/// `task_name` with everything but ASCII letters, digits, `-`, `_` and `.`
+/// replaced by `-`, for the files kept per task.
+fn task_file_stem(task_name: &str) -> String {
+    task_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Longest task path Task Scheduler accepts, `AUI\` prefix included.
+const WINDOWS_TASK_PATH_MAX: usize = 238;
+
+/// Checks `name` against Task Scheduler's rules for a task under `AUI\`:
+/// none of `\/:*?"<>|` or control characters, no trailing dot or space,
+/// not a reserved device name (tasks are stored as files), and short enough
+/// that the full `AUI\<name>` path fits. Usable on any platform, so a name
+/// can be checked before a schedule is exported to a Windows machine.
+#[tauri::command]
+fn validate_windows_task_name(name: String) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Task name is empty".into());
+    }
+    if let Some(c) = name.chars().find(|c| {
+        matches!(c, '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control()
+    }) {
+        return Err(format!(
+            "Task name '{}' contains '{}', which Windows task names can't contain",
+            name,
+            c.escape_default()
+        ));
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Err(format!(
+            "Task name '{}' can't end with a dot or space on Windows",
+            name
+        ));
+    }
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    let reserved = ["CON", "PRN", "AUX", "NUL"]
+        .iter()
+        .any(|r| stem.eq_ignore_ascii_case(r))
+        || (stem.len() == 4
+            && ["COM", "LPT"]
+                .iter()
+                .any(|p| stem.get(..3).is_some_and(|s| s.eq_ignore_ascii_case(p)))
+            && matches!(stem.as_bytes()[3], b'1'..=b'9'));
+    if reserved {
+        return Err(format!(
+            "Task name '{}' is a reserved device name on Windows",
+            name
+        ));
+    }
+    let path_len = format!("AUI\\{}", name).chars().count();
+    if path_len > WINDOWS_TASK_PATH_MAX {
+        return Err(format!(
+            "Task name '{}' is too long: AUI\\<name> may be at most {} characters, this is {}",
+            name, WINDOWS_TASK_PATH_MAX, path_len
+        ));
+    }
+    Ok(())
+}
+
+/// Creates (or overwrites) `AUI\<task_name>` with the given schtasks schedule
+/// flags, e.g. `["/SC", "DAILY", "/ST", "09:00"]`.
+#[cfg(target_os = "windows")]
+fn create_schtasks_task(
+    task_name: &str,
+    script_path: &str,
+    schedule: Vec<String>,
+) -> Result<CreateTaskResult, String> {
+    validate_windows_task_name(task_name.to_string())?;
+    // Not fatal: schtasks also creates the folder along with the task
+    let _ = ensure_task_folder();
+    let tn = format!("AUI\\{}", task_name);
+    let tr = windows_script_command(script_path, false)?;
+
+    let mut args = vec![
+        "/Create".to_string(),
+        "/TN".to_string(),
+        tn.clone(),
+        "/TR".to_string(),
+        tr,
+    ];
+    args.extend(schedule.iter().cloned());
+    args.push("/F".to_string()); // Force overwrite if exists
+
+    let output = hidden_command("schtasks.exe")
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to run schtasks: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("schtasks failed: {}", stderr));
+    }
+
+    Ok(CreateTaskResult {
+        message: format!("Created scheduled task: {}", tn),
+        schedule_spec: schedule.join(" "),
+        raw_command: display_command("schtasks.exe", &args),
+        script_path: script_path.to_string(),
+        timezone: schedule_timezone(),
+    })
+}
+
+/// Appends `<cron_line> <command> # AUI:<task_name>` to the crontab.
+#[cfg(not(target_os = "windows"))]
+fn install_cron_entry(
+    task_name: &str,
+    script_path: &str,
+    cron_line: &str,
+    command: &str,
+) -> Result<CreateTaskResult, String> {
+    install_cron_entries(task_name, script_path, &[cron_line.to_string()], command)
+}
+
+/// Appends one line per schedule in `cron_lines`, all running `command`
+/// under the same `# AUI:<task_name>` marker, in a single crontab edit.
+#[cfg(not(target_os = "windows"))]
+fn install_cron_entries(
+    task_name: &str,
+    script_path: &str,
+    cron_lines: &[String],
+    command: &str,
+) -> Result<CreateTaskResult, String> {
+    let entries: String = cron_lines
+        .iter()
+        .map(|cron_line| format!("{} {} # AUI:{}\n", cron_line, command, task_name))
+        .collect();
+
+    modify_crontab(|existing| format!("{}{}", with_trailing_newline(existing), entries))?;
+
+    Ok(CreateTaskResult {
+        message: format!("Created cron job: AUI:{}", task_name),
+        schedule_spec: cron_lines.join("; "),
+        raw_command: entries.trim_end().to_string(),
+        script_path: script_path.to_string(),
+        timezone: schedule_timezone(),
+    })
+}
+
+/// What a task was created from, kept in `~/.aui/tasks/<name>.json` next to
+/// its crontab marker so listings needn't reverse-engineer the cron line.
+/// `start_times` holds every time of a multi-time task.
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+struct TaskMetadata {
+    task_name: String,
+    script_path: String,
+    spec: ScheduleSpec,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    start_times: Vec<String>,
+    #[serde(default)]
+    priority: Option<i32>,
+    created_at: String,
+}
+
+#[cfg(not(target_os = "windows"))]
+fn task_metadata_path(task_name: &str) -> Option<std::path::PathBuf> {
+    let home = std::env::var("HOME").ok().filter(|h| !h.is_empty())?;
+    Some(
+        std::path::Path::new(&home)
+            .join(".aui")
+            .join("tasks")
+            .join(format!("{}.json", task_file_stem(task_name))),
+    )
+}
+
+/// Records how `task_name` was created. Best effort: the crontab entry is
+/// what schedules the task, so a failure here only costs listing detail.
+#[cfg(not(target_os = "windows"))]
+fn save_task_metadata(
+    task_name: &str,
+    script_path: &str,
+    spec: ScheduleSpec,
+    start_times: Vec<String>,
+    priority: Option<i32>,
+) {
+    if previewing_crontab() {
+        return;
+    }
+    let Some(path) = task_metadata_path(task_name) else {
+        return;
+    };
+    let metadata = TaskMetadata {
+        task_name: task_name.to_string(),
+        script_path: script_path.to_string(),
+        spec,
+        start_times,
+        priority,
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    if let (Some(dir), Ok(json)) = (path.parent(), serde_json::to_string_pretty(&metadata)) {
+        let _ = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, json));
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn load_task_metadata(task_name: &str) -> Option<TaskMetadata> {
+    let contents = std::fs::read_to_string(task_metadata_path(task_name)?).ok()?;
+    // Names that differ only in unsafe characters share a file
+    serde_json::from_str(&contents)
+        .ok()
+        .filter(|m: &TaskMetadata| m.task_name == task_name)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn remove_task_metadata(task_name: &str) {
+    if let Some(path) = task_metadata_path(task_name) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Whether one-shot jobs can be handed to `at`. macOS ships `at` with atrun
+/// disabled, so jobs would never fire there; only Linux uses it.
+#[cfg(not(target_os = "windows"))]
+fn at_available() -> bool {
+    cfg!(target_os = "linux") && find_in_path("at").is_some()
+}
+
+/// Escapes a literal for use in a grep basic regular expression.
+fn grep_escape(value: &str) -> String {
+    value
+        .chars()
+        .flat_map(|c| match c {
+            '.' | '[' | ']' | '*' | '^' | '$' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect()
+}
+
+/// AUI one-shot jobs queued with `at`, as (job id, scheduled time, job line).
+/// Returns nothing when `at` isn't installed.
+#[cfg(not(target_os = "windows"))]
+fn aui_at_jobs() -> Vec<(String, String, String)> {
+    if !at_available() {
+        return Vec::new();
+    }
+    let queue = match StdCommand::new("atq").output() {
+        Ok(o) => String::from_utf8_lossy(&o.stdout).to_string(),
+        Err(_) => return Vec::new(),
+    };
+
+    let mut jobs = Vec::new();
+    for line in queue.lines() {
+        // atq: "<id>\t<Www Mmm dd hh:mm:ss yyyy> <queue> <user>"
+        let Some((id, rest)) = line.split_once('\t') else {
+            continue;
+        };
+        let when: Vec<&str> = rest.split_whitespace().take(5).collect();
+        let Ok(body) = StdCommand::new("at").args(["-c", id.trim()]).output() else {
+            continue;
+        };
+        let body = String::from_utf8_lossy(&body.stdout).to_string();
+        if let Some(job_line) = body.lines().find(|l| l.contains("# AUI:")) {
+            jobs.push((id.trim().to_string(), when.join(" "), job_line.to_string()));
+        }
+    }
+    jobs
+}
+
+/// Schedules `script_path` to run exactly once at `run_at` (local time).
+/// On Windows this is `/SC ONCE` with an explicit `/SD` and `/ST`. On Linux
+/// `at` is used when installed; otherwise (and on macOS) a crontab line pinned
+/// to the date is installed, running a wrapper that removes the line after
+/// firing (see `one_shot_wrapper`). A `backend` of "cron" keeps the job in
+/// the crontab even where `at` is available (see `validate_backend`).
+fn schedule_one_shot(
+    app: &tauri::AppHandle,
+    task_name: &str,
+    script_path: &str,
+    run_at: chrono::DateTime<chrono::Local>,
+    shell: Option<&str>,
+    priority: Option<i32>,
+    backend: Option<&str>,
+) -> Result<CreateTaskResult, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = (app, shell, backend);
+        let result = create_schtasks_task(
+            task_name,
+            script_path,
+            vec![
+                "/SC".to_string(),
+                "ONCE".to_string(),
+                "/SD".to_string(),
+                run_at.format("%m/%d/%Y").to_string(),
+                "/ST".to_string(),
+                run_at.format("%H:%M").to_string(),
+            ],
+        )?;
+        apply_priority(task_name, priority, result)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::io::Write;
+
+        let command = nice_command(unix_script_command(script_path, shell), priority);
+
+        if backend != Some("cron") && at_available() {
+            let when = run_at.format("%Y%m%d%H%M").to_string();
+            let job = format!("{} # AUI:{}\n", command, task_name);
+            let result = CreateTaskResult {
+                message: format!("Created one-shot at job: AUI:{}", task_name),
+                schedule_spec: format!("at -t {}", when),
+                raw_command: job.trim_end().to_string(),
+                script_path: script_path.to_string(),
+                timezone: schedule_timezone(),
+            };
+            if previewing_crontab() {
+                return Ok(result);
+            }
+
+            let mut child = StdCommand::new("at")
+                .args(["-t", &when])
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped())
+                .spawn()
+                .map_err(|e| format!("Failed to run at: {}", e))?;
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin
+                    .write_all(job.as_bytes())
+                    .map_err(|e| format!("Failed to write at job: {}", e))?;
+            }
+            let output = child
+                .wait_with_output()
+                .map_err(|e| format!("at process error: {}", e))?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!("at failed: {}", stderr.trim()));
+            }
+
+            return Ok(result);
+        }
+
+        let wrapper = one_shot_wrapper(app, task_name, script_path, shell)?;
+        let cron_line = run_at.format("%M %H %d %m *").to_string();
+        let command = format!(
+            "[ \"$(date +\\%Y)\" = \"{}\" ] && {}",
+            run_at.format("%Y"),
+            nice_command(format!("/bin/sh {}", shell_quote(&wrapper)), priority)
+        );
+        install_cron_entry(task_name, script_path, &cron_line, &command)
+    }
+}
+
+/// The second line of every `one_shot_wrapper`.
+#[cfg(not(target_os = "windows"))]
+const ONE_SHOT_WRAPPER_HEADER: &str =
+    "# AUI one-shot wrapper: runs the script once, then removes its crontab line";
+
+/// The script the `one_shot_wrapper` at `path` runs, or `None` when `path`
+/// isn't one (or is gone, as it is once the task has fired).
+#[cfg(not(target_os = "windows"))]
+fn one_shot_wrapper_script(path: &str) -> Option<String> {
+    if !path.ends_with("-once.sh") {
+        return None;
+    }
+    let contents = std::fs::read_to_string(path).ok()?;
+    let mut lines = contents.lines().skip(1);
+    if lines.next()? != ONE_SHOT_WRAPPER_HEADER {
+        return None;
+    }
+    shell_words(lines.next()?).pop()
+}
+
+/// Writes the wrapper a one-shot crontab line runs,
+/// `<app_data_dir>/schedules/<task>-once.sh`: it runs the script, then
+/// deletes the lines ending in the task's `# AUI:` marker from the crontab,
+/// along with the task's metadata and the wrapper itself. Other lines are
+/// left as they are, and the crontab is only rewritten when a line was
+/// removed, so a second run (or a line already gone) changes nothing.
+/// With `AUI_TEST_RUN` set (see `test_run_task`) it only runs the script.
+/// The command running the script follows `ONE_SHOT_WRAPPER_HEADER`, which
+/// is how `one_shot_wrapper_script` finds it again.
+#[cfg(not(target_os = "windows"))]
+fn one_shot_wrapper(
+    app: &tauri::AppHandle,
+    task_name: &str,
+    script_path: &str,
+    shell: Option<&str>,
+) -> Result<String, String> {
+    let file = task_file_stem(task_name);
+    // Previews only need the path, so nothing is created for them
+    let dir = if previewing_crontab() {
+        wrapper_dir_path(app)?
+    } else {
+        wrapper_dir(app)?
+    };
+    let wrapper = dir.join(format!("{}-once.sh", file));
+    let mut cleanup = vec!["\"$0\"".to_string()];
+    if let Some(metadata) = task_metadata_path(task_name) {
+        cleanup.insert(0, shell_quote(&metadata.to_string_lossy()));
+    }
+    let lines = [
+        "#!/bin/sh".to_string(),
+        ONE_SHOT_WRAPPER_HEADER.to_string(),
+        unix_script_command(script_path, shell),
+        "code=$?".to_string(),
+        "[ -n \"$AUI_TEST_RUN\" ] && exit $code".to_string(),
+        format!("marker={}", shell_quote(&format!("# AUI:{}", task_name))),
+        "if current=$(crontab -l 2>/dev/null); then".to_string(),
+        "  kept=$(printf '%s\\n' \"$current\" | AUI_MARKER=\"$marker\" awk '{ line = $0; sub(/[ \\t]+$/, \"\", line); m = ENVIRON[\"AUI_MARKER\"]; if (length(line) < length(m) || substr(line, length(line) - length(m) + 1) != m) print }')".to_string(),
+        "  if [ \"$kept\" != \"$current\" ]; then".to_string(),
+        "    if [ -n \"$kept\" ]; then printf '%s\\n' \"$kept\" | crontab -; else crontab -r; fi".to_string(),
+        "  fi".to_string(),
+        "fi".to_string(),
+        format!("rm -f {}", cleanup.join(" ")),
+        "exit $code".to_string(),
+    ];
+    if previewing_crontab() {
+        return Ok(wrapper.to_string_lossy().to_string());
+    }
+    write_wrapper(&wrapper, &format!("{}\n", lines.join("\n")))
+}
+
+/// The crontab schedule and command that run `command` once at `run_at`.
+/// cron has no year field, so the command itself checks the year before
+/// running, then strips its own line from the crontab. This self-contained
+/// form is for crontab text exported elsewhere; lines installed here run a
+/// `one_shot_wrapper` instead.
+fn one_shot_cron_entry(
+    task_name: &str,
+    command: &str,
+    run_at: chrono::NaiveDateTime,
+) -> (String, String) {
+    let cron_line = run_at.format("%M %H %d %m *").to_string();
+    let self_remove = format!(
+        "crontab -l | grep -v -- {} | crontab -",
+        shell_quote(&format!("# AUI:{}$", grep_escape(task_name)))
+    );
+    let command = format!(
+        "[ \"$(date +\\%Y)\" = \"{}\" ] && {{ {}; {}; }}",
+        run_at.format("%Y"),
+        command,
+        self_remove
+    );
+    (cron_line, command)
+}
+
+/// Schedules a script to run once at a specific future local datetime
+/// (`YYYY-MM-DD HH:MM`). Unlike the "once" preset this never recurs.
+#[tauri::command]
+fn create_one_shot_task(
+    app: tauri::AppHandle,
+    task_name: String,
+    script_path: String,
+    run_at: String,
+    shell: Option<String>,
+) -> Result<CreateTaskResult, String> {
+    validate_task_name(&task_name)?;
+    let shell = resolve_shell(shell)?;
+    let script_path = expand_user_path(&script_path);
+    let run_at = future_local_datetime(parse_run_at(&run_at)?)?;
+    let result = schedule_timed(
+        &app,
+        &task_name,
+        &script_path,
+        shell.clone(),
+        |script, shell| {
+            schedule_one_shot(
+                &app,
+                &task_name,
+                &script,
+                run_at,
+                shell.as_deref(),
+                None,
+                None,
+            )
+        },
+    )?;
+    #[cfg(not(target_os = "windows"))]
+    save_task_metadata(
+        &task_name,
+        &script_path,
+        ScheduleSpec {
+            run_at: Some(run_at.format("%Y-%m-%d %H:%M").to_string()),
+            shell,
+            ..Default::default()
+        },
+        Vec::new(),
+        None,
+    );
+    Ok(result)
+}
+
+/// Parses a delay such as `30m`, `2h`, `1h30m` or `90s` (units `s`, `m`,
+/// `h`, `d`). The total has to be positive.
+fn parse_delay(delay: &str) -> Result<chrono::Duration, String> {
+    let invalid = || format!("Invalid delay '{}': expected e.g. 30m, 2h or 1h30m", delay);
+    let mut total: i64 = 0;
+    let mut digits = String::new();
+    for c in delay.trim().chars().filter(|c| !c.is_whitespace()) {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            _ => return Err(invalid()),
+        };
+        let amount: i64 = digits.parse().map_err(|_| invalid())?;
+        total = amount
+            .checked_mul(unit)
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(invalid)?;
+        digits.clear();
+    }
+    if !digits.is_empty() {
+        return Err(invalid());
+    }
+    if total <= 0 {
+        return Err(format!("Delay '{}' must be greater than zero", delay));
+    }
+    chrono::Duration::try_seconds(total).ok_or_else(invalid)
+}
+
+/// Schedules a script to run once after `delay` (`30m`, `2h`, `1h30m` ...,
+/// see `parse_delay`), rounded up to the next whole minute since schedulers
+/// only start tasks on the minute.
+#[tauri::command]
+fn schedule_once_in(
+    app: tauri::AppHandle,
+    task_name: String,
+    script_path: String,
+    delay: String,
+    shell: Option<String>,
+) -> Result<CreateTaskResult, String> {
+    use chrono::TimeZone;
+
+    validate_task_name(&task_name)?;
+    let shell = resolve_shell(shell)?;
+    let script_path = expand_user_path(&script_path);
+    let at = chrono::Local::now()
+        .checked_add_signed(parse_delay(&delay)?)
+        .ok_or_else(|| format!("Delay '{}' is too long", delay))?;
+    let seconds = at.timestamp();
+    let seconds = if seconds % 60 == 0 && at.timestamp_subsec_nanos() == 0 {
+        seconds
+    } else {
+        (seconds / 60 + 1) * 60
+    };
+    let run_at = chrono::Local
+        .timestamp_opt(seconds, 0)
+        .single()
+        .ok_or_else(|| format!("Delay '{}' is too long", delay))?;
+    let result = schedule_timed(
+        &app,
+        &task_name,
+        &script_path,
+        shell.clone(),
+        |script, shell| {
+            schedule_one_shot(
+                &app,
+                &task_name,
+                &script,
+                run_at,
+                shell.as_deref(),
+                None,
+                None,
+            )
+        },
+    )?;
+    #[cfg(not(target_os = "windows"))]
+    save_task_metadata(
+        &task_name,
+        &script_path,
+        ScheduleSpec {
+            run_at: Some(run_at.format("%Y-%m-%d %H:%M").to_string()),
+            shell,
+            ..Default::default()
+        },
+        Vec::new(),
+        None,
+    );
+    Ok(result)
+}
+
+/// Decodes schtasks output, which is UTF-16 when a BOM is present.
+#[cfg(target_os = "windows")]
+fn decode_schtasks_output(bytes: &[u8]) -> String {
+    if bytes.starts_with(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    }
+}
+
+/// Reads the full Task Scheduler XML definition of `AUI\<task_name>`.
+#[cfg(target_os = "windows")]
+fn export_task_definition(task_name: &str) -> Result<String, String> {
+    let tn = format!("AUI\\{}", task_name);
+    let output = hidden_command("schtasks.exe")
+        .args(["/Query", "/XML", "/TN", &tn])
+        .output()
+        .map_err(|e| format!("Failed to run schtasks: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("schtasks query failed: {}", stderr.trim()));
+    }
+    Ok(decode_schtasks_output(&output.stdout))
+}
+
+/// Creates or replaces `AUI\<task_name>` from a Task Scheduler XML
+/// definition. schtasks expects the file as UTF-16 to match the XML header.
+#[cfg(target_os = "windows")]
+fn import_task_definition(task_name: &str, xml: &str) -> Result<(), String> {
+    validate_windows_task_name(task_name.to_string())?;
+    let _ = ensure_task_folder();
+    let tn = format!("AUI\\{}", task_name);
+    let file = std::env::temp_dir().join(format!(
+        "aui-task-{}-{}.xml",
+        std::process::id(),
+        unix_timestamp()
+    ));
+
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in xml.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+    std::fs::write(&file, bytes).map_err(|e| format!("Failed to write task XML: {}", e))?;
+
+    let output = hidden_command("schtasks.exe")
+        .args(["/Create", "/TN", &tn, "/XML"])
+        .arg(&file)
+        .arg("/F")
+        .output();
+    let _ = std::fs::remove_file(&file);
+    let output = output.map_err(|e| format!("Failed to run schtasks: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("schtasks import failed: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+/// Sets a direct child of `<Settings>` in a task definition, adding it when
+/// missing.
+#[cfg(target_os = "windows")]
+fn set_task_setting(xml: &str, tag: &str, value: &str) -> String {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    if let (Some(start), Some(end)) = (xml.find(&open), xml.find(&close)) {
+        if start < end {
+            return format!("{}{}{}{}", &xml[..start], open, value, &xml[end..]);
+        }
+    }
+    xml.replacen(
+        "</Settings>",
+        &format!("  {}{}{}\n  </Settings>", open, value, close),
+        1,
+    )
+}
+
+/// Applies the `ac_power_only` condition to a freshly created task by
+/// round-tripping its XML definition, since schtasks has no flag for it.
+#[cfg(target_os = "windows")]
+fn apply_power_condition(
+    task_name: &str,
+    ac_power_only: Option<bool>,
+    result: CreateTaskResult,
+) -> Result<CreateTaskResult, String> {
+    let Some(ac_only) = ac_power_only else {
+        return Ok(result);
+    };
+    let value = if ac_only { "true" } else { "false" };
+    let xml = export_task_definition(task_name)?;
+    let xml = set_task_setting(&xml, "DisallowStartIfOnBatteries", value);
+    let xml = set_task_setting(&xml, "StopIfGoingOnBatteries", value);
+    import_task_definition(task_name, &xml)?;
+
+    Ok(CreateTaskResult {
+        schedule_spec: format!(
+            "{} [DisallowStartIfOnBatteries={}]",
+            result.schedule_spec, value
+        ),
+        ..result
+    })
+}
+
+/// Checks a task priority against what the platform allows: Task Scheduler
+/// priorities 0 (highest) to 10 (lowest, 7 is the default) on Windows,
+/// `nice` levels 0 to 19 elsewhere (raising priority would need root).
+fn validate_priority(priority: Option<i32>) -> Result<Option<i32>, String> {
+    let range = if cfg!(target_os = "windows") {
+        0..=10
+    } else {
+        0..=19
+    };
+    match priority {
+        Some(p) if !range.contains(&p) => Err(format!(
+            "Priority {} is out of range: expected {} to {}",
+            p,
+            range.start(),
+            range.end()
+        )),
+        _ => Ok(priority),
+    }
+}
+
+/// Runs `command` at `nice` level `priority` when one is given.
+#[cfg(not(target_os = "windows"))]
+fn nice_command(command: String, priority: Option<i32>) -> String {
+    match priority {
+        Some(p) => format!("nice -n {} {}", p, command),
+        None => command,
+    }
+}
+
+/// Sets the `<Priority>` of a freshly created task by round-tripping its XML
+/// definition, since schtasks has no flag for it.
+#[cfg(target_os = "windows")]
+fn apply_priority(
+    task_name: &str,
+    priority: Option<i32>,
+    result: CreateTaskResult,
+) -> Result<CreateTaskResult, String> {
+    let Some(priority) = priority else {
+        return Ok(result);
+    };
+    let xml = export_task_definition(task_name)?;
+    let xml = set_task_setting(&xml, "Priority", &priority.to_string());
+    import_task_definition(task_name, &xml)?;
+
+    Ok(CreateTaskResult {
+        schedule_spec: format!("{} [Priority={}]", result.schedule_spec, priority),
+        ..result
+    })
+}
+
+/// The priority of each task in the `\AUI` folder by name. Empty if Task
+/// Scheduler can't be asked, so the listing still works without them.
+#[cfg(target_os = "windows")]
+fn task_priorities() -> HashMap<String, i32> {
+    task_service_script(
+        "try { $s.GetFolder('\\AUI').GetTasks(1) | \
+         ForEach-Object { $_.Name + \"`t\" + $_.Definition.Settings.Priority } } catch { }",
+    )
+    .unwrap_or_default()
+    .lines()
+    .filter_map(|line| {
+        let (name, priority) = line.trim().rsplit_once('\t')?;
+        Some((name.to_string(), priority.parse().ok()?))
+    })
+    .collect()
+}
+
+/// Returns the full Task Scheduler XML of an AUI task (Windows only), for
+/// inspection or backup of settings the preset options don't cover.
+#[tauri::command]
+fn export_task_xml(task_name: String) -> Result<String, String> {
+    validate_task_name(&task_name)?;
+    #[cfg(target_os = "windows")]
+    return export_task_definition(&task_name);
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = task_name;
+        Err("Unsupported: task XML is only available with the Windows Task Scheduler".into())
+    }
+}
+
+/// Creates (or replaces) an AUI task from Task Scheduler XML (Windows only).
+/// Without `task_name` the name is taken from the XML's `\AUI\<name>` URI.
+/// Returns the task name.
+#[tauri::command]
+fn import_task_xml(xml: String, task_name: Option<String>) -> Result<String, String> {
+    if let Some(name) = &task_name {
+        validate_task_name(name)?;
+    }
+    #[cfg(target_os = "windows")]
+    {
+        if !xml.contains("<Task") {
+            return Err("Not a Task Scheduler XML definition".into());
+        }
+        let task_name = task_name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .or_else(|| {
+                let uri = xml.split("<URI>").nth(1)?.split("</URI>").next()?;
+                uri.trim().strip_prefix("\\AUI\\").map(str::to_string)
+            })
+            .ok_or("Task name missing: pass task_name or include an \\AUI\\ URI")?;
+        import_task_definition(&task_name, &xml)?;
+        Ok(task_name)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (xml, task_name);
+        Err("Unsupported: task XML is only available with the Windows Task Scheduler".into())
+    }
+}
+
+/// Checks that text looks like a crontab: every line is blank, a comment,
+/// an environment assignment, or a schedule (5 fields or an `@` shorthand)
+/// followed by a command. Errors name the first offending line.
+#[cfg(not(target_os = "windows"))]
+fn validate_crontab(contents: &str) -> Result<(), String> {
+    if contents.trim().is_empty() {
+        return Err("Crontab file is empty".into());
+    }
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |reason: String| format!("Line {}: {} ('{}')", number + 1, reason, line);
+
+        if let Some((name, _)) = line.split_once('=') {
+            let name = name.trim();
+            if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                continue;
+            }
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields[0] == "@reboot" {
+            if fields.len() < 2 {
+                return Err(invalid("missing command".into()));
+            }
+            continue;
+        }
+        let schedule_len = if fields[0].starts_with('@') { 1 } else { 5 };
+        if fields.len() <= schedule_len {
+            return Err(invalid("expected a schedule followed by a command".into()));
+        }
+        parse_cron(&fields[..schedule_len].join(" ")).map_err(invalid)?;
+    }
+    Ok(())
+}
+
+/// Writes the user's entire crontab, AUI entries or not, to `dest_path` as
+/// a backup that `restore_crontab` can put back.
+#[tauri::command]
+fn backup_crontab(dest_path: String) -> Result<String, String> {
+    #[cfg(not(target_os = "windows"))]
+    {
+        let dest = std::path::PathBuf::from(expand_user_path(&dest_path));
+        if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let contents = read_crontab()?;
+        std::fs::write(&dest, &contents)
+            .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+        Ok(format!(
+            "Backed up {} crontab lines to {}",
+            contents.lines().count(),
+            dest.display()
+        ))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = dest_path;
+        Err("Unsupported: there is no crontab on Windows".into())
+    }
+}
+
+/// Replaces the user's entire crontab with the contents of `src_path`,
+/// typically a `backup_crontab` file. The file must be non-empty and parse
+/// as a crontab; nothing is changed otherwise.
+#[tauri::command]
+fn restore_crontab(src_path: String) -> Result<String, String> {
+    #[cfg(not(target_os = "windows"))]
+    {
+        let src = expand_user_path(&src_path);
+        let contents =
+            std::fs::read_to_string(&src).map_err(|e| format!("Failed to read {}: {}", src, e))?;
+        validate_crontab(&contents)
+            .map_err(|e| format!("{} is not a valid crontab: {}", src, e))?;
+        write_crontab(&with_trailing_newline(&contents))?;
+        Ok(format!(
+            "Restored {} crontab lines from {}",
+            contents.lines().count(),
+            src
+        ))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = src_path;
+        Err("Unsupported: there is no crontab on Windows".into())
+    }
+}
+
+/// A damaged `# AUI:` line in the crontab, as `audit_aui_crontab` reports
+/// it. `kind` is "glued" (several entries run together on one line),
+/// "orphaned_marker" (a marker with no schedule or command), "malformed"
+/// (a schedule cron would reject), "duplicate" (a repeat of an earlier
+/// line) or "duplicate_name" (two different commands under one name).
+#[derive(serde::Serialize)]
+struct CrontabProblem {
+    line: usize,
+    kind: String,
+    task_name: Option<String>,
+    text: String,
+    detail: String,
+}
+
+#[derive(serde::Serialize)]
+struct CrontabRepair {
+    problems: Vec<CrontabProblem>,
+    fixed: usize,
+    crontab: String,
+    applied: bool,
+}
+
+/// Why the schedule-and-command part of a crontab line is unusable, if it
+/// is. Same rules as `validate_crontab`.
+#[cfg(not(target_os = "windows"))]
+fn cron_entry_problem(entry: &str) -> Option<String> {
+    let fields: Vec<&str> = entry.split_whitespace().collect();
+    let schedule_len = match fields.first() {
+        None => return Some("has no schedule or command".into()),
+        Some(first) if first.starts_with('@') => 1,
+        Some(_) => 5,
+    };
+    if fields.len() <= schedule_len {
+        return Some("expected a schedule followed by a command".into());
+    }
+    if fields[0] == "@reboot" {
+        return None;
+    }
+    parse_cron(&fields[..schedule_len].join(" ")).err()
+}
+
+/// Where `# AUI:` markers start in a crontab line, skipping quoted text:
+/// one-shot entries name their own marker in the command that removes them.
+#[cfg(not(target_os = "windows"))]
+fn aui_marker_offsets(line: &str) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') if line[i..].starts_with("# AUI:") => offsets.push(i),
+            _ => {}
+        }
+    }
+    offsets
+}
+
+/// Splits a line that holds several `# AUI:` entries, left by appends to a
+/// crontab without a trailing newline. The name and the next schedule run
+/// together (`# AUI:nightly0 9 * * *`), so each split point is chosen where
+/// the rest reads as a crontab entry, preferring names with recorded
+/// metadata and then the longest name. `None` when no such split exists.
+#[cfg(not(target_os = "windows"))]
+fn split_glued_cron_line(line: &str) -> Option<Vec<String>> {
+    let line = line.trim();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for marker in aui_marker_offsets(line) {
+        parts.push(&line[start..marker]);
+        start = marker + "# AUI:".len();
+    }
+    parts.push(&line[start..]);
+    let mut lines = Vec::new();
+    let mut entry = parts[0].to_string();
+    for part in &parts[1..parts.len() - 1] {
+        let splits: Vec<(&str, &str)> = part
+            .char_indices()
+            .skip(1)
+            .map(|(i, _)| (part[..i].trim(), &part[i..]))
+            .filter(|(name, rest)| {
+                !name.is_empty()
+                    && cron_entry_problem(rest.trim().trim_start_matches('#')).is_none()
+            })
+            .collect();
+        let (name, rest) = splits
+            .iter()
+            .rev()
+            .find(|(name, _)| task_metadata_path(name).is_some_and(|p| p.exists()))
+            .or_else(|| splits.last())?;
+        lines.push(format!("{} # AUI:{}", entry.trim(), name));
+        entry = rest.to_string();
+    }
+    let name = parts[parts.len() - 1].trim();
+    if name.is_empty() {
+        return None;
+    }
+    lines.push(format!("{} # AUI:{}", entry.trim(), name));
+    Some(lines)
+}
+
+/// Finds the damaged `# AUI:` lines in `contents` and returns them with the
+/// crontab as it reads once they're fixed. Lines without a marker are kept
+/// untouched, except that a command left on the line above a lone marker
+/// is joined back to it when the task's recorded script shows it's ours.
+#[cfg(not(target_os = "windows"))]
+fn audit_crontab_text(contents: &str) -> (Vec<CrontabProblem>, String) {
+    let now = chrono::Local::now().naive_local();
+    let mut problems = Vec::new();
+    let mut kept: Vec<String> = Vec::new();
+    // The last non-blank kept line and its line number, if it has no
+    // marker and could be rejoined
+    let mut untagged_above: Option<(usize, usize)> = None;
+    let mut seen_lines: HashMap<String, usize> = HashMap::new();
+    let mut seen_names: HashMap<String, (usize, String)> = HashMap::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let number = index + 1;
+        if !line.contains("# AUI:") {
+            if !line.trim().is_empty() {
+                untagged_above =
+                    (!line.trim_start().starts_with('#')).then_some((kept.len(), number));
+            }
+            kept.push(line.to_string());
+            continue;
+        }
+        let above = untagged_above.take();
+        let mut problem = |kind: &str, task_name: Option<&str>, detail: String| {
+            problems.push(CrontabProblem {
+                line: number,
+                kind: kind.into(),
+                task_name: task_name.map(str::to_string),
+                text: line.to_string(),
+                detail,
+            })
+        };
+
+        let entries = if aui_marker_offsets(line).len() > 1 {
+            match split_glued_cron_line(line) {
+                Some(entries) => {
+                    problem(
+                        "glued",
+                        None,
+                        format!(
+                            "{} entries run together; split onto their own lines",
+                            entries.len()
+                        ),
+                    );
+                    entries
+                }
+                None => {
+                    problem(
+                        "glued",
+                        None,
+                        "several entries run together and can't be told apart; removed".into(),
+                    );
+                    continue;
+                }
+            }
+        } else {
+            vec![line.trim().to_string()]
+        };
+
+        for entry_line in entries {
+            let Some((entry, name)) = entry_line.rsplit_once("# AUI:") else {
+                continue;
+            };
+            let name = name.trim();
+            if name.is_empty() {
+                problem("malformed", None, "marker has no task name; removed".into());
+                continue;
+            }
+            let entry = entry.trim().trim_start_matches('#').trim();
+            if entry.is_empty() {
+                let script = load_task_metadata(name).map(|m| m.script_path);
+                let rejoin = above.filter(|(i, _)| {
+                    cron_entry_problem(&kept[*i]).is_none()
+                        && script.as_deref().is_some_and(|s| {
+                            kept[*i].contains(s) || cron_command_script(&kept[*i]) == s
+                        })
+                });
+                if let Some((i, command_line)) = rejoin {
+                    // Blank lines the split left in between go with it
+                    kept.truncate(i + 1);
+                    kept[i] = format!("{} # AUI:{}", kept[i].trim(), name);
+                    problem(
+                        "orphaned_marker",
+                        Some(name),
+                        format!(
+                            "marker split from its command; rejoined with line {}",
+                            command_line
+                        ),
+                    );
+                } else {
+                    problem(
+                        "orphaned_marker",
+                        Some(name),
+                        "marker with no schedule or command; removed".into(),
+                    );
+                }
+                continue;
+            }
+            if let Some(reason) = cron_entry_problem(entry) {
+                problem("malformed", Some(name), format!("{}; removed", reason));
+                continue;
+            }
+
+            let normalized = entry_line.split_whitespace().collect::<Vec<_>>().join(" ");
+            if let Some(first) = seen_lines.get(&normalized) {
+                problem(
+                    "duplicate",
+                    Some(name),
+                    format!("repeats line {}; removed", first),
+                );
+                continue;
+            }
+            seen_lines.insert(normalized, number);
+            if let Some(task) = parse_cron_task(&entry_line, now) {
+                match seen_names.get(name) {
+                    Some((first, command)) if *command != task.command => problem(
+                        "duplicate_name",
+                        Some(name),
+                        format!(
+                            "line {} runs a different command under the same name; \
+                             delete or rename one of the tasks",
+                            first
+                        ),
+                    ),
+                    Some(_) => {}
+                    None => {
+                        seen_names.insert(name.to_string(), (number, task.command));
+                    }
+                }
+            }
+            kept.push(entry_line);
+        }
+    }
+
+    let repaired = if kept.is_empty() {
+        String::new()
+    } else {
+        with_trailing_newline(&kept.join("\n"))
+    };
+    (problems, repaired)
+}
+
+/// Reports the `# AUI:` lines in the crontab that earlier versions left
+/// damaged: entries glued onto one line, markers cut off from their
+/// command, schedules cron rejects and repeated lines. Nothing is changed;
+/// `repair_aui_crontab` fixes what this finds.
+#[tauri::command]
+fn audit_aui_crontab() -> Result<Vec<CrontabProblem>, String> {
+    #[cfg(not(target_os = "windows"))]
+    {
+        Ok(audit_crontab_text(&read_crontab()?).0)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        Err("Unsupported: there is no crontab on Windows".into())
+    }
+}
+
+/// Fixes what `audit_aui_crontab` reports: glued entries are split onto
+/// their own lines, cut-off markers rejoined or removed, and malformed or
+/// repeated lines removed. Lines without an `# AUI:` marker are left alone,
+/// and tasks sharing a name are only reported, since either may be the one
+/// the user wants. With `fix` false this previews the repaired crontab
+/// without writing it.
+#[tauri::command]
+fn repair_aui_crontab(fix: bool) -> Result<CrontabRepair, String> {
+    #[cfg(not(target_os = "windows"))]
+    {
+        let (problems, crontab) = if fix {
+            let problems = std::cell::RefCell::new(Vec::new());
+            let crontab = modify_crontab(|existing| {
+                let (found, repaired) = audit_crontab_text(existing);
+                *problems.borrow_mut() = found;
+                repaired
+            })?;
+            (problems.into_inner(), crontab)
+        } else {
+            audit_crontab_text(&read_crontab()?)
+        };
+        let fixed = problems
+            .iter()
+            .filter(|p| p.kind != "duplicate_name")
+            .count();
+        Ok(CrontabRepair {
+            problems,
+            fixed,
+            crontab,
+            applied: fix,
+        })
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = fix;
+        Err("Unsupported: there is no crontab on Windows".into())
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SchedulerAccess {
+    allowed: bool,
+    error: Option<String>,
+}
+
+/// Checks up front whether AUI may change scheduled tasks, so the UI can
+/// warn instead of failing on the first edit. On macOS/Linux this reads the
+/// crontab and writes the identical content back (cron.allow/cron.deny or a
+/// locked-down crontab make that fail). On Windows it creates and deletes
+/// a trigger-less, disabled probe task in the `\AUI` folder.
+#[tauri::command]
+fn can_modify_crontab() -> SchedulerAccess {
+    #[cfg(not(target_os = "windows"))]
+    let result = modify_crontab(|existing| existing.to_string()).map(|_| ());
+
+    #[cfg(target_os = "windows")]
+    let result = {
+        let probe = "aui-write-test";
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-16\"?>\n\
+             <Task version=\"1.2\" xmlns=\"http://schemas.microsoft.com/windows/2004/02/mit/task\">\n  \
+             <Settings>\n    <Enabled>false</Enabled>\n  </Settings>\n  \
+             <Actions Context=\"Author\">\n    <Exec>\n      <Command>cmd.exe</Command>\n    \
+             </Exec>\n  </Actions>\n</Task>\n";
+        import_task_definition(probe, xml).and_then(|_| unschedule_task(probe).map(|_| ()))
+    };
+
+    SchedulerAccess {
+        allowed: result.is_ok(),
+        error: result.err(),
+    }
+}
+
+/// The cron expression equivalent to a `create_scheduled_task` repeat preset,
+/// or `None` for one-shot schedules. schtasks starts hourly tasks at the
+/// start time's minute; the crontab entry always runs on the hour.
+fn preset_cron(repeat: &str, hour: u32, minute: u32) -> Option<String> {
+    match repeat {
+        "hourly" if cfg!(target_os = "windows") => Some(format!("{} * * * *", minute)),
+        "hourly" => Some("0 * * * *".to_string()),
+        "daily" => Some(format!("{} {} * * *", minute, hour)),
+        "weekly" => Some(format!("{} {} * * 1", minute, hour)),
+        "monthly" => Some(format!("{} {} 1 * *", minute, hour)),
+        _ => None,
+    }
+}
+
+/// The optional settings of `create_scheduled_task`, described there.
+#[derive(serde::Deserialize, Default)]
+struct TaskOptions {
+    #[serde(default)]
+    shell: Option<String>,
+    #[serde(default)]
+    idle_minutes: Option<u32>,
+    #[serde(default)]
+    ac_power_only: Option<bool>,
+    #[serde(default)]
+    completion_webhook: Option<String>,
+    #[serde(default)]
+    priority: Option<i32>,
+    #[serde(default)]
+    require_signed: Option<bool>,
+    #[serde(default)]
+    prevent_overlap: Option<bool>,
+    #[serde(default)]
+    env_profile: Option<String>,
+    #[serde(default)]
+    completion_email: Option<String>,
+    #[serde(default)]
+    prepend_path: Option<Vec<String>>,
+    #[serde(default)]
+    backend: Option<String>,
+}
+
+/// Creates a scheduled task that runs a PowerShell script at the specified time.
+/// On Windows, uses schtasks.exe. On macOS/Linux, uses crontab, running the
+/// script under `shell` (its detected interpreter by default; ignored on
+/// Windows). `shell` and everything below are fields of `options`.
+///
+/// Windows-only options, ignored elsewhere:
+/// - `idle_minutes`: instead of a time, run once the machine has been idle
+///   for this many minutes (`/SC ONIDLE /I`).
+/// - `ac_power_only`: `true` only starts (and keeps running) the task on AC
+///   power, `false` also allows batteries. Unset keeps the schtasks default.
+///
+/// With `completion_webhook` the task runs a wrapper (see
+/// `completion_wrapper`) that POSTs the outcome to that URL once the script
+/// has finished. `priority` keeps heavy jobs from starving interactive work:
+/// a Task Scheduler priority on Windows, a `nice` level elsewhere (see
+/// `validate_priority`). With `require_signed` the task is only created if
+/// the script carries a trusted signature (see `check_script_signature`).
+/// With `prevent_overlap` a run is skipped while the previous one is still
+/// going (see `overlap_wrapper`). `env_profile` runs the script with a
+/// saved profile's variables (see `env_wrapper`). `completion_email` mails
+/// the outcome to that address through the saved SMTP settings (see
+/// `email_wrapper`). `prepend_path` puts directories in front of the
+/// scheduler's PATH, for tools it wouldn't find (see `get_scheduled_path`).
+/// `backend` overrides the scheduler `get_scheduling_backend` would pick
+/// (see `validate_backend`). Every run is timed for `get_task_metrics` (see
+/// `timing_wrapper`).
+#[tauri::command]
+fn create_scheduled_task(
+    app: tauri::AppHandle,
+    task_name: String,
+    script_path: String,
+    start_time: String,
+    start_date: String,
+    repeat: String,
+    options: Option<TaskOptions>,
+) -> Result<CreateTaskResult, String> {
+    validate_task_name(&task_name)?;
+    let TaskOptions {
+        shell,
+        idle_minutes,
+        ac_power_only,
+        completion_webhook,
+        priority,
+        require_signed,
+        prevent_overlap,
+        env_profile,
+        completion_email,
+        prepend_path,
+        backend,
+    } = options.unwrap_or_default();
+    let priority = validate_priority(priority)?;
+    let one_shot = !matches!(repeat.as_str(), "hourly" | "daily" | "weekly" | "monthly");
+    let backend = validate_backend(backend.as_deref(), one_shot)?;
+    if require_signed == Some(true) {
+        require_signed_script(&expand_user_path(&script_path))?;
+    }
+    let env = resolve_env_profile(&app, env_profile)?;
+    let prepend_path = prepend_path.unwrap_or_default();
+    let completion_webhook = completion_webhook.filter(|w| !w.trim().is_empty());
+    let completion_email = completion_email.filter(|e| !e.trim().is_empty());
+    let needs_env = !env.is_empty() || prepend_path.iter().any(|d| !d.trim().is_empty());
+    let wrapped = needs_env
+        || completion_webhook.is_some()
+        || completion_email.is_some()
+        || prevent_overlap == Some(true);
+    let script = expand_user_path(&script_path);
+    if wrapped && !std::path::Path::new(&script).is_file() {
+        return Err(format!("Script not found: {}", script));
+    }
+
+    // Every wrapper is rendered and the schedule checked before any file is
+    // written, so a bad option leaves nothing behind
+    let mut wrappers: Vec<Wrapper> = Vec::new();
+    let mut script_path = script_path;
+    let mut shell = shell;
+    // Innermost, so only the script itself is timed
+    if !is_timed_wrapper(&app, &task_name, &script_path) {
+        wrappers.push(timing_wrapper(
+            &app,
+            &task_name,
+            &script_path,
+            shell.take(),
+        )?);
+        script_path = wrappers[wrappers.len() - 1].path();
+    }
+    if needs_env {
+        wrappers.push(env_wrapper(
+            &app,
+            &task_name,
+            &script_path,
+            shell.take(),
+            &env,
+            &prepend_path,
+        )?);
+        script_path = wrappers[wrappers.len() - 1].path();
+    }
+    if let Some(webhook) = &completion_webhook {
+        wrappers.push(completion_wrapper(
+            &app,
+            &task_name,
+            &script_path,
+            shell.take(),
+            webhook,
+        )?);
+        script_path = wrappers[wrappers.len() - 1].path();
+    }
+    if let Some(to) = &completion_email {
+        wrappers.push(email_wrapper(
+            &app,
+            &task_name,
+            &script_path,
+            shell.take(),
+            to,
+        )?);
+        script_path = wrappers[wrappers.len() - 1].path();
+    }
+    // Outermost, so a skipped run doesn't report a completion either
+    if prevent_overlap == Some(true) {
+        wrappers.push(overlap_wrapper(
+            &app,
+            &task_name,
+            &script_path,
+            shell.take(),
+        )?);
+        script_path = wrappers[wrappers.len() - 1].path();
+    }
+    check_preset_schedule(&start_time, &start_date, &repeat, idle_minutes)?;
+
+    write_wrappers(&wrappers)?;
+    let result = create_preset_task(
+        &app,
+        task_name.clone(),
+        script_path,
+        start_time,
+        start_date,
+        repeat,
+        shell,
+        idle_minutes,
+        ac_power_only,
+        priority,
+        backend,
+    );
+    match result {
+        Ok(result) => {
+            let kept: Vec<String> = wrappers.iter().map(Wrapper::path).collect();
+            remove_replaced_wrappers(&app, &task_name, &kept);
+            Ok(result)
+        }
+        Err(e) => {
+            remove_wrappers(&wrappers);
+            Err(e)
+        }
+    }
+}
+
+/// After `task_name` was (re)created using `wrappers`, removes the ones it
+/// had before and no longer uses. Only on Windows, where schtasks replaces
+/// a task of the same name; a crontab gains a second line instead, and the
+/// first still needs its wrappers.
+fn remove_replaced_wrappers(app: &tauri::AppHandle, task_name: &str, wrappers: &[String]) {
+    #[cfg(target_os = "windows")]
+    remove_task_wrappers(app, task_name, wrappers);
+    #[cfg(not(target_os = "windows"))]
+    let _ = (app, task_name, wrappers);
+}
+
+/// Checks the schedule `create_preset_task` would create without creating
+/// anything, so a bad one is refused before any wrapper is written.
+fn check_preset_schedule(
+    start_time: &str,
+    start_date: &str,
+    repeat: &str,
+    idle_minutes: Option<u32>,
+) -> Result<(), String> {
+    parse_time(start_time)?;
+    #[cfg(target_os = "windows")]
+    if let Some(minutes) = idle_minutes {
+        return check_idle_minutes(minutes);
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = idle_minutes;
+    if !matches!(repeat, "hourly" | "daily" | "weekly" | "monthly") {
+        one_shot_preset_time(start_time, start_date)?;
+    }
+    Ok(())
+}
+
+/// The range schtasks accepts for `/SC ONIDLE /I`.
+#[cfg(target_os = "windows")]
+fn check_idle_minutes(minutes: u32) -> Result<(), String> {
+    if !(1..=999).contains(&minutes) {
+        return Err("Idle time must be between 1 and 999 minutes".into());
+    }
+    Ok(())
+}
+
+/// When a one-shot preset task runs: at `start_date` + `start_time`, or the
+/// next occurrence of `start_time` when no date is given. Errors once that
+/// is past.
+fn one_shot_preset_time(
+    start_time: &str,
+    start_date: &str,
+) -> Result<chrono::DateTime<chrono::Local>, String> {
+    let (hour, minute) = parse_time(start_time)?;
+    let run_at = if start_date.trim().is_empty() {
+        let now = chrono::Local::now();
+        let today = now
+            .date_naive()
+            .and_hms_opt(hour, minute, 0)
+            .ok_or_else(|| format!("Invalid time '{}'", start_time))?;
+        if today > now.naive_local() {
+            today
+        } else {
+            today + chrono::Duration::days(1)
+        }
+    } else {
+        parse_run_at(&format!("{} {:02}:{:02}", start_date.trim(), hour, minute))?
+    };
+    future_local_datetime(run_at)
+}
+
+/// Schedules `script_path` as it is, for `create_scheduled_task` once its
+/// wrappers are in place. `priority` and `backend` are taken as already
+/// validated.
+#[allow(clippy::too_many_arguments)]
+fn create_preset_task(
+    app: &tauri::AppHandle,
+    task_name: String,
+    script_path: String,
+    start_time: String,
+    start_date: String,
+    repeat: String,
+    shell: Option<String>,
+    idle_minutes: Option<u32>,
+    ac_power_only: Option<bool>,
+    priority: Option<i32>,
+    backend: Option<String>,
+) -> Result<CreateTaskResult, String> {
+    let shell = resolve_shell(shell)?;
+    let script_path = expand_user_path(&script_path);
+    let (hour, minute) = parse_time(&start_time)?;
+
+    #[cfg(target_os = "windows")]
+    if let Some(minutes) = idle_minutes {
+        check_idle_minutes(minutes)?;
+        let result = create_schtasks_task(
+            &task_name,
+            &script_path,
+            vec![
+                "/SC".to_string(),
+                "ONIDLE".to_string(),
+                "/I".to_string(),
+                minutes.to_string(),
+            ],
+        )?;
+        let result = apply_priority(&task_name, priority, result)?;
+        return apply_power_condition(&task_name, ac_power_only, result);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = (idle_minutes, ac_power_only);
+
+    if !matches!(repeat.as_str(), "hourly" | "daily" | "weekly" | "monthly") {
+        let result = schedule_one_shot(
+            app,
+            &task_name,
+            &script_path,
+            one_shot_preset_time(&start_time, &start_date)?,
+            shell.as_deref(),
+            priority,
+            backend.as_deref(),
+        )?;
+        #[cfg(target_os = "windows")]
+        let result = apply_power_condition(&task_name, ac_power_only, result)?;
+        #[cfg(not(target_os = "windows"))]
+        save_task_metadata(
+            &task_name,
+            &script_path,
+            ScheduleSpec {
+                repeat: Some(repeat.clone()),
+                start_time: Some(format!("{:02}:{:02}", hour, minute)),
+                start_date: Some(start_date.trim().to_string()).filter(|d| !d.is_empty()),
+                shell,
+                ..Default::default()
+            },
+            Vec::new(),
+            priority,
+        );
+        return Ok(result);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = shell;
+        // Map repeat type to schtasks /SC value
+        let sc = match repeat.as_str() {
+            "hourly" => "HOURLY",
+            "daily" => "DAILY",
+            "weekly" => "WEEKLY",
+            _ => "MONTHLY",
+        };
+
+        let mut schedule = vec![
+            "/SC".to_string(),
+            sc.to_string(),
+            "/ST".to_string(),
+            format!("{:02}:{:02}", hour, minute),
+        ];
+
+        // Add start date for non-hourly schedules
+        if sc != "HOURLY" && !start_date.is_empty() {
+            schedule.push("/SD".to_string());
+            schedule.push(start_date.clone());
+        }
+
+        let result = create_schtasks_task(&task_name, &script_path, schedule)?;
+        let result = apply_priority(&task_name, priority, result)?;
+        apply_power_condition(&task_name, ac_power_only, result)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        // macOS/Linux: append a crontab entry. cron has no start-date field.
+        let cron_line = preset_cron(&repeat, hour, minute).unwrap_or_default();
+
+        let result = install_cron_entry(
+            &task_name,
+            &script_path,
+            &cron_line,
+            &nice_command(
+                unix_script_command(&script_path, shell.as_deref()),
+                priority,
+            ),
+        )?;
+        save_task_metadata(
+            &task_name,
+            &script_path,
+            ScheduleSpec {
+                repeat: Some(repeat),
+                start_time: Some(format!("{:02}:{:02}", hour, minute)),
+                start_date: Some(start_date.trim().to_string()).filter(|d| !d.is_empty()),
+                shell,
+                ..Default::default()
+            },
+            Vec::new(),
+            priority,
+        );
+        Ok(result)
+    }
+}
+
+/// One field of a cron expression: its normalized source text and the
+/// sorted values it matches.
+struct CronField {
+    raw: String,
+    values: Vec<u32>,
+}
+
+impl CronField {
+    /// Whether the field was `*` (or a range/step covering every value).
+    fn is_any(&self, min: u32, max: u32) -> bool {
+        self.values.len() as u32 == max - min + 1
+    }
+
+    fn single(&self) -> Option<u32> {
+        (self.values.len() == 1).then(|| self.values[0])
+    }
+}
+
+/// A parsed standard 5-field cron expression
+/// (minute, hour, day of month, month, day of week).
+struct CronExpr {
+    minute: CronField,
+    hour: CronField,
+    day_of_month: CronField,
+    month: CronField,
+    day_of_week: CronField,
+}
+
+const CRON_MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const CRON_DAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+fn parse_cron_value(token: &str, names: &[&str], offset: u32) -> Option<u32> {
+    token.parse::<u32>().ok().or_else(|| {
+        let lower = token.to_lowercase();
+        names
+            .iter()
+            .position(|n| *n == lower)
+            .map(|i| i as u32 + offset)
+    })
+}
+
+fn parse_cron_field(
+    raw: &str,
+    label: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_offset: u32,
+) -> Result<CronField, String> {
+    let mut values = Vec::new();
+
+    for part in raw.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Invalid step '{}' in {} field", step, label))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            let a = parse_cron_value(a, names, name_offset);
+            let b = parse_cron_value(b, names, name_offset);
+            match (a, b) {
+                (Some(a), Some(b)) if a <= b => (a, b),
+                _ => return Err(format!("Invalid range '{}' in {} field", range, label)),
+            }
+        } else {
+            let v = parse_cron_value(range, names, name_offset)
+                .ok_or_else(|| format!("Invalid value '{}' in {} field", range, label))?;
+            // `5/15` means "from 5 to the end in steps of 15"
+            if part.contains('/') {
+                (v, max)
+            } else {
+                (v, v)
+            }
+        };
+
+        if start < min || end > max {
+            return Err(format!(
+                "Value '{}' out of range {}-{} in {} field",
+                range, min, max, label
+            ));
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+
+    values.sort_unstable();
+    values.dedup();
+    Ok(CronField {
+        raw: raw.to_string(),
+        values,
+    })
+}
+
+/// Parses and validates a 5-field cron expression. The `@hourly`, `@daily`,
+/// `@midnight`, `@weekly`, `@monthly` and `@yearly`/`@annually` shorthands
+/// are expanded; `@reboot` has no fixed schedule and is rejected.
+fn parse_cron(expr: &str) -> Result<CronExpr, String> {
+    let expanded = match expr.trim() {
+        "@hourly" => "0 * * * *",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@weekly" => "0 0 * * 0",
+        "@monthly" => "0 0 1 * *",
+        "@yearly" | "@annually" => "0 0 1 1 *",
+        other if other.starts_with('@') => {
+            return Err(format!("Unsupported cron shorthand '{}'", other));
+        }
+        other => other,
+    };
+
+    let fields: Vec<&str> = expanded.split_whitespace().collect();
+    if fields.len() != 5 {
+        return Err(format!(
+            "Invalid cron expression '{}': expected 5 fields (minute hour day month weekday), got {}",
+            expr.trim(),
+            fields.len()
+        ));
+    }
+
+    let mut day_of_week = parse_cron_field(fields[4], "day-of-week", 0, 7, &CRON_DAY_NAMES, 0)?;
+    // Both 0 and 7 mean Sunday
+    if day_of_week.values.contains(&7) {
+        day_of_week.values.retain(|v| *v != 7);
+        if !day_of_week.values.contains(&0) {
+            day_of_week.values.insert(0, 0);
+        }
+    }
+
+    Ok(CronExpr {
+        minute: parse_cron_field(fields[0], "minute", 0, 59, &[], 0)?,
+        hour: parse_cron_field(fields[1], "hour", 0, 23, &[], 0)?,
+        day_of_month: parse_cron_field(fields[2], "day-of-month", 1, 31, &[], 0)?,
+        month: parse_cron_field(fields[3], "month", 1, 12, &CRON_MONTH_NAMES, 1)?,
+        day_of_week,
+    })
+}
+
+impl std::fmt::Display for CronExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.minute.raw,
+            self.hour.raw,
+            self.day_of_month.raw,
+            self.month.raw,
+            self.day_of_week.raw
+        )
+    }
+}
+
+/// Formats a time of day as "9:05 AM".
+fn format_time_12h(hour: u32, minute: u32) -> String {
+    let suffix = if hour < 12 { "AM" } else { "PM" };
+    let hour12 = match hour % 12 {
+        0 => 12,
+        h => h,
+    };
+    format!("{}:{:02} {}", hour12, minute, suffix)
+}
+
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Joins values as a list, collapsing runs of three or more
+/// consecutive values into "x through y".
+fn describe_values(values: &[u32], name: impl Fn(u32) -> String) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < values.len() {
+        let mut j = i;
+        while j + 1 < values.len() && values[j + 1] == values[j] + 1 {
+            j += 1;
+        }
+        if j - i >= 2 {
+            parts.push(format!("{} through {}", name(values[i]), name(values[j])));
+        } else {
+            parts.extend(values[i..=j].iter().map(|v| name(*v)));
+        }
+        i = j + 1;
+    }
+
+    join_list(&parts)
+}
+
+/// Joins phrases as "a, b and c".
+fn join_list(parts: &[String]) -> String {
+    match parts.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => parts.join(""),
+    }
+}
+
+/// The `n` of a `*/n` field.
+fn cron_step(field: &CronField) -> Option<u32> {
+    field.raw.strip_prefix("*/").and_then(|n| n.parse().ok())
+}
+
+/// The time-of-day part of a description, e.g. "every 15 minutes" or
+/// "at 9:00 AM and 5:00 PM". `None` when there is no short phrasing.
+fn describe_cron_time(cron: &CronExpr) -> Option<String> {
+    let minute_any = cron.minute.is_any(0, 59);
+    let hour_any = cron.hour.is_any(0, 23);
+    let hours = &cron.hour.values;
+
+    if minute_any && hour_any {
+        return Some("every minute".to_string());
+    }
+    if let Some(step) = cron_step(&cron.minute).filter(|_| hour_any) {
+        return Some(format!("every {} minutes", step));
+    }
+    if minute_any {
+        let hour = cron.hour.single()?;
+        return Some(format!(
+            "every minute from {} to {}",
+            format_time_12h(hour, 0),
+            format_time_12h(hour, 59)
+        ));
+    }
+
+    if let Some(minute) = cron.minute.single() {
+        let past = if minute == 0 {
+            String::new()
+        } else {
+            format!(" at {} minutes past the hour", minute)
+        };
+        if hour_any {
+            return Some(format!("every hour{}", past));
+        }
+        if let Some(step) = cron_step(&cron.hour) {
+            return Some(format!("every {} hours{}", step, past));
+        }
+        if hours.len() <= 6 {
+            let times: Vec<String> = hours.iter().map(|h| format_time_12h(*h, minute)).collect();
+            return Some(format!("at {}", join_list(&times)));
+        }
+        let contiguous = hours.windows(2).all(|w| w[1] == w[0] + 1);
+        return contiguous.then(|| {
+            format!(
+                "every hour{} from {} to {}",
+                past,
+                format_time_12h(hours[0], minute),
+                format_time_12h(hours[hours.len() - 1], minute)
+            )
+        });
+    }
+
+    let hour = cron.hour.single()?;
+    (cron.minute.values.len() <= 6).then(|| {
+        let times: Vec<String> = cron
+            .minute
+            .values
+            .iter()
+            .map(|m| format_time_12h(hour, *m))
+            .collect();
+        format!("at {}", join_list(&times))
+    })
+}
+
+/// Describes a cron expression in plain English, e.g. "Runs every day at
+/// 9:00 AM" or "Runs every 15 minutes on Monday through Friday". Exotic
+/// expressions fall back to quoting the expression itself.
+fn describe_cron(cron: &CronExpr) -> String {
+    let Some(time) = describe_cron_time(cron) else {
+        return format!("Runs on the cron schedule \"{}\"", cron);
+    };
+
+    let dom_any = cron.day_of_month.is_any(1, 31);
+    let dow_any = cron.day_of_week.is_any(0, 6);
+    let month_any = cron.month.is_any(1, 12);
+
+    const DAY_NAMES: [&str; 7] = [
+        "Sunday",
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+    ];
+    let weekdays = || match cron.day_of_week.values.as_slice() {
+        [1, 2, 3, 4, 5] => "on weekdays".to_string(),
+        [0, 6] => "on weekends".to_string(),
+        days => format!(
+            "on {}",
+            describe_values(days, |d| DAY_NAMES[d as usize].to_string())
+        ),
+    };
+    let month_days = || {
+        format!(
+            "on the {} of the month",
+            describe_values(&cron.day_of_month.values, ordinal)
+        )
+    };
+
+    let days = match (dom_any, dow_any) {
+        (true, true) => None,
+        (true, false) => Some(weekdays()),
+        (false, true) => Some(month_days()),
+        // cron runs when either field matches
+        (false, false) => Some(format!("{} or {}", month_days(), weekdays())),
+    };
+    let months = (!month_any).then(|| {
+        const NAMES: [&str; 12] = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        format!(
+            "in {}",
+            describe_values(&cron.month.values, |m| NAMES[m as usize - 1].to_string())
+        )
+    });
+
+    let mut description = match (&days, time.starts_with("at ")) {
+        (None, true) => format!("Runs every day {}", time),
+        _ => format!("Runs {}", time),
+    };
+    for part in [days, months].into_iter().flatten() {
+        description.push(' ');
+        description.push_str(&part);
+    }
+    description
+}
+
+/// The first time strictly after `after` that `cron` fires, searching up to
+/// four years ahead (long enough for a February 29th schedule).
+fn cron_next_run(cron: &CronExpr, after: chrono::NaiveDateTime) -> Option<chrono::NaiveDateTime> {
+    use chrono::{Datelike, Timelike};
+
+    let start =
+        after.date().and_hms_opt(after.hour(), after.minute(), 0)? + chrono::Duration::minutes(1);
+    // With both day fields restricted cron fires on either; otherwise on both
+    let either_day = !cron.day_of_month.is_any(1, 31) && !cron.day_of_week.is_any(0, 6);
+
+    for offset in 0..=366 * 4 {
+        let date = start.date() + chrono::Duration::days(offset);
+        if !cron.month.values.contains(&date.month()) {
+            continue;
+        }
+        let dom = cron.day_of_month.values.contains(&date.day());
+        let dow = cron
+            .day_of_week
+            .values
+            .contains(&date.weekday().num_days_from_sunday());
+        if !(if either_day { dom || dow } else { dom && dow }) {
+            continue;
+        }
+
+        let today = offset == 0;
+        for &hour in &cron.hour.values {
+            if today && hour < start.hour() {
+                continue;
+            }
+            for &minute in &cron.minute.values {
+                if today && hour == start.hour() && minute < start.minute() {
+                    continue;
+                }
+                return date.and_hms_opt(hour, minute, 0);
+            }
+        }
+    }
+    None
+}
+
+/// The `create_scheduled_task` preset a cron expression corresponds to, or
+/// "cron" for anything more specific.
+#[cfg(not(target_os = "windows"))]
+fn cron_schedule_kind(cron: &CronExpr) -> &'static str {
+    let every_day = cron.day_of_month.is_any(1, 31)
+        && cron.month.is_any(1, 12)
+        && cron.day_of_week.is_any(0, 6);
+    let at_time = cron.hour.single().is_some() && cron.minute.single().is_some();
+
+    if every_day && cron.hour.is_any(0, 23) && cron.minute.single().is_some() {
+        "hourly"
+    } else if every_day && at_time {
+        "daily"
+    } else if at_time
+        && cron.day_of_month.is_any(1, 31)
+        && cron.month.is_any(1, 12)
+        && cron.day_of_week.single().is_some()
+    {
+        "weekly"
+    } else if at_time
+        && cron.month.is_any(1, 12)
+        && cron.day_of_week.is_any(0, 6)
+        && cron.day_of_month.single().is_some()
+    {
+        "monthly"
+    } else {
+        "cron"
+    }
+}
+
+/// Translates the cron forms that have a schtasks equivalent into schedule
+/// flags. Anything else (e.g. minute lists, mixed day-of-month and weekday
+/// restrictions) is rejected rather than approximated.
+#[cfg(target_os = "windows")]
+fn cron_to_schtasks(cron: &CronExpr) -> Result<Vec<String>, String> {
+    let unsupported = || {
+        format!(
+            "Unsupported: cron expression '{}' has no schtasks equivalent",
+            cron
+        )
+    };
+    let flags = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    let any_dom = cron.day_of_month.is_any(1, 31);
+    let any_month = cron.month.is_any(1, 12);
+    let any_dow = cron.day_of_week.values.len() == 7;
+    let every_day = any_dom && any_month && any_dow;
+
+    // Evenly spaced intervals: "*/n * * * *" and "m */n * * *"
+    let step_of = |field: &CronField, max: u32| -> Option<u32> {
+        let v = &field.values;
+        if v.len() < 2 || v[0] != 0 {
+            return None;
+        }
+        let step = v[1] - v[0];
+        let even = v.windows(2).all(|w| w[1] - w[0] == step) && (max + 1) % step == 0;
+        (even && v.len() as u32 == (max + 1) / step).then_some(step)
+    };
+
+    if every_day && cron.hour.is_any(0, 23) {
+        if let Some(step) = step_of(&cron.minute, 59) {
+            return Ok(flags(&[
+                "/SC",
+                "MINUTE",
+                "/MO",
+                &step.to_string(),
+                "/ST",
+                "00:00",
+            ]));
+        }
+    }
+
+    let minute = cron.minute.single().ok_or_else(unsupported)?;
+
+    if every_day {
+        if cron.hour.is_any(0, 23) {
+            return Ok(flags(&[
+                "/SC",
+                "HOURLY",
+                "/ST",
+                &format!("00:{:02}", minute),
+            ]));
+        }
+        if let Some(step) = step_of(&cron.hour, 23) {
+            return Ok(flags(&[
+                "/SC",
+                "HOURLY",
+                "/MO",
+                &step.to_string(),
+                "/ST",
+                &format!("00:{:02}", minute),
+            ]));
+        }
+    }
+
+    let hour = cron.hour.single().ok_or_else(unsupported)?;
+    let start = format!("{:02}:{:02}", hour, minute);
+
+    if every_day {
+        return Ok(flags(&["/SC", "DAILY", "/ST", &start]));
+    }
+
+    if any_dom && any_month {
+        let days: Vec<String> = cron
+            .day_of_week
+            .values
+            .iter()
+            .map(|d| CRON_DAY_NAMES[*d as usize].to_uppercase())
+            .collect();
+        return Ok(flags(&[
+            "/SC",
+            "WEEKLY",
+            "/D",
+            &days.join(","),
+            "/ST",
+            &start,
+        ]));
+    }
+
+    if any_dow {
+        let days: Vec<String> = cron
+            .day_of_month
+            .values
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        let mut schedule = flags(&["/SC", "MONTHLY", "/D", &days.join(",")]);
+        if !any_month {
+            let months: Vec<String> = cron
+                .month
+                .values
+                .iter()
+                .map(|m| CRON_MONTH_NAMES[*m as usize - 1].to_uppercase())
+                .collect();
+            schedule.extend(flags(&["/M", &months.join(",")]));
+        }
+        schedule.extend(flags(&["/ST", &start]));
+        return Ok(schedule);
+    }
+
+    Err(unsupported())
+}
+
+/// Schedules a script from a raw 5-field cron expression. On macOS/Linux the
+/// expression is written to the crontab as-is (shorthands expanded); on
+/// Windows common forms are translated to schtasks flags and anything
+/// without an equivalent is rejected with an `Unsupported` error. With
+/// `prevent_overlap` a run is skipped while the previous one is still going
+/// (see `overlap_wrapper`).
+#[tauri::command]
+fn create_scheduled_task_cron(
+    app: tauri::AppHandle,
+    task_name: String,
+    script_path: String,
+    cron_expr: String,
+    shell: Option<String>,
+    prevent_overlap: Option<bool>,
+) -> Result<CreateTaskResult, String> {
+    validate_task_name(&task_name)?;
+    if prevent_overlap == Some(true) {
+        let script = expand_user_path(&script_path);
+        if !std::path::Path::new(&script).is_file() {
+            return Err(format!("Script not found: {}", script));
+        }
+    }
+    // A bad expression is refused before any wrapper is written
+    let cron = parse_cron(&cron_expr)?;
+    #[cfg(target_os = "windows")]
+    cron_to_schtasks(&cron)?;
+    #[cfg(not(target_os = "windows"))]
+    let _ = cron;
+
+    let mut wrappers: Vec<Wrapper> = Vec::new();
+    let mut script_path = script_path;
+    let mut shell = shell;
+    if !is_timed_wrapper(&app, &task_name, &script_path) {
+        wrappers.push(timing_wrapper(
+            &app,
+            &task_name,
+            &script_path,
+            shell.take(),
+        )?);
+        script_path = wrappers[wrappers.len() - 1].path();
+    }
+    if prevent_overlap == Some(true) {
+        wrappers.push(overlap_wrapper(
+            &app,
+            &task_name,
+            &script_path,
+            shell.take(),
+        )?);
+        script_path = wrappers[wrappers.len() - 1].path();
+    }
+    write_wrappers(&wrappers)?;
+    match create_cron_task(task_name.clone(), script_path, cron_expr, shell) {
+        Ok(result) => {
+            let kept: Vec<String> = wrappers.iter().map(Wrapper::path).collect();
+            remove_replaced_wrappers(&app, &task_name, &kept);
+            Ok(result)
+        }
+        Err(e) => {
+            remove_wrappers(&wrappers);
+            Err(e)
+        }
+    }
+}
+
+/// `create_scheduled_task_cron` without its wrappers.
+fn create_cron_task(
+    task_name: String,
+    script_path: String,
+    cron_expr: String,
+    shell: Option<String>,
+) -> Result<CreateTaskResult, String> {
+    let shell = resolve_shell(shell)?;
+    let script_path = expand_user_path(&script_path);
+    let cron = parse_cron(&cron_expr)?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = shell;
+        create_schtasks_task(&task_name, &script_path, cron_to_schtasks(&cron)?)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let result = install_cron_entry(
+            &task_name,
+            &script_path,
+            &cron.to_string(),
+            &unix_script_command(&script_path, shell.as_deref()),
+        )?;
+        save_task_metadata(
+            &task_name,
+            &script_path,
+            ScheduleSpec {
+                cron: Some(cron_expr.trim().to_string()),
+                shell,
+                ..Default::default()
+            },
+            Vec::new(),
+            None,
+        );
+        Ok(result)
+    }
+}
+
+/// Escapes text for an XML element or attribute value.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// A task definition with one calendar trigger per time of day, since
+/// schtasks' flags only allow a single start time.
+#[cfg(target_os = "windows")]
+fn multi_trigger_task_xml(
+    script_path: &str,
+    times: &[(u32, u32)],
+    repeat: &str,
+) -> Result<String, String> {
+    let schedule = match repeat {
+        "daily" => "<ScheduleByDay><DaysInterval>1</DaysInterval></ScheduleByDay>".to_string(),
+        "weekly" => "<ScheduleByWeek><WeeksInterval>1</WeeksInterval>\
+                     <DaysOfWeek><Monday /></DaysOfWeek></ScheduleByWeek>"
+            .to_string(),
+        "monthly" => {
+            let months: String = [
+                "January",
+                "February",
+                "March",
+                "April",
+                "May",
+                "June",
+                "July",
+                "August",
+                "September",
+                "October",
+                "November",
+                "December",
+            ]
+            .iter()
+            .map(|m| format!("<{} />", m))
+            .collect();
+            format!(
+                "<ScheduleByMonth><DaysOfMonth><Day>1</Day></DaysOfMonth>\
+                 <Months>{}</Months></ScheduleByMonth>",
+                months
+            )
+        }
+        other => return Err(format!("Unsupported repeat '{}' for multiple times", other)),
+    };
+    let today = chrono::Local::now().format("%Y-%m-%d");
+    let triggers: String = times
+        .iter()
+        .map(|(hour, minute)| {
+            format!(
+                "    <CalendarTrigger>\n      <StartBoundary>{}T{:02}:{:02}:00</StartBoundary>\n      \
+                 <Enabled>true</Enabled>\n      {}\n    </CalendarTrigger>\n",
+                today, hour, minute, schedule
+            )
+        })
+        .collect();
+
+    // The script command line is "<program> <arguments>", program maybe quoted
+    let command = windows_script_command(script_path, false)?;
+    let (program, arguments) = match command.strip_prefix('"') {
+        Some(rest) => rest.split_once("\" ").unwrap_or((rest, "")),
+        None => command.split_once(' ').unwrap_or((&command, "")),
+    };
+    let program = program.trim_end_matches('"');
+
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-16\"?>\n\
+         <Task version=\"1.2\" xmlns=\"http://schemas.microsoft.com/windows/2004/02/mit/task\">\n  \
+         <Triggers>\n{}  </Triggers>\n  \
+         <Settings>\n    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>\n    \
+         <Enabled>true</Enabled>\n  </Settings>\n  \
+         <Actions Context=\"Author\">\n    <Exec>\n      <Command>{}</Command>\n      \
+         <Arguments>{}</Arguments>\n    </Exec>\n  </Actions>\n\
+         </Task>\n",
+        triggers,
+        xml_escape(program),
+        xml_escape(arguments)
+    ))
+}
+
+/// Schedules a script to run at several times of day, e.g. 08:00, 12:00
+/// and 17:00 every day. `repeat` is daily (the default), weekly (Mondays)
+/// or monthly (on the 1st), as for `create_scheduled_task`. On macOS/Linux
+/// each time is its own crontab line sharing the task's marker; on Windows
+/// it is one task with a trigger per time, created from XML.
+#[tauri::command]
+fn create_scheduled_task_multi(
+    app: tauri::AppHandle,
+    task_name: String,
+    script_path: String,
+    start_times: Vec<String>,
+    repeat: Option<String>,
+    shell: Option<String>,
+) -> Result<CreateTaskResult, String> {
+    validate_task_name(&task_name)?;
+    let shell = resolve_shell(shell)?;
+    let script_path = expand_user_path(&script_path);
+    let repeat = repeat
+        .map(|r| r.trim().to_lowercase())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "daily".into());
+    if !matches!(repeat.as_str(), "daily" | "weekly" | "monthly") {
+        return Err(format!(
+            "Unsupported repeat '{}' for multiple times: expected daily, weekly or monthly",
+            repeat
+        ));
+    }
+    let mut times = start_times
+        .iter()
+        .map(|t| parse_time(t))
+        .collect::<Result<Vec<_>, _>>()?;
+    times.sort();
+    times.dedup();
+    if times.is_empty() {
+        return Err("At least one start time is required".into());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        schedule_timed(&app, &task_name, &script_path, shell, |script, _| {
+            let xml = multi_trigger_task_xml(&script, &times, &repeat)?;
+            import_task_definition(&task_name, &xml)?;
+            let listed: Vec<String> = times
+                .iter()
+                .map(|(h, m)| format!("{:02}:{:02}", h, m))
+                .collect();
+            Ok(CreateTaskResult {
+                message: format!("Created scheduled task: AUI\\{}", task_name),
+                schedule_spec: format!("{} at {}", repeat.to_uppercase(), listed.join(", ")),
+                raw_command: windows_script_command(&script, false)?,
+                script_path: script,
+                timezone: schedule_timezone(),
+            })
+        })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let cron_lines: Vec<String> = times
+            .iter()
+            .filter_map(|(hour, minute)| preset_cron(&repeat, *hour, *minute))
+            .collect();
+        let result = schedule_timed(
+            &app,
+            &task_name,
+            &script_path,
+            shell.clone(),
+            |script, shell| {
+                install_cron_entries(
+                    &task_name,
+                    &script,
+                    &cron_lines,
+                    &unix_script_command(&script, shell.as_deref()),
+                )
+            },
+        )?;
+        save_task_metadata(
+            &task_name,
+            &script_path,
+            ScheduleSpec {
+                repeat: Some(repeat),
+                shell,
+                ..Default::default()
+            },
+            times
+                .iter()
+                .map(|(h, m)| format!("{:02}:{:02}", h, m))
+                .collect(),
+            None,
+        );
+        Ok(result)
+    }
+}
+
+/// Describes a schedule in plain English for display, from either a cron
+/// expression or a `create_scheduled_task` preset (`repeat` + `start_time`).
+#[tauri::command]
+fn describe_schedule(
+    cron_expr: Option<String>,
+    repeat: Option<String>,
+    start_time: Option<String>,
+) -> Result<String, String> {
+    if let Some(expr) = cron_expr.filter(|e| !e.trim().is_empty()) {
+        return Ok(describe_cron(&parse_cron(&expr)?));
+    }
+
+    let repeat = repeat.unwrap_or_default();
+    let (hour, minute) = parse_time(start_time.as_deref().unwrap_or("00:00"))?;
+    match preset_cron(&repeat, hour, minute) {
+        Some(expr) => Ok(describe_cron(&parse_cron(&expr)?)),
+        None => Ok(format!("Runs once at {}", format_time_12h(hour, minute))),
+    }
+}
+
+/// Lists all AUI scheduled tasks.
+/// On Windows, queries schtasks under the AUI\ folder.
+/// On macOS/Linux, searches crontab for AUI: markers.
+/// Having no tasks (even no crontab or task folder at all) is an empty list;
+/// errors mean the scheduler couldn't be queried.
+#[tauri::command]
+fn list_scheduled_tasks() -> Result<Vec<ScheduledTask>, String> {
+    scheduled_tasks()
+}
+
+/// systemd timers in the user's session named `aui-<task>.timer`, for
+/// schedules set up by hand or by other tools alongside AUI's own. A
+/// missing `systemctl` or session bus lists as empty.
+#[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+fn systemd_timer_tasks() -> Vec<ScheduledTask> {
+    let systemctl = |args: &[&str]| {
+        StdCommand::new("systemctl")
+            .arg("--user")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+    };
+    // `{ OnCalendar=*-*-* 09:00:00 ; next_elapse=... }` -> "*-*-* 09:00:00"
+    let field = |value: &str, key: &str| {
+        value
+            .split_once(key)
+            .map(|(_, rest)| rest.split(" ;").next().unwrap_or(rest).trim().to_string())
+    };
+
+    let Some(units) = systemctl(&[
+        "list-units",
+        "--type=timer",
+        "--all",
+        "--plain",
+        "--no-legend",
+        "aui-*.timer",
+    ]) else {
+        return Vec::new();
+    };
+
+    let mut tasks = Vec::new();
+    for unit in units.lines().filter_map(|l| l.split_whitespace().next()) {
+        let Some(name) = unit
+            .strip_prefix("aui-")
+            .and_then(|n| n.strip_suffix(".timer"))
+        else {
+            continue;
+        };
+        let Some(show) = systemctl(&[
+            "show",
+            unit,
+            "-p",
+            "Unit",
+            "-p",
+            "ActiveState",
+            "-p",
+            "TimersCalendar",
+            "-p",
+            "TimersMonotonic",
+            "-p",
+            "NextElapseUSecRealtime",
+        ]) else {
+            continue;
+        };
+        let property = |key: &str| {
+            show.lines()
+                .find_map(|l| l.strip_prefix(key)?.strip_prefix('='))
+                .unwrap_or("")
+                .trim()
+                .to_string()
+        };
+
+        let calendar = field(&property("TimersCalendar"), "OnCalendar=");
+        let (schedule_spec, schedule_kind) = match calendar {
+            Some(spec) => {
+                let kind = match spec.as_str() {
+                    "hourly" | "daily" | "weekly" | "monthly" => spec.clone(),
+                    _ => "calendar".into(),
+                };
+                (spec, kind)
+            }
+            None => {
+                let monotonic = property("TimersMonotonic");
+                let spec = monotonic
+                    .trim_start_matches('{')
+                    .split(" ;")
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .to_string();
+                (spec, "interval".into())
+            }
+        };
+        // "Wed 2026-10-14 09:00:00 CEST", in local time
+        let next_elapse = property("NextElapseUSecRealtime");
+        let mut words = next_elapse.split_whitespace().skip(1);
+        let next_run = words
+            .next()
+            .zip(words.next())
+            .and_then(|(date, time)| {
+                chrono::NaiveDateTime::parse_from_str(
+                    &format!("{} {}", date, time),
+                    "%Y-%m-%d %H:%M:%S",
+                )
+                .ok()
+            })
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string());
+
+        let service = Some(property("Unit"))
+            .filter(|u| !u.is_empty())
+            .unwrap_or_else(|| format!("aui-{}.service", name));
+        let command = systemctl(&["show", &service, "-p", "ExecStart", "--value"])
+            .and_then(|exec| field(&exec, "argv[]="))
+            .unwrap_or_default();
+        tasks.push(ScheduledTask {
+            backend: "systemd".into(),
+            task_name: name.to_string(),
+            schedule_spec,
+            schedule_kind,
+            script_path: shell_words(&command).pop().unwrap_or_default(),
+            command,
+            node_id: None,
+            enabled: property("ActiveState") == "active",
+            next_run,
+            last_result: None,
+            last_result_text: None,
+            priority: None,
+            metadata: None,
+            timezone: None,
+        });
+    }
+    tasks
+}
+
+/// Lists AUI tasks from every scheduler backend on the platform, each
+/// tagged with its `backend`: the `\AUI` Task Scheduler folder on Windows;
+/// the crontab, queued `at` jobs and, on Linux, `aui-*` systemd user timers
+/// elsewhere. A task found under the same name in several backends is
+/// listed once, with `backend` naming them all (e.g. "cron+systemd"), the
+/// earliest next run, and enabled if any of them is.
+#[tauri::command]
+fn list_all_scheduled_tasks() -> Result<Vec<ScheduledTask>, String> {
+    #[allow(unused_mut)]
+    let mut found = scheduled_tasks()?;
+    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+    {
+        let timezone = schedule_timezone();
+        found.extend(systemd_timer_tasks().into_iter().map(|task| ScheduledTask {
+            timezone: Some(timezone.clone()),
+            ..task
+        }));
+    }
+
+    let mut tasks: Vec<ScheduledTask> = Vec::new();
+    for task in found {
+        let Some(existing) = tasks.iter_mut().find(|t| t.task_name == task.task_name) else {
+            tasks.push(task);
+            continue;
+        };
+        if !existing.backend.split('+').any(|b| b == task.backend) {
+            existing.backend = format!("{}+{}", existing.backend, task.backend);
+        }
+        existing.enabled |= task.enabled;
+        existing.next_run = match (existing.next_run.take(), task.next_run) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+    Ok(tasks)
+}
+
+/// Re-reads the scheduler and emits the fresh list as a `tasks-changed`
+/// event, for when tasks may have been edited outside AUI.
+#[tauri::command]
+fn refresh_scheduled_tasks(app: tauri::AppHandle) -> Result<Vec<ScheduledTask>, String> {
+    let tasks = scheduled_tasks()?;
+    let _ = app.emit("tasks-changed", &tasks);
+    Ok(tasks)
+}
+
+/// How long a changed task list must stay unchanged before the watcher
+/// reports it, so a burst of edits is one event.
+const TASK_WATCH_SETTLE: Duration = Duration::from_secs(1);
+
+/// The running task watcher, if any. Dropping the sender stops it.
+#[derive(Default)]
+struct TaskWatcher(Mutex<Option<mpsc::Sender<()>>>);
+
+/// Polls the scheduler every `interval_secs` (10 by default, at least 2)
+/// and emits `tasks-changed` with the new list whenever it differs from
+/// the last one, e.g. after a `crontab -e` or a change in Task Scheduler.
+/// Starting it again replaces the running watcher.
+#[tauri::command]
+fn start_task_watcher(
+    app: tauri::AppHandle,
+    watcher: tauri::State<'_, TaskWatcher>,
+    interval_secs: Option<u64>,
+) -> Result<(), String> {
+    let interval = Duration::from_secs(interval_secs.unwrap_or(10).max(2));
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    *watcher.0.lock().map_err(|e| e.to_string())? = Some(stop_tx);
+
+    std::thread::spawn(move || {
+        // Errors (e.g. schtasks briefly failing) are skipped, not reported
+        // as an empty list
+        let read = || {
+            scheduled_tasks().ok().and_then(|tasks| {
+                let fingerprint = serde_json::to_string(&tasks).ok()?;
+                Some((fingerprint, tasks))
+            })
+        };
+        let stopped = |wait: Duration| {
+            !matches!(
+                stop_rx.recv_timeout(wait),
+                Err(mpsc::RecvTimeoutError::Timeout)
+            )
+        };
+
+        let mut last = read().map(|(fingerprint, _)| fingerprint);
+        while !stopped(interval) {
+            let Some((mut fingerprint, mut tasks)) = read() else {
+                continue;
+            };
+            if last.as_ref() == Some(&fingerprint) {
+                continue;
+            }
+            loop {
+                if stopped(TASK_WATCH_SETTLE) {
+                    return;
+                }
+                match read() {
+                    Some((settled, _)) if settled == fingerprint => break,
+                    Some((next, next_tasks)) => {
+                        fingerprint = next;
+                        tasks = next_tasks;
+                    }
+                    None => break,
+                }
+            }
+            let _ = app.emit("tasks-changed", &tasks);
+            last = Some(fingerprint);
+        }
+    });
+    Ok(())
+}
+
+/// Stops the watcher started with `start_task_watcher`.
+#[tauri::command]
+fn stop_task_watcher(watcher: tauri::State<'_, TaskWatcher>) -> Result<(), String> {
+    watcher
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .map(|_| ())
+        .ok_or_else(|| "No task watcher is running".to_string())
+}
+
+/// One trigger of a task in the `\AUI` folder, as read over COM for
+/// `export_crontab_fragment`. `kind` is the `TASK_TRIGGER_TYPE2` value and
+/// the bitmasks are Task Scheduler's (Sunday, day 1 and January are bit 0).
+#[cfg(target_os = "windows")]
+#[derive(serde::Deserialize)]
+struct WindowsTrigger {
+    name: String,
+    enabled: bool,
+    command: String,
+    kind: i32,
+    start: Option<String>,
+    interval: Option<String>,
+    days_interval: Option<u32>,
+    days_of_week: Option<u32>,
+    days_of_month: Option<u32>,
+    months: Option<u32>,
+}
+
+/// The cron schedule and command equivalent to a Task Scheduler trigger,
+/// or why there is none.
+#[cfg(target_os = "windows")]
+fn windows_trigger_cron(trigger: &WindowsTrigger) -> Result<(String, String), String> {
+    use chrono::Timelike;
+
+    let bits = |mask: u32, count: u32, first: u32| {
+        let values: Vec<String> = (0..count)
+            .filter(|bit| mask & (1 << bit) != 0)
+            .map(|bit| (bit + first).to_string())
+            .collect();
+        values.join(",")
+    };
+    let start = trigger
+        .start
+        .as_deref()
+        .and_then(|s| s.get(..19))
+        .and_then(|s| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").ok());
+    let command = trigger.command.clone();
+
+    // TASK_TRIGGER_BOOT
+    if trigger.kind == 8 {
+        return Ok(("@reboot".into(), command));
+    }
+    let Some(start) = start else {
+        return Err("has no start time".into());
+    };
+    let (minute, hour) = (start.minute(), start.hour());
+
+    // Time triggers repeating on an interval are schtasks' MINUTE/HOURLY
+    if let Some(interval) = trigger.interval.as_deref().filter(|i| !i.is_empty()) {
+        let minutes = interval
+            .strip_prefix("PT")
+            .and_then(|rest| {
+                if let Some(h) = rest.strip_suffix('H') {
+                    h.parse::<u32>().ok().map(|h| h * 60)
+                } else {
+                    rest.strip_suffix('M')?.parse::<u32>().ok()
+                }
+            })
+            .ok_or_else(|| format!("repeats every {}", interval))?;
+        return match minutes {
+            m if m > 0 && m < 60 && 60 % m == 0 => Ok((format!("*/{} * * * *", m), command)),
+            m if m % 60 == 0 && 24 % (m / 60) == 0 => {
+                let step = m / 60;
+                let hours = if step == 1 {
+                    "*".to_string()
+                } else {
+                    format!("*/{}", step)
+                };
+                Ok((format!("{} {} * * *", minute, hours), command))
+            }
+            _ => Err(format!("repeats every {}", interval)),
+        };
+    }
+
+    match trigger.kind {
+        // TASK_TRIGGER_TIME
+        1 => {
+            let name = trigger.name.as_str();
+            Ok(one_shot_cron_entry(name, &command, start))
+        }
+        // TASK_TRIGGER_DAILY
+        2 => match trigger.days_interval.unwrap_or(1) {
+            1 => Ok((format!("{} {} * * *", minute, hour), command)),
+            n => Err(format!("runs every {} days", n)),
+        },
+        // TASK_TRIGGER_WEEKLY
+        3 => match trigger.days_of_week.map(|d| bits(d, 7, 0)) {
+            Some(days) if !days.is_empty() => {
+                Ok((format!("{} {} * * {}", minute, hour, days), command))
+            }
+            _ => Err("has no weekdays".into()),
+        },
+        // TASK_TRIGGER_MONTHLY
+        4 => {
+            let days = trigger
+                .days_of_month
+                .map(|d| bits(d, 31, 1))
+                .unwrap_or_default();
+            if days.is_empty() {
+                return Err("has no days of the month".into());
+            }
+            let months = match trigger.months.map(|m| bits(m, 12, 1)) {
+                Some(m) if !m.is_empty() && m.split(',').count() < 12 => m,
+                _ => "*".into(),
+            };
+            Ok((
+                format!("{} {} {} {} *", minute, hour, days, months),
+                command,
+            ))
+        }
+        5 => Err("runs on a weekday of the month".into()),
+        6 => Err("runs when the computer is idle".into()),
+        9 => Err("runs at logon".into()),
+        kind => Err(format!("has a trigger of type {}", kind)),
+    }
+}
+
+/// The AUI-managed schedule as crontab text, `# AUI:<name>` markers and
+/// all, for auditing or pasting into another machine's crontab. On
+/// macOS/Linux these are the crontab lines themselves plus queued `at`
+/// jobs written as the self-removing one-shot lines cron would use; on
+/// Windows each task's triggers are translated, and those cron can't
+/// express are listed as comments. Disabled tasks are commented out.
+#[tauri::command]
+fn export_crontab_fragment() -> Result<String, String> {
+    let mut lines = vec![format!(
+        "# AUI schedule exported {}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    )];
+
+    #[cfg(target_os = "windows")]
+    {
+        let json = task_service_script(
+            "$out = @(); try { $s.GetFolder('\\AUI').GetTasks(1) | ForEach-Object { \
+             $t = $_; $a = @($t.Definition.Actions)[0]; \
+             foreach ($tr in $t.Definition.Triggers) { $out += [pscustomobject]@{ \
+             name = $t.Name; enabled = [bool]($t.Enabled -and $tr.Enabled); \
+             command = ('\"' + $a.Path + '\" ' + $a.Arguments).Trim(); \
+             kind = [int]$tr.Type; start = $tr.StartBoundary; \
+             interval = $tr.Repetition.Interval; days_interval = $tr.DaysInterval; \
+             days_of_week = $tr.DaysOfWeek; days_of_month = $tr.DaysOfMonth; \
+             months = $tr.MonthsOfYear } } } } catch { }; \
+             ConvertTo-Json -InputObject @($out) -Compress",
+        )?;
+        let triggers: Vec<WindowsTrigger> = if json.is_empty() {
+            Vec::new()
+        } else {
+            serde_json::from_str(&json)
+                .map_err(|e| format!("Unexpected Task Scheduler output: {}", e))?
+        };
+        for trigger in &triggers {
+            match windows_trigger_cron(trigger) {
+                Ok((schedule, command)) => lines.push(format!(
+                    "{}{} {} # AUI:{}",
+                    if trigger.enabled { "" } else { "#" },
+                    schedule,
+                    command,
+                    trigger.name
+                )),
+                Err(reason) => lines.push(format!(
+                    "# {} {}, which cron can't express",
+                    trigger.name, reason
+                )),
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let now = chrono::Local::now().naive_local();
+        lines.extend(
+            read_crontab()?
+                .lines()
+                .filter(|line| parse_cron_task(line, now).is_some())
+                .map(|line| line.trim().to_string()),
+        );
+        for (_, when, job) in aui_at_jobs() {
+            let Some((command, name)) = job.rsplit_once("# AUI:") else {
+                continue;
+            };
+            let Ok(run_at) = chrono::NaiveDateTime::parse_from_str(&when, "%a %b %e %H:%M:%S %Y")
+            else {
+                continue;
+            };
+            let name = name.trim();
+            let (schedule, command) = one_shot_cron_entry(name, command.trim(), run_at);
+            lines.push(format!("{} {} # AUI:{}", schedule, command, name));
+        }
+    }
+
+    Ok(format!("{}\n", lines.join("\n")))
+}
+
+/// How a task was created, as recorded alongside its crontab entry: the
+/// original preset, cron expression or run time rather than the cron line
+/// it became. `None` for tasks created before this was recorded, and always
+/// on Windows, where Task Scheduler keeps the definition itself.
+#[tauri::command]
+fn get_task_metadata(task_name: String) -> Result<Option<TaskMetadata>, String> {
+    validate_task_name(&task_name)?;
+    #[cfg(target_os = "windows")]
+    {
+        let _ = task_name;
+        Ok(None)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Ok(scheduled_tasks()?
+            .into_iter()
+            .find(|t| t.task_name == task_name)
+            .and_then(|t| t.metadata))
+    }
+}
+
+/// Deletes a scheduled task by name.
+/// On Windows, removes from Task Scheduler. On macOS/Linux, removes from crontab.
+/// The wrapper scripts AUI generated for the task go with it (see
+/// `remove_task_wrappers`), as one may hold an env profile's secrets.
+#[tauri::command]
+fn delete_scheduled_task(app: tauri::AppHandle, task_name: String) -> Result<String, String> {
+    validate_task_name(&task_name)?;
+    let message = unschedule_task(&task_name)?;
+    remove_task_wrappers(&app, &task_name, &[]);
+    Ok(message)
+}
+
+/// The suffixes of the wrappers a task can have in `wrapper_dir`: none for
+/// `node_script`'s, then `env_wrapper`, `timing_wrapper`,
+/// `completion_wrapper`, `email_wrapper`, `overlap_wrapper` and
+/// `one_shot_wrapper`.
+const TASK_WRAPPER_SUFFIXES: [&str; 7] = [
+    "", "-env", "-timing", "-webhook", "-email", "-lock", "-once",
+];
+
+/// Removes the wrappers `task_name` has in `wrapper_dir`, except the paths
+/// in `keep`. Best effort: a wrapper left behind doesn't run on its own.
+fn remove_task_wrappers(app: &tauri::AppHandle, task_name: &str, keep: &[String]) {
+    let Ok(dir) = wrapper_dir_path(app) else {
+        return;
+    };
+    let file = task_file_stem(task_name);
+    for suffix in TASK_WRAPPER_SUFFIXES {
+        for extension in ["sh", "ps1"] {
+            let wrapper = dir.join(format!("{}{}.{}", file, suffix, extension));
+            if !keep.contains(&wrapper.to_string_lossy().to_string()) {
+                let _ = std::fs::remove_file(wrapper);
+            }
+        }
+    }
+}
+
+/// Removes `task_name` from the scheduler, and on macOS/Linux its
+/// metadata, leaving its wrappers alone.
+fn unschedule_task(task_name: &str) -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let tn = format!("AUI\\{}", task_name);
+        let output = hidden_command("schtasks.exe")
+            .args(["/Delete", "/TN", &tn, "/F"])
+            .output()
+            .map_err(|e| format!("Failed to run schtasks: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("schtasks delete failed: {}", stderr));
+        }
+
+        Ok(format!("Deleted scheduled task: {}", tn))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let marker = format!("# AUI:{}", task_name);
+
+        modify_crontab(|existing| {
+            let filtered: Vec<&str> = existing
+                .lines()
+                .filter(|line| !line.trim_end().ends_with(&marker))
+                .collect();
+            with_trailing_newline(&filtered.join("\n"))
+        })?;
+
+        // One-shot tasks may live in the at queue instead of the crontab
+        let at_jobs = if previewing_crontab() {
+            Vec::new()
+        } else {
+            aui_at_jobs()
+        };
+        for (id, _, job) in at_jobs {
+            if job.ends_with(&marker) {
+                let _ = StdCommand::new("atrm").arg(&id).output();
+            }
+        }
+        if !previewing_crontab() {
+            remove_task_metadata(task_name);
+        }
+
+        Ok(format!("Deleted cron job: AUI:{}", task_name))
+    }
+}
+
+/// How to schedule a task: a cron expression, a one-shot `run_at`, or a
+/// `create_scheduled_task` preset (`repeat` + `start_time` [+ `start_date`]).
+#[derive(serde::Deserialize, serde::Serialize, Clone, Default)]
+struct ScheduleSpec {
+    #[serde(default)]
+    cron: Option<String>,
+    #[serde(default)]
+    run_at: Option<String>,
+    #[serde(default)]
+    repeat: Option<String>,
+    #[serde(default)]
+    start_time: Option<String>,
+    #[serde(default)]
+    start_date: Option<String>,
+    #[serde(default)]
+    shell: Option<String>,
+}
+
+/// Creates a task from a `ScheduleSpec` through the matching command.
+fn create_task_from_spec(
+    app: &tauri::AppHandle,
+    task_name: &str,
+    script_path: &str,
+    spec: &ScheduleSpec,
+) -> Result<CreateTaskResult, String> {
+    validate_task_name(task_name)?;
+    let non_empty = |v: &Option<String>| {
+        v.as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    if let Some(cron) = non_empty(&spec.cron) {
+        return schedule_timed(
+            app,
+            task_name,
+            script_path,
+            spec.shell.clone(),
+            |script, shell| create_cron_task(task_name.to_string(), script, cron, shell),
+        );
+    }
+    if let Some(run_at) = non_empty(&spec.run_at) {
+        return create_one_shot_task(
+            app.clone(),
+            task_name.to_string(),
+            script_path.to_string(),
+            run_at,
+            spec.shell.clone(),
+        );
+    }
+    let start_time = non_empty(&spec.start_time)
+        .ok_or("Schedule needs a cron expression, run_at, or start_time")?;
+    schedule_timed(
+        app,
+        task_name,
+        script_path,
+        spec.shell.clone(),
+        |script, shell| {
+            create_preset_task(
+                app,
+                task_name.to_string(),
+                script,
+                start_time,
+                non_empty(&spec.start_date).unwrap_or_default(),
+                non_empty(&spec.repeat).unwrap_or_else(|| "daily".to_string()),
+                shell,
+                None,
+                None,
+                None,
+                None,
+            )
+        },
+    )
+}
+
+/// One task for `create_scheduled_tasks_batch`.
+#[derive(serde::Deserialize)]
+struct BatchTaskSpec {
+    task_name: String,
+    script_path: String,
+    schedule: ScheduleSpec,
+}
+
+/// How one spec of a batch went: `result` when the task was created,
+/// otherwise `error`. `rolled_back` marks tasks deleted again because a
+/// later spec failed.
+#[derive(serde::Serialize)]
+struct BatchTaskOutcome {
+    task_name: String,
+    result: Option<CreateTaskResult>,
+    error: Option<String>,
+    rolled_back: bool,
+}
+
+/// Outcome of `create_scheduled_tasks_batch`, one entry per spec in order.
+#[derive(serde::Serialize)]
+struct BatchCreateResult {
+    created: usize,
+    failed: usize,
+    rolled_back: bool,
+    outcomes: Vec<BatchTaskOutcome>,
+}
+
+/// Creates many tasks at once, e.g. when provisioning a machine. Each spec
+/// is created on its own, so a bad entry doesn't stop the rest, unless
+/// `rollback_on_error` is set: then the first failure deletes the tasks
+/// the batch already created and the remaining specs are skipped. Names
+/// that already exist, or repeat within the batch, are refused rather than
+/// replaced, so a rollback never removes a task the batch didn't create.
+#[tauri::command]
+fn create_scheduled_tasks_batch(
+    app: tauri::AppHandle,
+    specs: Vec<BatchTaskSpec>,
+    rollback_on_error: Option<bool>,
+) -> Result<BatchCreateResult, String> {
+    let rollback_on_error = rollback_on_error.unwrap_or(false);
+    let mut taken: std::collections::HashSet<String> = scheduled_tasks()?
+        .into_iter()
+        .map(|t| t.task_name)
+        .collect();
+
+    let mut outcomes: Vec<BatchTaskOutcome> = Vec::with_capacity(specs.len());
+    let mut aborted = false;
+    for spec in specs {
+        let task_name = spec.task_name.trim().to_string();
+        if aborted {
+            outcomes.push(BatchTaskOutcome {
+                task_name,
+                result: None,
+                error: Some("Skipped: an earlier task in the batch failed".into()),
+                rolled_back: false,
+            });
+            continue;
+        }
+
+        let created = if let Err(e) = validate_task_name(&task_name) {
+            Err(e)
+        } else if !taken.insert(task_name.clone()) {
+            Err(format!(
+                "Conflict: task '{}' already exists or repeats in the batch",
+                task_name
+            ))
+        } else {
+            create_task_from_spec(&app, &task_name, &spec.script_path, &spec.schedule)
+        };
+        let failed = created.is_err();
+        outcomes.push(match created {
+            Ok(result) => BatchTaskOutcome {
+                task_name,
+                result: Some(result),
+                error: None,
+                rolled_back: false,
+            },
+            Err(e) => BatchTaskOutcome {
+                task_name,
+                result: None,
+                error: Some(e),
+                rolled_back: false,
+            },
+        });
+
+        if failed && rollback_on_error {
+            aborted = true;
+            for outcome in outcomes.iter_mut().filter(|o| o.result.is_some()) {
+                match delete_scheduled_task(app.clone(), outcome.task_name.clone()) {
+                    Ok(_) => outcome.rolled_back = true,
+                    Err(e) => {
+                        outcome.error = Some(format!("Rollback failed: {}", e));
+                    }
+                }
+            }
+        }
+    }
+
+    let created = outcomes
+        .iter()
+        .filter(|o| o.result.is_some() && !o.rolled_back)
+        .count();
+    Ok(BatchCreateResult {
+        created,
+        failed: outcomes.iter().filter(|o| o.result.is_none()).count(),
+        rolled_back: aborted,
+        outcomes,
+    })
+}
+
+/// A scheduling change for `preview_crontab_change`: `action` is "create",
+/// "update" (delete, then create) or "delete". Creating needs `script_path`
+/// and `schedule`.
+#[derive(serde::Deserialize)]
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+struct CrontabOperation {
+    action: String,
+    task_name: String,
+    #[serde(default)]
+    script_path: Option<String>,
+    #[serde(default)]
+    schedule: Option<ScheduleSpec>,
+}
+
+/// Renders the changes from `before` to `after` as a unified diff with
+/// three lines of context. Empty when the texts are the same.
+#[cfg(not(target_os = "windows"))]
+fn unified_diff(before: &str, after: &str, from_label: &str, to_label: &str) -> String {
+    const CONTEXT: usize = 3;
+
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+
+    // Longest common subsequence table, filled from the end
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Edit script as (tag, old index, new index); tag is ' ', '-' or '+'
+    let mut ops: Vec<(char, usize, usize)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((' ', i, j));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', i, j));
+            i += 1;
+        } else {
+            ops.push(('+', i, j));
+            j += 1;
+        }
+    }
+
+    let changes: Vec<usize> = (0..ops.len()).filter(|k| ops[*k].0 != ' ').collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    let mut diff = format!("--- {}\n+++ {}\n", from_label, to_label);
+    let mut next = 0;
+    while next < changes.len() {
+        // Grow the hunk while the next change is within reach of its context
+        let start = changes[next].saturating_sub(CONTEXT);
+        let mut last = changes[next];
+        next += 1;
+        while next < changes.len() && changes[next] <= last + 2 * CONTEXT {
+            last = changes[next];
+            next += 1;
+        }
+        let end = (last + CONTEXT + 1).min(ops.len());
+        let hunk = &ops[start..end];
+
+        let old_count = hunk.iter().filter(|op| op.0 != '+').count();
+        let new_count = hunk.iter().filter(|op| op.0 != '-').count();
+        let range = |first: usize, count: usize| {
+            // Empty ranges are numbered by the line before them
+            let first = if count == 0 { first } else { first + 1 };
+            format!("{},{}", first, count)
+        };
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range(hunk[0].1, old_count),
+            range(hunk[0].2, new_count)
+        ));
+        for (tag, i, j) in hunk {
+            let line = if *tag == '+' { new[*j] } else { old[*i] };
+            diff.push_str(&format!("{}{}\n", tag, line));
+        }
+    }
+    diff
+}
+
+/// Shows what a create, update or delete would do to the crontab without
+/// applying it, as a unified diff of the current crontab against the
+/// result. The diff is empty when the crontab wouldn't change, e.g. for a
+/// one-shot task that goes to `at`.
+#[tauri::command]
+fn preview_crontab_change(
+    app: tauri::AppHandle,
+    operation: CrontabOperation,
+) -> Result<String, String> {
+    validate_task_name(&operation.task_name)?;
+    #[cfg(not(target_os = "windows"))]
+    {
+        let action = operation.action.trim().to_lowercase();
+        if !matches!(action.as_str(), "create" | "update" | "delete") {
+            return Err(format!(
+                "Unknown operation '{}': expected create, update or delete",
+                operation.action
+            ));
+        }
+
+        let before = read_crontab()?;
+        CRONTAB_PREVIEW.with(|p| *p.borrow_mut() = Some(before.clone()));
+        let applied = (|| {
+            if action != "create" {
+                unschedule_task(&operation.task_name)?;
+            }
+            if action != "delete" {
+                let script_path = operation
+                    .script_path
+                    .as_deref()
+                    .ok_or("Creating a task needs script_path")?;
+                let schedule = operation
+                    .schedule
+                    .as_ref()
+                    .ok_or("Creating a task needs a schedule")?;
+                create_task_from_spec(&app, &operation.task_name, script_path, schedule)?;
+            }
+            Ok::<(), String>(())
+        })();
+        let after = CRONTAB_PREVIEW.with(|p| p.borrow_mut().take());
+        applied?;
+
+        Ok(unified_diff(
+            &before,
+            &after.unwrap_or_default(),
+            "crontab (current)",
+            &format!("crontab (after {} {})", action, operation.task_name),
+        ))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = (app, operation);
+        Err("Unsupported: there is no crontab on Windows".into())
+    }
+}
+
+/// Task Scheduler result codes worth explaining. Anything else is shown as
+/// its hex code.
+const TASK_RESULTS: [(u32, &str); 22] = [
+    (0x0, "The task completed successfully"),
+    (
+        0x1,
+        "The program exited with code 1 (incorrect or unknown function)",
+    ),
+    (0x2, "The file specified was not found"),
+    (0xA, "The environment is incorrect"),
+    (
+        0x41300,
+        "The task is ready to run at its next scheduled time",
+    ),
+    (0x41301, "The task is currently running"),
+    (0x41302, "The task is disabled"),
+    (0x41303, "The task has not yet run"),
+    (0x41304, "There are no more runs scheduled for this task"),
+    (0x41305, "One or more of the task's properties are not set"),
+    (
+        0x41306,
+        "The last run of the task was terminated by the user",
+    ),
+    (
+        0x41307,
+        "The task has no triggers, or its triggers are disabled or expired",
+    ),
+    (0x41308, "Event triggers don't have set run times"),
+    (
+        0x8004130F,
+        "The task's credentials are missing or corrupted",
+    ),
+    (0x80041315, "The Task Scheduler service is not running"),
+    (0x8004131F, "An instance of the task is already running"),
+    (0x80070002, "The program or script was not found"),
+    (0x80070005, "Access is denied"),
+    (0x800704DD, "The task's user is not logged on"),
+    (
+        0x800710E0,
+        "The operator or administrator refused the request",
+    ),
+    (
+        0xC000013A,
+        "The program was terminated by Ctrl+C or by closing its window",
+    ),
+    (
+        0xC0000142,
+        "The program failed to initialize (DLL initialization failed)",
+    ),
+];
+
+/// Parses a Task Scheduler result code: decimal as schtasks prints it
+/// (negative for the 0x8... HRESULTs), or hex with a `0x` prefix.
+fn parse_task_result(code: &str) -> Option<u32> {
+    let code = code.trim();
+    match code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => code
+            .parse::<u32>()
+            .ok()
+            .or_else(|| code.parse::<i32>().ok().map(|c| c as u32)),
+    }
+}
+
+/// What a Task Scheduler result code means, or `None` when it isn't one.
+fn task_result_text(code: &str) -> Option<String> {
+    let code = parse_task_result(code)?;
+    Some(
+        TASK_RESULTS
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, text)| text.to_string())
+            .unwrap_or_else(|| format!("0x{:X}", code)),
+    )
+}
+
+/// Decodes a Task Scheduler last-run result (`0x41301`, `267009`, `-2147...`)
+/// into a message for the handful of common codes, falling back to the
+/// code in hex.
+#[tauri::command]
+fn decode_task_result(code: String) -> Result<String, String> {
+    task_result_text(&code).ok_or_else(|| format!("Invalid task result code '{}'", code))
+}
+
+/// An AUI scheduled task. `node_id` is set for tasks created from a graph
+/// node by `schedule_node`. `schedule_kind` is the matching
+/// `create_scheduled_task` preset ("once", "hourly", "daily", ...), or
+/// "cron" / the scheduler's own trigger type when there is none, and
+/// `next_run` is local time as `YYYY-MM-DD HH:MM` when known.
+/// `last_result` is the exit code Task Scheduler recorded for the last run
+/// (cron keeps no such record), and `last_result_text` what it means (see
+/// `decode_task_result`). `priority` is the Task Scheduler priority (0-10)
+/// or the task's `nice` level, when it has one. `metadata` is what the task
+/// was created from, when AUI recorded it (macOS/Linux, see `TaskMetadata`).
+/// `timezone` is the zone `next_run` and the schedule are in. `backend` is
+/// where the task lives: "schtasks", "cron", "at" or "systemd" (see
+/// `list_all_scheduled_tasks`).
+#[derive(serde::Serialize, Clone)]
+struct ScheduledTask {
+    backend: String,
+    task_name: String,
+    schedule_spec: String,
+    schedule_kind: String,
+    command: String,
+    script_path: String,
+    node_id: Option<String>,
+    enabled: bool,
+    next_run: Option<String>,
+    last_result: Option<String>,
+    last_result_text: Option<String>,
+    priority: Option<i32>,
+    metadata: Option<TaskMetadata>,
+    timezone: Option<ScheduleTimezone>,
+}
+
+/// A saved graph as far as scheduling is concerned: nodes that may carry a
+/// script to run and a schedule for it. Other node fields are ignored.
+#[derive(serde::Deserialize)]
+struct Graph {
+    #[serde(default)]
+    nodes: Vec<GraphNode>,
+}
+
+#[derive(serde::Deserialize, Clone)]
+struct GraphNode {
+    id: String,
+    #[serde(default, alias = "scriptPath")]
+    script_path: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    #[serde(default)]
+    schedule: Option<ScheduleSpec>,
+}
+
+/// Resolves a graph name to its file: `<app_data_dir>/graphs/<name>.json`,
+/// or the given path when it already names a `.json` file.
+fn graph_file(app: &tauri::AppHandle, graph_name: &str) -> Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+
+    let graph_name = graph_name.trim();
+    if graph_name.is_empty() {
+        return Err("Graph name is empty".into());
+    }
+    if graph_name.ends_with(".json") || graph_name.contains(['/', '\\']) {
+        return Ok(std::path::PathBuf::from(expand_user_path(graph_name)));
+    }
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))?;
+    Ok(dir.join("graphs").join(format!("{}.json", graph_name)))
+}
+
+fn load_graph(path: &std::path::Path) -> Result<Graph, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read graph {}: {}", path.display(), e))?;
+    serde_json::from_str(&raw).map_err(|e| format!("Invalid graph {}: {}", path.display(), e))
+}
+
+/// A node or edge present in both graphs whose fields differ. `fields`
+/// names the top-level fields that changed.
+#[derive(serde::Serialize)]
+struct GraphItemChange {
+    id: String,
+    fields: Vec<String>,
+    before: serde_json::Value,
+    after: serde_json::Value,
+}
+
+/// What changed from one graph to another, node by node and edge by edge.
+#[derive(serde::Serialize, Default)]
+struct GraphDiff {
+    added_nodes: Vec<serde_json::Value>,
+    removed_nodes: Vec<serde_json::Value>,
+    modified_nodes: Vec<GraphItemChange>,
+    added_edges: Vec<serde_json::Value>,
+    removed_edges: Vec<serde_json::Value>,
+    modified_edges: Vec<GraphItemChange>,
+}
+
+/// The items of a graph's `key` array by id, in file order. Edges without
+/// an `id` are identified by `source->target`.
+fn graph_items(
+    graph: &serde_json::Value,
+    key: &str,
+    label: &str,
+) -> Result<Vec<(String, serde_json::Value)>, String> {
+    let Some(items) = graph.get(key) else {
+        return Ok(Vec::new());
+    };
+    let items = items
+        .as_array()
+        .ok_or_else(|| format!("Invalid {} graph: '{}' is not a list", label, key))?;
+    let mut seen = std::collections::HashSet::new();
+    let mut keyed = Vec::new();
+    for item in items {
+        let field = |name: &str| item.get(name).and_then(|v| v.as_str());
+        let id = match (field("id"), field("source"), field("target")) {
+            (Some(id), _, _) => id.to_string(),
+            (None, Some(source), Some(target)) if key == "edges" => {
+                format!("{}->{}", source, target)
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid {} graph: an item of '{}' has no id",
+                    label, key
+                ))
+            }
+        };
+        if !seen.insert(id.clone()) {
+            return Err(format!(
+                "Invalid {} graph: duplicate id '{}' in '{}'",
+                label, id, key
+            ));
+        }
+        keyed.push((id, item.clone()));
+    }
+    Ok(keyed)
+}
+
+/// Compares two graphs (as saved JSON, e.g. a backup and the current file)
+/// by node and edge id, returning what was added, removed and modified
+/// rather than a text diff.
+#[tauri::command]
+fn diff_graphs(json_a: String, json_b: String) -> Result<GraphDiff, String> {
+    let parse = |raw: &str, label: &str| -> Result<serde_json::Value, String> {
+        let graph: serde_json::Value =
+            serde_json::from_str(raw).map_err(|e| format!("Invalid {} graph: {}", label, e))?;
+        if !graph.is_object() {
+            return Err(format!("Invalid {} graph: not a JSON object", label));
+        }
+        Ok(graph)
+    };
+    let a = parse(&json_a, "first")?;
+    let b = parse(&json_b, "second")?;
+
+    let mut diff = GraphDiff::default();
+    for key in ["nodes", "edges"] {
+        let before = graph_items(&a, key, "first")?;
+        let after = graph_items(&b, key, "second")?;
+        let before_ids: HashMap<&str, &serde_json::Value> =
+            before.iter().map(|(id, v)| (id.as_str(), v)).collect();
+        let after_ids: std::collections::HashSet<&str> =
+            after.iter().map(|(id, _)| id.as_str()).collect();
+
+        let mut added = Vec::new();
+        let mut modified = Vec::new();
+        for (id, item) in &after {
+            let Some(old) = before_ids.get(id.as_str()) else {
+                added.push(item.clone());
+                continue;
+            };
+            if *old == item {
+                continue;
+            }
+            let empty = serde_json::Map::new();
+            let old_fields = old.as_object().unwrap_or(&empty);
+            let new_fields = item.as_object().unwrap_or(&empty);
+            let mut fields: Vec<String> = old_fields
+                .keys()
+                .chain(new_fields.keys())
+                .filter(|k| old_fields.get(*k) != new_fields.get(*k))
+                .cloned()
+                .collect();
+            fields.sort();
+            fields.dedup();
+            modified.push(GraphItemChange {
+                id: id.clone(),
+                fields,
+                before: (*old).clone(),
+                after: item.clone(),
+            });
+        }
+        let removed: Vec<serde_json::Value> = before
+            .iter()
+            .filter(|(id, _)| !after_ids.contains(id.as_str()))
+            .map(|(_, v)| v.clone())
+            .collect();
+
+        if key == "nodes" {
+            diff.added_nodes = added;
+            diff.removed_nodes = removed;
+            diff.modified_nodes = modified;
+        } else {
+            diff.added_edges = added;
+            diff.removed_edges = removed;
+            diff.modified_edges = modified;
+        }
+    }
+    Ok(diff)
+}
+
+/// Task name for a graph node, `<graph>__<node id>`, restricted to
+/// characters both schtasks and the cron marker accept.
+fn node_task_name(graph_path: &std::path::Path, node_id: &str) -> String {
+    let graph = graph_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!("{}__{}", graph, node_id)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// The script a node's task runs. Nodes with args or env get a small
+/// wrapper script under `<app_data_dir>/schedules` that sets them up.
+fn node_script(
+    app: &tauri::AppHandle,
+    graph_path: &std::path::Path,
+    node: &GraphNode,
+    task_name: &str,
+) -> Result<String, String> {
+    let script = node
+        .script_path
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| format!("Node '{}' has no script to schedule", node.id))?;
+    let mut script = expand_user_path(script);
+    if std::path::Path::new(&script).is_relative() {
+        if let Some(dir) = graph_path.parent() {
+            script = expand_user_path(&dir.join(&script).to_string_lossy());
+        }
+    }
+    if !std::path::Path::new(&script).is_file() {
+        return Err(format!("Script not found: {}", script));
+    }
+    if node.args.is_empty() && node.env.is_empty() {
+        return Ok(script);
+    }
+
+    let dir = wrapper_dir(app)?;
+    let file = task_file_stem(task_name);
+    let mut env: Vec<(&String, &String)> = node.env.iter().collect();
+    env.sort();
+    if let Some((key, _)) = env.iter().find(|(k, _)| !valid_env_name(k)) {
+        return Err(format!("Invalid environment variable name '{}'", key));
+    }
+
+    #[cfg(target_os = "windows")]
+    let (wrapper, contents) = {
+        let quote = |v: &str| format!("'{}'", v.replace('\'', "''"));
+        let mut lines: Vec<String> = env
+            .iter()
+            .map(|(k, v)| format!("$env:{} = {}", k, quote(v)))
+            .collect();
+        let interpreter = interpreter_for(&script);
+        let invocation = if interpreter.program.to_lowercase().starts_with("powershell") {
+            quote(&script)
+        } else {
+            let mut parts = vec![quote(&interpreter.program)];
+            parts.extend(interpreter.args.iter().map(|a| quote(a)));
+            parts.push(quote(&script));
+            parts.join(" ")
+        };
+        let args: Vec<String> = node.args.iter().map(|a| quote(a)).collect();
+        lines.push(
+            format!("& {} {}", invocation, args.join(" "))
+                .trim_end()
+                .to_string(),
+        );
+        lines.push("exit $LASTEXITCODE".to_string());
+        (
+            dir.join(format!("{}.ps1", file)),
+            format!("\u{feff}{}\r\n", lines.join("\r\n")),
+        )
+    };
+    #[cfg(not(target_os = "windows"))]
+    let (wrapper, contents) = {
+        let mut lines = vec!["#!/bin/sh".to_string()];
+        lines.extend(
+            env.iter()
+                .map(|(k, v)| format!("export {}={}", k, shell_quote(v))),
+        );
+        let args: Vec<String> = node.args.iter().map(|a| shell_quote(a)).collect();
+        lines.push(
+            format!(
+                "exec {} {}",
+                unix_script_command(&script, None),
+                args.join(" ")
+            )
+            .trim_end()
+            .to_string(),
+        );
+        (
+            dir.join(format!("{}.sh", file)),
+            format!("{}\n", lines.join("\n")),
+        )
+    };
+
+    std::fs::write(&wrapper, contents)
+        .map_err(|e| format!("Failed to write {}: {}", wrapper.display(), e))?;
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&wrapper, std::fs::Permissions::from_mode(0o755));
+    }
+    Ok(wrapper.to_string_lossy().to_string())
+}
+
+/// The wrapper `create_scheduled_task` schedules for a task with a
+/// completion webhook, `<app_data_dir>/schedules/<task>-webhook.sh` (`.ps1`
+/// on Windows): it runs the script, then POSTs `{task_name, exit_code,
+/// timestamp}` as JSON to `webhook` with curl, and exits with the script's
+/// exit code. A failed POST doesn't fail the task; it is appended to the
+/// task's log (see `task_log_file`) instead.
+fn completion_wrapper(
+    app: &tauri::AppHandle,
+    task_name: &str,
+    script_path: &str,
+    shell: Option<String>,
+    webhook: &str,
+) -> Result<Wrapper, String> {
+    let webhook = parse_http_url(webhook)?.to_string();
+    let shell = resolve_shell(shell)?;
+    let script = expand_user_path(script_path);
+    let (log, dir, file) = wrapper_paths(app, task_name)?;
+
+    #[cfg(target_os = "windows")]
+    let (wrapper, contents) = {
+        let _ = shell;
+        let quote = |v: &str| format!("'{}'", v.replace('\'', "''"));
+        let invocation = powershell_invocation(&script);
+        let lines = [
+            format!("& {}", invocation),
+            "$code = $LASTEXITCODE".to_string(),
+            format!(
+                "$payload = [ordered]@{{ task_name = {}; exit_code = $code; timestamp = (Get-Date).ToUniversalTime().ToString('yyyy-MM-ddTHH:mm:ssZ') }} | ConvertTo-Json -Compress",
+                quote(task_name)
+            ),
+            format!(
+                "$out = $payload | curl.exe -sS -f -m 30 -X POST -H 'Content-Type: application/json' --data-binary '@-' {} 2>&1",
+                quote(&webhook)
+            ),
+            format!(
+                "if ($LASTEXITCODE -ne 0) {{ Add-Content -LiteralPath {} -Value \"$((Get-Date).ToString('s')) completion webhook to {} failed: $out\" }}",
+                quote(&log),
+                webhook.replace('"', "`\"").replace('$', "`$")
+            ),
+            "exit $code".to_string(),
+        ];
+        (
+            dir.join(format!("{}-webhook.ps1", file)),
+            format!("\u{feff}{}\r\n", lines.join("\r\n")),
+        )
+    };
+    #[cfg(not(target_os = "windows"))]
+    let (wrapper, contents) = {
+        let name = serde_json::to_string(task_name).map_err(|e| e.to_string())?;
+        let lines = [
+            "#!/bin/sh".to_string(),
+            unix_script_command(&script, shell.as_deref()),
+            "code=$?".to_string(),
+            format!(
+                "payload=$(printf '{{\"task_name\":%s,\"exit_code\":%s,\"timestamp\":\"%s\"}}' {} \"$code\" \"$(date -u +%Y-%m-%dT%H:%M:%SZ)\")",
+                shell_quote(&name)
+            ),
+            format!(
+                "if ! out=$(curl -sS -f -m 30 -X POST -H 'Content-Type: application/json' --data \"$payload\" {} 2>&1); then",
+                shell_quote(&webhook)
+            ),
+            format!(
+                "  printf '%s completion webhook to %s failed: %s\\n' \"$(date +%Y-%m-%dT%H:%M:%S)\" {} \"$out\" >> {}",
+                shell_quote(&webhook),
+                shell_quote(&log)
+            ),
+            "fi".to_string(),
+            "exit $code".to_string(),
+        ];
+        (
+            dir.join(format!("{}-webhook.sh", file)),
+            format!("{}\n", lines.join("\n")),
+        )
+    };
+
+    Ok(Wrapper {
+        path: wrapper,
+        contents,
+        private: false,
+    })
+}
+
+/// How to reach an SMTP server. `security` is "starttls" (the default,
+/// port 587), "tls" for implicit TLS (port 465) or "none" (port 25);
+/// `username`/`password` are only sent when given.
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+struct SmtpConfig {
+    host: String,
+    #[serde(default)]
+    port: Option<u16>,
+    #[serde(default)]
+    security: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+    from: String,
+}
+
+/// curl's exit code when the SMTP server rejected the login.
+const CURL_LOGIN_DENIED: i32 = 67;
+
+/// A bare `user@host` address, without display name or anything that could
+/// break out of a header or SMTP command.
+fn valid_email_address(address: &str) -> bool {
+    let mut parts = address.split('@');
+    let (Some(local), Some(domain), None) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !address
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || "<>()[],;:\"\\".contains(c))
+}
+
+/// Checks an SMTP config and renders it as a curl config file (`--config`),
+/// which keeps the password out of the process list.
+fn smtp_curl_config(config: &SmtpConfig) -> Result<String, String> {
+    let host = config.host.trim();
+    if host.is_empty()
+        || !host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':' | '[' | ']'))
+    {
+        return Err(format!("Invalid SMTP host '{}'", config.host));
+    }
+    if !valid_email_address(config.from.trim()) {
+        return Err(format!("Invalid sender address '{}'", config.from));
+    }
+    let (scheme, default_port, require_tls) = match config
+        .security
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or("starttls")
+    {
+        "starttls" => ("smtp", 587, true),
+        "tls" => ("smtps", 465, false),
+        "none" => ("smtp", 25, false),
+        other => {
+            return Err(format!(
+                "Unsupported SMTP security '{}': expected starttls, tls or none",
+                other
+            ))
+        }
+    };
+
+    let quote = |value: &str| -> Result<String, String> {
+        if value.chars().any(char::is_control) {
+            return Err("SMTP settings can't contain control characters".into());
+        }
+        Ok(format!(
+            "\"{}\"",
+            value.replace('\\', "\\\\").replace('"', "\\\"")
+        ))
+    };
+    let mut lines = vec![
+        format!(
+            "url = {}",
+            quote(&format!(
+                "{}://{}:{}",
+                scheme,
+                host,
+                config.port.unwrap_or(default_port)
+            ))?
+        ),
+        format!("mail-from = {}", quote(config.from.trim())?),
+        "connect-timeout = 15".to_string(),
+        "max-time = 120".to_string(),
+    ];
+    if require_tls {
+        lines.push("ssl-reqd".into());
+    }
+    if let Some(user) = config.username.as_deref().filter(|u| !u.is_empty()) {
+        let password = config.password.as_deref().unwrap_or_default();
+        lines.push(format!(
+            "user = {}",
+            quote(&format!("{}:{}", user, password))?
+        ));
+    }
+    Ok(format!("{}\n", lines.join("\n")))
+}
+
+/// Turns curl's exit status for an SMTP transfer into a readable error.
+fn smtp_error(code: Option<i32>, stderr: &str) -> String {
+    let reason = match code {
+        Some(CURL_LOGIN_DENIED) => "the server rejected the username or password",
+        Some(6) => "the SMTP host could not be resolved",
+        Some(7) => "could not connect to the SMTP server",
+        Some(28) => "the SMTP server did not respond in time",
+        Some(64) => "the server does not support STARTTLS",
+        Some(35) | Some(58) | Some(60) => "the TLS handshake failed",
+        Some(55) | Some(56) => "the connection dropped while sending",
+        _ => "",
+    };
+    match (reason, stderr.trim()) {
+        ("", detail) => format!("Sending email failed: {}", detail),
+        (reason, "") => format!("Sending email failed: {}", reason),
+        (reason, detail) => format!("Sending email failed: {} ({})", reason, detail),
+    }
+}
+
+/// A header value as an RFC 2047 encoded word when it isn't plain ASCII.
+/// Control characters are refused, since a line break would end the header
+/// and start another. Addresses don't go through here: they can't be
+/// encoded, and `valid_email_address` already keeps them to one word.
+fn encode_header(value: &str) -> Result<String, String> {
+    if value.chars().any(char::is_control) {
+        return Err(format!(
+            "Email header '{}' can't contain control characters",
+            value.escape_default()
+        ));
+    }
+    if value.is_ascii() {
+        return Ok(value.to_string());
+    }
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in value.as_bytes().chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    Ok(format!("=?UTF-8?B?{}?=", encoded))
+}
+
+/// Sends an email through `smtp_config`, e.g. to check the settings before
+/// tasks rely on them. Files in `attachments` are attached base64-encoded.
+/// Connection, TLS and login failures come back as distinct errors.
+#[tauri::command(async)]
+fn send_email(
+    smtp_config: SmtpConfig,
+    to: Vec<String>,
+    subject: String,
+    body: String,
+    attachments: Option<Vec<String>>,
+) -> Result<String, String> {
+    use std::io::Write;
+
+    let config = smtp_curl_config(&smtp_config)?;
+    let to: Vec<String> = to.iter().map(|t| t.trim().to_string()).collect();
+    if to.is_empty() {
+        return Err("No recipients given".into());
+    }
+    if let Some(bad) = to.iter().find(|t| !valid_email_address(t)) {
+        return Err(format!("Invalid recipient address '{}'", bad));
+    }
+    if subject.chars().any(char::is_control) {
+        return Err("Subject can't contain control characters".into());
+    }
+    let subject = encode_header(&subject)?;
+    let attachments: Vec<String> = attachments
+        .unwrap_or_default()
+        .iter()
+        .map(|a| expand_user_path(a))
+        .collect();
+    if let Some(missing) = attachments
+        .iter()
+        .find(|a| !std::path::Path::new(a).is_file())
+    {
+        return Err(format!("Attachment not found: {}", missing));
+    }
+
+    throttle_fetch()?;
+    let mut command = curl_command();
+    command.args(["-sS", "--config", "-"]);
+    for recipient in &to {
+        command.args(["--mail-rcpt", recipient]);
+    }
+    command
+        .arg("-H")
+        .arg(format!("From: {}", smtp_config.from.trim()))
+        .arg("-H")
+        .arg(format!("To: {}", to.join(", ")))
+        .arg("-H")
+        .arg(format!("Subject: {}", subject))
+        .arg("--form-string")
+        .arg(format!("={}", body));
+    for attachment in &attachments {
+        command.arg("-F").arg(format!(
+            "=@\"{}\";encoder=base64",
+            attachment.replace('\\', "\\\\").replace('"', "\\\"")
+        ));
+    }
+
+    let output = command
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(config.as_bytes())?;
+            }
+            child.wait_with_output()
+        })
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(smtp_error(
+            output.status.code(),
+            &String::from_utf8_lossy(&output.stderr),
+        ));
+    }
+    Ok(format!("Sent email to {}", to.join(", ")))
+}
+
+/// Where the SMTP settings for task completion emails live, as a private
+/// curl config file (see `save_smtp_config`).
+fn smtp_config_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join("smtp.curlrc"))
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+/// Saves the SMTP settings that `completion_email` on
+/// `create_scheduled_task` sends through. Tasks read it on every run, so
+/// changing it applies to existing tasks too.
+#[tauri::command]
+fn save_smtp_config(app: tauri::AppHandle, smtp_config: SmtpConfig) -> Result<(), String> {
+    let config = smtp_curl_config(&smtp_config)?;
+    let path = smtp_config_path(&app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    write_private_file(&path, &config)
+}
+
+/// The wrapper `create_scheduled_task` runs for a completion email,
+/// `<app_data_dir>/schedules/<task>-email.sh` (`.ps1` on Windows). It runs
+/// the script, then mails its exit code to `to` through the settings saved
+/// with `save_smtp_config`, and exits with the script's code. A failed send
+/// is noted in the task log.
+fn email_wrapper(
+    app: &tauri::AppHandle,
+    task_name: &str,
+    script_path: &str,
+    shell: Option<String>,
+    to: &str,
+) -> Result<Wrapper, String> {
+    let to = to.trim();
+    if !valid_email_address(to) {
+        return Err(format!("Invalid recipient address '{}'", to));
+    }
+    let smtp = smtp_config_path(app)?;
+    let config = std::fs::read_to_string(&smtp)
+        .map_err(|_| "No SMTP settings saved; save them with save_smtp_config first".to_string())?;
+    let from = config
+        .lines()
+        .find_map(|l| l.strip_prefix("mail-from = "))
+        .map(|f| f.trim_matches('"').to_string())
+        .unwrap_or_default();
+    if !valid_email_address(&from) {
+        return Err(format!(
+            "Invalid sender address '{}' in {}; save the SMTP settings again",
+            from.escape_default(),
+            smtp.display()
+        ));
+    }
+    let smtp = smtp.to_string_lossy().to_string();
+    let shell = resolve_shell(shell)?;
+    let script = expand_user_path(script_path);
+    let (log, dir, file) = wrapper_paths(app, task_name)?;
+    let subject = encode_header(&format!("[AUI] {} finished", task_name))?;
+
+    #[cfg(target_os = "windows")]
+    let (wrapper, contents) = {
+        let _ = shell;
+        let quote = |v: &str| format!("'{}'", v.replace('\'', "''"));
+        // For values inside a double-quoted PowerShell string
+        let escape = |v: &str| v.replace('`', "``").replace('$', "`$").replace('"', "`\"");
+        let lines = [
+            format!("& {}", powershell_invocation(&script)),
+            "$code = $LASTEXITCODE".to_string(),
+            format!(
+                "$message = \"From: {}`r`nTo: {}`r`nSubject: {} (exit code $code)`r`n`r`nTask {} finished at $((Get-Date).ToString('s')) with exit code $code.`r`n\"",
+                escape(&from),
+                escape(to),
+                escape(&subject),
+                escape(task_name)
+            ),
+            format!(
+                "$out = $message | curl.exe -sS --config {} --mail-rcpt {} -T - 2>&1",
+                quote(&smtp),
+                quote(to)
+            ),
+            format!(
+                "if ($LASTEXITCODE -ne 0) {{ Add-Content -LiteralPath {} -Value \"$((Get-Date).ToString('s')) completion email to {} failed: $out\" }}",
+                quote(&log),
+                escape(to)
+            ),
+            "exit $code".to_string(),
+        ];
+        (
+            dir.join(format!("{}-email.ps1", file)),
+            format!("\u{feff}{}\r\n", lines.join("\r\n")),
+        )
+    };
+    #[cfg(not(target_os = "windows"))]
+    let (wrapper, contents) = {
+        let lines = [
+            "#!/bin/sh".to_string(),
+            unix_script_command(&script, shell.as_deref()),
+            "code=$?".to_string(),
+            format!(
+                "if ! out=$(printf 'From: %s\\r\\nTo: %s\\r\\nSubject: %s (exit code %s)\\r\\n\\r\\nTask %s finished at %s with exit code %s.\\r\\n' {} {} {} \"$code\" {} \"$(date +%Y-%m-%dT%H:%M:%S)\" \"$code\" | curl -sS --config {} --mail-rcpt {} -T - 2>&1); then",
+                shell_quote(&from),
+                shell_quote(to),
+                shell_quote(&subject),
+                shell_quote(task_name),
+                shell_quote(&smtp),
+                shell_quote(to)
+            ),
+            format!(
+                "  printf '%s completion email to %s failed: %s\\n' \"$(date +%Y-%m-%dT%H:%M:%S)\" {} \"$out\" >> {}",
+                shell_quote(to),
+                shell_quote(&log)
+            ),
+            "fi".to_string(),
+            "exit $code".to_string(),
+        ];
+        (
+            dir.join(format!("{}-email.sh", file)),
+            format!("{}\n", lines.join("\n")),
+        )
+    };
+
+    Ok(Wrapper {
+        path: wrapper,
+        contents,
+        private: false,
+    })
+}
+
+/// Where a task's wrapper scripts go: the task's log (created along with
+/// its directory), `wrapper_dir`, and the file-safe task name the log is
+/// named with, to name the wrapper after.
+fn wrapper_paths(
+    app: &tauri::AppHandle,
+    task_name: &str,
+) -> Result<(String, std::path::PathBuf, String), String> {
+    let log = task_log_file(app, task_name)?;
+    if let Some(dir) = log.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let dir = wrapper_dir(app)?;
+    Ok((
+        log.to_string_lossy().to_string(),
+        dir,
+        task_file_stem(task_name),
+    ))
+}
+
+/// A wrapper script rendered by `env_wrapper`, `timing_wrapper`,
+/// `completion_wrapper`, `email_wrapper` or `overlap_wrapper` but not yet
+/// written, so a task's
+/// wrappers can all be checked before the first lands on disk.
+struct Wrapper {
+    path: std::path::PathBuf,
+    contents: String,
+    /// Readable by the current user only, for wrappers holding secrets
+    private: bool,
+}
+
+impl Wrapper {
+    fn path(&self) -> String {
+        self.path.to_string_lossy().to_string()
+    }
+
+    fn write(&self) -> Result<String, String> {
+        if !self.private {
+            return write_wrapper(&self.path, &self.contents);
+        }
+        write_private_file(&self.path, &self.contents)?;
+        #[cfg(not(target_os = "windows"))]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o700));
+        }
+        Ok(self.path())
+    }
+}
+
+/// Writes `wrappers` in turn. If one fails, those already written are
+/// removed again.
+fn write_wrappers(wrappers: &[Wrapper]) -> Result<(), String> {
+    for (written, wrapper) in wrappers.iter().enumerate() {
+        if let Err(e) = wrapper.write() {
+            remove_wrappers(&wrappers[..written]);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+fn remove_wrappers(wrappers: &[Wrapper]) {
+    for wrapper in wrappers {
+        let _ = std::fs::remove_file(&wrapper.path);
+    }
+}
+
+/// Writes a wrapper script, executable on Unix, and returns its path.
+fn write_wrapper(wrapper: &std::path::Path, contents: &str) -> Result<String, String> {
+    std::fs::write(wrapper, contents)
+        .map_err(|e| format!("Failed to write {}: {}", wrapper.display(), e))?;
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(wrapper, std::fs::Permissions::from_mode(0o755));
+    }
+    Ok(wrapper.to_string_lossy().to_string())
+}
+
+/// How a PowerShell wrapper invokes `script`: directly for `.ps1`, through
+/// its interpreter otherwise.
+#[cfg(target_os = "windows")]
+fn powershell_invocation(script: &str) -> String {
+    let quote = |v: &str| format!("'{}'", v.replace('\'', "''"));
+    let interpreter = interpreter_for(script);
+    if interpreter.program.to_lowercase().starts_with("powershell") {
+        quote(script)
+    } else {
+        let mut parts = vec![quote(&interpreter.program)];
+        parts.extend(interpreter.args.iter().map(|a| quote(a)));
+        parts.push(quote(script));
+        parts.join(" ")
+    }
+}
+
+/// The lock file `overlap_wrapper` holds while a run of `task_name` is in
+/// progress: `<app_data_dir>/locks/<name>.lock`.
+fn task_lock_file(app: &tauri::AppHandle, task_name: &str) -> Result<std::path::PathBuf, String> {
+    let (_, dir, file) = wrapper_paths(app, task_name)?;
+    let locks = dir.with_file_name("locks");
+    std::fs::create_dir_all(&locks)
+        .map_err(|e| format!("Failed to create {}: {}", locks.display(), e))?;
+    Ok(locks.join(format!("{}.lock", file)))
+}
+
+/// The line after the shebang (or first, in PowerShell) of every
+/// `timing_wrapper`, followed by the script it runs as a JSON string.
+const TIMING_WRAPPER_HEADER: &str = "# AUI timing wrapper, runs: ";
+
+/// The wrapper every scheduled run of a task goes through,
+/// `<app_data_dir>/schedules/<task>-timing.sh` (`.ps1` on Windows): it runs
+/// the script and appends when it started, how long it took in
+/// milliseconds and its exit code to the task's timing file (see
+/// `task_timing_file`), then exits with the script's exit code. With
+/// `AUI_TEST_RUN` set (see `test_run_task`) nothing is appended.
+/// `timing_wrapper_script` reads the script back from its header.
+fn timing_wrapper(
+    app: &tauri::AppHandle,
+    task_name: &str,
+    script_path: &str,
+    shell: Option<String>,
+) -> Result<Wrapper, String> {
+    let shell = resolve_shell(shell)?;
+    let script = expand_user_path(script_path);
+    let (_, dir, file) = wrapper_paths(app, task_name)?;
+    let timing = task_timing_file(app, task_name)?
+        .to_string_lossy()
+        .to_string();
+    let header = format!(
+        "{}{}",
+        TIMING_WRAPPER_HEADER,
+        serde_json::to_string(&script).map_err(|e| e.to_string())?
+    );
+
+    #[cfg(target_os = "windows")]
+    let (wrapper, contents) = {
+        let _ = shell;
+        let quote = |v: &str| format!("'{}'", v.replace('\'', "''"));
+        let lines = [
+            header,
+            "$started = Get-Date -Format s".to_string(),
+            "$watch = [Diagnostics.Stopwatch]::StartNew()".to_string(),
+            format!("& {}", powershell_invocation(&script)),
+            "$code = $LASTEXITCODE".to_string(),
+            format!(
+                "if (-not $env:AUI_TEST_RUN) {{ Add-Content -LiteralPath {} -Value \"started=$started duration_ms=$($watch.ElapsedMilliseconds) exit=$code source=scheduled\" }}",
+                quote(&timing)
+            ),
+            "exit $code".to_string(),
+        ];
+        (
+            dir.join(format!("{}-timing.ps1", file)),
+            format!("\u{feff}{}\r\n", lines.join("\r\n")),
+        )
+    };
+    #[cfg(not(target_os = "windows"))]
+    let (wrapper, contents) = {
+        let lines = [
+            "#!/bin/sh".to_string(),
+            header,
+            "# Milliseconds since the epoch: macOS date has no %N, so perl is asked instead".to_string(),
+            "now_ms() {".to_string(),
+            "  ns=$(date +%s%N 2>/dev/null)".to_string(),
+            "  case \"$ns\" in".to_string(),
+            "    ''|*[!0-9]*) perl -MTime::HiRes=time -e 'printf \"%d\\n\", time * 1000' 2>/dev/null || echo $(($(date +%s) * 1000)) ;;".to_string(),
+            "    *) echo $((ns / 1000000)) ;;".to_string(),
+            "  esac".to_string(),
+            "}".to_string(),
+            "started=$(date +%Y-%m-%dT%H:%M:%S)".to_string(),
+            "start=$(now_ms)".to_string(),
+            unix_script_command(&script, shell.as_deref()),
+            "code=$?".to_string(),
+            format!(
+                "[ -n \"$AUI_TEST_RUN\" ] || printf 'started=%s duration_ms=%s exit=%s source=scheduled\\n' \"$started\" \"$(($(now_ms) - start))\" \"$code\" >> {}",
+                shell_quote(&timing)
+            ),
+            "exit $code".to_string(),
+        ];
+        (
+            dir.join(format!("{}-timing.sh", file)),
+            format!("{}\n", lines.join("\n")),
+        )
+    };
+
+    Ok(Wrapper {
+        path: wrapper,
+        contents,
+        private: false,
+    })
+}
+
+/// The script the `timing_wrapper` at `path` runs, or `None` when `path`
+/// isn't one.
+fn timing_wrapper_script(path: &str) -> Option<String> {
+    if !(path.ends_with("-timing.sh") || path.ends_with("-timing.ps1")) {
+        return None;
+    }
+    let contents = std::fs::read_to_string(path).ok()?;
+    let script = contents.lines().take(2).find_map(|line| {
+        line.trim_start_matches('\u{feff}')
+            .strip_prefix(TIMING_WRAPPER_HEADER)
+    })?;
+    serde_json::from_str(script).ok()
+}
+
+/// Whether `script_path` is one of the wrappers `create_scheduled_task`
+/// chains for `task_name`, all of which run through its `timing_wrapper`
+/// already, as when a task is recreated from its metadata.
+fn is_timed_wrapper(app: &tauri::AppHandle, task_name: &str, script_path: &str) -> bool {
+    let Ok(dir) = wrapper_dir_path(app) else {
+        return false;
+    };
+    let path = std::path::PathBuf::from(expand_user_path(script_path));
+    let file = task_file_stem(task_name);
+    ["-env", "-webhook", "-email", "-lock", "-timing"]
+        .iter()
+        .flat_map(|suffix| {
+            ["sh", "ps1"].map(|extension| format!("{}{}.{}", file, suffix, extension))
+        })
+        .any(|name| path == dir.join(name))
+}
+
+/// Schedules `script_path` for `task_name` through its `timing_wrapper`:
+/// `schedule` is given the script to schedule and the shell to run it
+/// with. The wrapper is removed again when `schedule` fails. Scripts that
+/// are timed already (see `is_timed_wrapper`) are scheduled as they are,
+/// and crontab previews only get the wrapper's path.
+fn schedule_timed<T>(
+    app: &tauri::AppHandle,
+    task_name: &str,
+    script_path: &str,
+    shell: Option<String>,
+    schedule: impl FnOnce(String, Option<String>) -> Result<T, String>,
+) -> Result<T, String> {
+    if is_timed_wrapper(app, task_name, script_path) {
+        return schedule(script_path.to_string(), shell);
+    }
+    #[cfg(not(target_os = "windows"))]
+    if previewing_crontab() {
+        let wrapper =
+            wrapper_dir_path(app)?.join(format!("{}-timing.sh", task_file_stem(task_name)));
+        return schedule(wrapper.to_string_lossy().to_string(), None);
+    }
+    let wrapper = timing_wrapper(app, task_name, script_path, shell)?;
+    wrapper.write()?;
+    match schedule(wrapper.path(), None) {
+        Ok(result) => {
+            remove_replaced_wrappers(app, task_name, &[wrapper.path()]);
+            Ok(result)
+        }
+        Err(e) => {
+            remove_wrappers(std::slice::from_ref(&wrapper));
+            Err(e)
+        }
+    }
+}
+
+/// The wrapper that keeps runs of a task from piling up,
+/// `<app_data_dir>/schedules/<task>-lock.sh` (`.ps1` on Windows). It takes
+/// the task's lock (see `task_lock_file`) before running the script: `flock`
+/// on Linux, an atomic `mkdir` where there's no `flock` (macOS), a file
+/// opened for exclusive writing on Windows. While the previous run still
+/// holds it the run is skipped, with "skipped: previous run still active"
+/// appended to the task's log. The lock file records the holder's pid.
+fn overlap_wrapper(
+    app: &tauri::AppHandle,
+    task_name: &str,
+    script_path: &str,
+    shell: Option<String>,
+) -> Result<Wrapper, String> {
+    let shell = resolve_shell(shell)?;
+    let script = expand_user_path(script_path);
+    let (log, dir, file) = wrapper_paths(app, task_name)?;
+    let lock = task_lock_file(app, task_name)?
+        .to_string_lossy()
+        .to_string();
+
+    #[cfg(target_os = "windows")]
+    let (wrapper, contents) = {
+        let _ = shell;
+        let quote = |v: &str| format!("'{}'", v.replace('\'', "''"));
+        let lines = [
+            "$code = 0".to_string(),
+            format!(
+                "try {{ $lock = [IO.File]::Open({}, 'OpenOrCreate', 'ReadWrite', 'Read') }} catch {{ Add-Content -LiteralPath {} -Value \"$((Get-Date).ToString('s')) skipped: previous run still active\"; exit 0 }}",
+                quote(&lock),
+                quote(&log)
+            ),
+            "$id = [Text.Encoding]::ASCII.GetBytes([string]$PID); $lock.SetLength(0); $lock.Write($id, 0, $id.Length); $lock.Flush()".to_string(),
+            format!(
+                "try {{ & {}; $code = $LASTEXITCODE }} finally {{ $lock.SetLength(0); $lock.Close() }}",
+                powershell_invocation(&script)
+            ),
+            "exit $code".to_string(),
+        ];
+        (
+            dir.join(format!("{}-lock.ps1", file)),
+            format!("\u{feff}{}\r\n", lines.join("\r\n")),
+        )
+    };
+    #[cfg(not(target_os = "windows"))]
+    let (wrapper, contents) = {
+        let lines = [
+            "#!/bin/sh".to_string(),
+            format!("lock={}", shell_quote(&lock)),
+            format!(
+                "skip() {{ printf '%s skipped: previous run still active\\n' \"$(date +%Y-%m-%dT%H:%M:%S)\" >> {}; exit 0; }}",
+                shell_quote(&log)
+            ),
+            "if command -v flock >/dev/null 2>&1; then".to_string(),
+            "  exec 9>>\"$lock\"".to_string(),
+            "  flock -n 9 || skip".to_string(),
+            "else".to_string(),
+            "  # Taken over only once the pid it records is gone".to_string(),
+            "  if ! mkdir \"$lock.d\" 2>/dev/null; then".to_string(),
+            "    owner=$(cat \"$lock\" 2>/dev/null)".to_string(),
+            "    [ -n \"$owner\" ] && kill -0 \"$owner\" 2>/dev/null && skip".to_string(),
+            "    rm -rf \"$lock.d\"".to_string(),
+            "    mkdir \"$lock.d\" 2>/dev/null || skip".to_string(),
+            "  fi".to_string(),
+            "  trap 'rm -rf \"$lock.d\"' EXIT".to_string(),
+            "fi".to_string(),
+            "echo $$ > \"$lock\"".to_string(),
+            unix_script_command(&script, shell.as_deref()),
+            "code=$?".to_string(),
+            ": > \"$lock\"".to_string(),
+            "exit $code".to_string(),
+        ];
+        (
+            dir.join(format!("{}-lock.sh", file)),
+            format!("{}\n", lines.join("\n")),
+        )
+    };
+
+    Ok(Wrapper {
+        path: wrapper,
+        contents,
+        private: false,
+    })
+}
+
+/// The wrapper that runs a task's script with `env` set,
+/// `<app_data_dir>/schedules/<task>-env.sh` (`.ps1` on Windows). The
+/// variables are copied in when the task is created, so later changes to
+/// the profile they came from need the task recreated; the file is only
+/// readable by the current user as they may be secrets. `prepend_path`
+/// directories go in front of the PATH the scheduler provides.
+fn env_wrapper(
+    app: &tauri::AppHandle,
+    task_name: &str,
+    script_path: &str,
+    shell: Option<String>,
+    env: &HashMap<String, String>,
+    prepend_path: &[String],
+) -> Result<Wrapper, String> {
+    let shell = resolve_shell(shell)?;
+    let script = expand_user_path(script_path);
+    let (_, dir, file) = wrapper_paths(app, task_name)?;
+    let mut env: Vec<(&String, &String)> = env.iter().collect();
+    env.sort();
+    if let Some((key, _)) = env.iter().find(|(k, _)| !valid_env_name(k)) {
+        return Err(format!("Invalid environment variable name '{}'", key));
+    }
+    let separator = if cfg!(target_os = "windows") {
+        ';'
+    } else {
+        ':'
+    };
+    let prepend_path: Vec<String> = prepend_path
+        .iter()
+        .map(|d| expand_user_path(d.trim()))
+        .filter(|d| !d.is_empty())
+        .collect();
+    for dir in &prepend_path {
+        if dir.contains(separator) || dir.chars().any(char::is_control) {
+            return Err(format!("Invalid PATH directory '{}'", dir));
+        }
+        if !std::path::Path::new(dir).is_dir() {
+            return Err(format!("Directory not found: {}", dir));
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    let (wrapper, contents) = {
+        let _ = shell;
+        let quote = |v: &str| format!("'{}'", v.replace('\'', "''"));
+        let mut lines: Vec<String> = env
+            .iter()
+            .map(|(k, v)| format!("$env:{} = {}", k, quote(v)))
+            .collect();
+        if !prepend_path.is_empty() {
+            lines.push(format!(
+                "$env:Path = {} + $env:Path",
+                quote(&format!("{};", prepend_path.join(";")))
+            ));
+        }
+        lines.push(format!("& {}", powershell_invocation(&script)));
+        lines.push("exit $LASTEXITCODE".to_string());
+        (
+            dir.join(format!("{}-env.ps1", file)),
+            format!("\u{feff}{}\r\n", lines.join("\r\n")),
+        )
+    };
+    #[cfg(not(target_os = "windows"))]
+    let (wrapper, contents) = {
+        let mut lines = vec!["#!/bin/sh".to_string()];
+        lines.extend(
+            env.iter()
+                .map(|(k, v)| format!("export {}={}", k, shell_quote(v))),
+        );
+        if !prepend_path.is_empty() {
+            let dirs: Vec<String> = prepend_path.iter().map(|d| shell_quote(d)).collect();
+            lines.push(format!("export PATH={}:\"$PATH\"", dirs.join(":")));
+        }
+        lines.push(format!(
+            "exec {}",
+            unix_script_command(&script, shell.as_deref())
+        ));
+        (
+            dir.join(format!("{}-env.sh", file)),
+            format!("{}\n", lines.join("\n")),
+        )
+    };
+
+    Ok(Wrapper {
+        path: wrapper,
+        contents,
+        private: true,
+    })
+}
+
+/// Whether a run of `task_name` is in progress, going by the pid the lock
+/// file of its overlap wrapper (see `overlap_wrapper`) records. Tasks
+/// created without `prevent_overlap` are never reported running.
+#[tauri::command]
+fn is_task_running(app: tauri::AppHandle, task_name: String) -> Result<bool, String> {
+    validate_task_name(&task_name)?;
+    let lock = task_lock_file(&app, &task_name)?;
+    let pid = std::fs::read_to_string(&lock).unwrap_or_default();
+    Ok(pid.trim().parse::<u32>().is_ok_and(process_alive))
+}
+
+/// Schedules the script of a node in a saved graph, under a task name
+/// derived from the graph and node id so `reconcile_schedules` can match it
+/// back. `schedule_spec` defaults to the schedule stored on the node.
+#[tauri::command]
+fn schedule_node(
+    app: tauri::AppHandle,
+    graph_name: String,
+    node_id: String,
+    schedule_spec: Option<ScheduleSpec>,
+) -> Result<ScheduledTask, String> {
+    let graph_path = graph_file(&app, &graph_name)?;
+    let graph = load_graph(&graph_path)?;
+    let node = graph
+        .nodes
+        .iter()
+        .find(|n| n.id == node_id)
+        .ok_or_else(|| format!("No node '{}' in graph {}", node_id, graph_path.display()))?;
+    let spec = schedule_spec
+        .or_else(|| node.schedule.clone())
+        .ok_or_else(|| format!("Node '{}' has no schedule", node.id))?;
+
+    let task_name = node_task_name(&graph_path, &node.id);
+    validate_task_name(&task_name)?;
+    let script_path = node_script(&app, &graph_path, node, &task_name)?;
+    let result = create_task_from_spec(&app, &task_name, &script_path, &spec)?;
+    let listed = scheduled_tasks()
+        .ok()
+        .and_then(|tasks| tasks.into_iter().find(|t| t.task_name == task_name));
+    Ok(ScheduledTask {
+        backend: listed.as_ref().map_or_else(
+            || {
+                if cfg!(target_os = "windows") {
+                    "schtasks"
+                } else {
+                    "cron"
+                }
+                .into()
+            },
+            |t| t.backend.clone(),
+        ),
+        task_name,
+        schedule_spec: result.schedule_spec,
+        schedule_kind: listed
+            .as_ref()
+            .map(|t| t.schedule_kind.clone())
+            .unwrap_or_else(|| "cron".into()),
+        command: result.raw_command,
+        script_path: result.script_path,
+        node_id: Some(node.id.clone()),
+        enabled: true,
+        priority: listed.as_ref().and_then(|t| t.priority),
+        metadata: listed.as_ref().and_then(|t| t.metadata.clone()),
+        timezone: Some(result.timezone),
+        next_run: listed.and_then(|t| t.next_run),
+        last_result: None,
+        last_result_text: None,
+    })
+}
+
+/// Names of all AUI scheduled tasks, from schtasks' `AUI\` folder or the
+/// `# AUI:` markers in the crontab and queued `at` jobs.
+fn aui_task_names() -> Result<Vec<String>, String> {
+    let mut names: Vec<String> = scheduled_tasks()?
+        .into_iter()
+        .map(|task| task.task_name)
+        .collect();
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+/// Splits one line of schtasks CSV output into its fields.
+#[cfg(target_os = "windows")]
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end().chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Normalizes a schtasks "Next Run Time" to `YYYY-MM-DD HH:MM`. Formats
+/// that aren't recognized are passed through; "N/A" means none.
+#[cfg(target_os = "windows")]
+fn normalize_next_run(value: &str) -> Option<String> {
+    const FORMATS: [&str; 4] = [
+        "%m/%d/%Y %I:%M:%S %p",
+        "%m/%d/%Y %H:%M:%S",
+        "%d/%m/%Y %H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+    ];
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case("N/A") {
+        return None;
+    }
+    let parsed = FORMATS
+        .iter()
+        .find_map(|f| chrono::NaiveDateTime::parse_from_str(value, f).ok());
+    Some(match parsed {
+        Some(time) => time.format("%Y-%m-%d %H:%M").to_string(),
+        None => value.to_string(),
+    })
+}
+
+/// Splits a POSIX shell command line into words, undoing single quotes,
+/// double quotes and backslash escapes. Operators are not recognized.
+#[cfg(not(target_os = "windows"))]
+fn shell_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                word.extend(chars.by_ref().take_while(|c| *c != '\''));
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        _ => word.push(c),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_with(String::new).extend(chars.next()),
+            c if c.is_whitespace() => words.extend(word.take()),
+            _ => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// The part of an AUI cron command that runs the script, without the year
+/// guard and self-removal tail of one-shot crontab lines.
+#[cfg(not(target_os = "windows"))]
+fn cron_task_command(command: &str) -> &str {
+    match command.split_once("&& { ") {
+        Some((_, rest)) => rest.split("; crontab -l").next().unwrap_or(rest),
+        // Guarded one-shot wrapper (see `one_shot_wrapper`)
+        None if command.starts_with("[ \"$(date +\\%Y)\" = \"") => command
+            .split_once("\" ] && ")
+            .map_or(command, |(_, rest)| rest),
+        None => command,
+    }
+}
+
+/// The script an AUI cron or `at` command runs: the last word of the
+/// command that runs it, or for a one-shot or timing wrapper the script it
+/// wraps.
+#[cfg(not(target_os = "windows"))]
+fn cron_command_script(command: &str) -> String {
+    let script = shell_words(cron_task_command(command))
+        .pop()
+        .unwrap_or_default();
+    let script = one_shot_wrapper_script(&script).unwrap_or(script);
+    timing_wrapper_script(&script).unwrap_or(script)
+}
+
+/// The `nice` level an AUI cron or `at` command runs at (see
+/// `nice_command`), if any.
+#[cfg(not(target_os = "windows"))]
+fn cron_command_priority(command: &str) -> Option<i32> {
+    let rest = cron_task_command(command)
+        .trim_start()
+        .strip_prefix("nice -n ")?;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// Parses a crontab line carrying an `# AUI:` marker. Lines commented out
+/// in front of the schedule are disabled tasks.
+#[cfg(not(target_os = "windows"))]
+fn parse_cron_task(line: &str, now: chrono::NaiveDateTime) -> Option<ScheduledTask> {
+    let (entry, name) = line.trim().rsplit_once("# AUI:")?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let disabled = entry.starts_with('#');
+    let entry = entry.trim_start_matches('#').trim();
+
+    let (schedule, command) = if entry.starts_with('@') {
+        entry.split_once(char::is_whitespace)?
+    } else {
+        // Five schedule fields, then the command
+        let mut end = 0;
+        for _ in 0..5 {
+            let rest = &entry[end..];
+            let start = end + (rest.len() - rest.trim_start().len());
+            end = start
+                + entry[start..]
+                    .find(char::is_whitespace)
+                    .unwrap_or(entry.len() - start);
+        }
+        (&entry[..end], &entry[end..])
+    };
+    let command = command.trim();
+    let cron = parse_cron(schedule).ok();
+
+    // One-shot lines guard the command with the year they belong to
+    let year = command
+        .strip_prefix("[ \"$(date +\\%Y)\" = \"")
+        .and_then(|rest| rest.split('"').next())
+        .and_then(|year| year.parse::<i32>().ok());
+    let (kind, next_run) = match (&cron, year) {
+        (Some(cron), Some(year)) => {
+            let next = chrono::NaiveDate::from_ymd_opt(
+                year,
+                cron.month.single()?,
+                cron.day_of_month.single()?,
+            )
+            .and_then(|d| d.and_hms_opt(cron.hour.single()?, cron.minute.single()?, 0))
+            .filter(|time| *time > now);
+            ("once", next)
+        }
+        (Some(cron), None) => (cron_schedule_kind(cron), cron_next_run(cron, now)),
+        (None, _) if schedule == "@reboot" => ("startup", None),
+        (None, _) => ("cron", None),
+    };
+
+    Some(ScheduledTask {
+        backend: "cron".into(),
+        task_name: name.to_string(),
+        schedule_spec: schedule.to_string(),
+        schedule_kind: kind.to_string(),
+        command: command.to_string(),
+        script_path: cron_command_script(command),
+        node_id: None,
+        enabled: !disabled,
+        next_run: next_run
+            .filter(|_| !disabled)
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string()),
+        last_result: None,
+        last_result_text: None,
+        priority: cron_command_priority(command),
+        metadata: None,
+        timezone: None,
+    })
+}
+
+/// The AUI tasks in a verbose schtasks CSV listing of the `AUI\` folder,
+/// with `priorities` from `task_priorities`.
+#[cfg(target_os = "windows")]
+fn schtasks_tasks(listing: &str, priorities: &HashMap<String, i32>) -> Vec<ScheduledTask> {
+    // Columns: host, name, next run, status, logon mode, last run, last
+    // result, author, task to run, start in, comment, state, ... schedule
+    // type (18), start time (19). Tasks with several triggers repeat.
+    let mut tasks: Vec<ScheduledTask> = Vec::new();
+    for line in listing.lines() {
+        let fields = parse_csv_line(line);
+        if fields.len() < 20 {
+            continue;
+        }
+        let Some(name) = fields[1].strip_prefix("\\AUI\\") else {
+            continue;
+        };
+        // One row per trigger; fold extra start times into the first
+        if let Some(task) = tasks.iter_mut().find(|t| t.task_name == name) {
+            let start = fields[19].trim();
+            if !start.is_empty() && !task.schedule_spec.contains(start) {
+                task.schedule_spec.push_str(&format!(", {}", start));
+            }
+            continue;
+        }
+
+        let schedule_type = fields[18].trim();
+        let lower = schedule_type.to_lowercase();
+        let kind = ["minute", "hourly", "daily", "weekly", "monthly"]
+            .into_iter()
+            .find(|k| lower.contains(k))
+            .or_else(|| lower.contains("one time").then_some("once"))
+            .or_else(|| lower.contains("logon").then_some("logon"))
+            .or_else(|| lower.contains("start").then_some("startup"))
+            .or_else(|| lower.contains("idle").then_some("idle"))
+            .map(str::to_string)
+            .unwrap_or(lower);
+        let command = fields[8].trim().to_string();
+        let script_path = command
+            .rsplit('"')
+            .nth(1)
+            .map(str::to_string)
+            .unwrap_or_else(|| command.clone());
+        let script_path = timing_wrapper_script(&script_path).unwrap_or(script_path);
+        let disabled = [&fields[3], &fields[11]]
+            .iter()
+            .any(|f| f.trim().eq_ignore_ascii_case("Disabled"));
+
+        tasks.push(ScheduledTask {
+            backend: "schtasks".into(),
+            task_name: name.to_string(),
+            schedule_spec: format!("{} {}", schedule_type, fields[19].trim())
+                .trim()
+                .to_string(),
+            schedule_kind: kind,
+            command,
+            script_path,
+            node_id: None,
+            enabled: !disabled,
+            next_run: normalize_next_run(&fields[2]),
+            last_result: Some(fields[6].trim().to_string()).filter(|r| !r.is_empty()),
+            last_result_text: task_result_text(fields[6].trim()),
+            priority: priorities.get(name).copied(),
+            metadata: None,
+            timezone: None,
+        });
+    }
+    tasks
+}
+
+/// The AUI tasks in crontab text, one per task even when it has a line per
+/// run time.
+#[cfg(not(target_os = "windows"))]
+fn crontab_tasks(crontab: &str, now: chrono::NaiveDateTime) -> Vec<ScheduledTask> {
+    let mut tasks: Vec<ScheduledTask> = Vec::new();
+    for task in crontab
+        .lines()
+        .filter_map(|line| parse_cron_task(line, now))
+    {
+        // Tasks with several run times have a line per time
+        let Some(existing) = tasks.iter_mut().find(|t| t.task_name == task.task_name) else {
+            tasks.push(task);
+            continue;
+        };
+        existing.schedule_spec = format!("{}; {}", existing.schedule_spec, task.schedule_spec);
+        if existing.schedule_kind != task.schedule_kind {
+            existing.schedule_kind = "cron".into();
+        }
+        existing.enabled |= task.enabled;
+        existing.next_run = match (existing.next_run.take(), task.next_run) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+    tasks
+}
+
+/// All AUI scheduled tasks, parsed from the verbose schtasks listing of the
+/// `AUI\` folder, or from the crontab and queued `at` jobs.
+fn scheduled_tasks() -> Result<Vec<ScheduledTask>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let listing = query_task_folder()?;
+        let priorities = task_priorities();
+        let mut tasks = schtasks_tasks(&listing, &priorities);
+        let timezone = schedule_timezone();
+        for task in &mut tasks {
+            task.timezone = Some(timezone.clone());
+        }
+        Ok(tasks)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let now = chrono::Local::now().naive_local();
+        let mut tasks = crontab_tasks(&read_crontab()?, now);
+        for (_, when, job) in aui_at_jobs() {
+            let Some((command, name)) = job.rsplit_once("# AUI:") else {
+                continue;
+            };
+            let command = command.trim().to_string();
+            let priority = cron_command_priority(&command);
+            tasks.push(ScheduledTask {
+                backend: "at".into(),
+                task_name: name.trim().to_string(),
+                schedule_spec: format!("at {}", when),
+                schedule_kind: "once".into(),
+                script_path: cron_command_script(&command),
+                command,
+                node_id: None,
+                enabled: true,
+                next_run: chrono::NaiveDateTime::parse_from_str(&when, "%a %b %e %H:%M:%S %Y")
+                    .ok()
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string()),
+                last_result: None,
+                last_result_text: None,
+                priority,
+                metadata: None,
+                timezone: None,
+            });
+        }
+
+        // Entries from before metadata was recorded, or whose metadata no
+        // longer matches the crontab, keep what the cron line says
+        let timezone = schedule_timezone();
+        for task in &mut tasks {
+            task.metadata =
+                load_task_metadata(&task.task_name).filter(|m| m.script_path == task.script_path);
+            task.timezone = Some(timezone.clone());
+        }
+        Ok(tasks)
+    }
+}
+
+/// Which tasks `query_scheduled_tasks` returns. Unset fields don't filter.
+#[derive(serde::Deserialize, Default)]
+struct TaskFilter {
+    #[serde(default)]
+    name_contains: Option<String>,
+    #[serde(default)]
+    enabled_only: Option<bool>,
+    #[serde(default)]
+    schedule_kind: Option<String>,
+}
+
+/// Lists AUI scheduled tasks matching `filter`, sorted by `sort_by`:
+/// "name" (the default) or "next_run", soonest first with tasks that have
+/// no upcoming run last. Name matching ignores case.
+#[tauri::command]
+fn query_scheduled_tasks(
+    filter: Option<TaskFilter>,
+    sort_by: Option<String>,
+) -> Result<Vec<ScheduledTask>, String> {
+    let filter = filter.unwrap_or_default();
+    let name_contains = filter
+        .name_contains
+        .map(|n| n.trim().to_lowercase())
+        .filter(|n| !n.is_empty());
+    let kind = filter
+        .schedule_kind
+        .map(|k| k.trim().to_lowercase())
+        .filter(|k| !k.is_empty());
+
+    let mut tasks: Vec<ScheduledTask> = scheduled_tasks()?
+        .into_iter()
+        .filter(|t| {
+            name_contains
+                .as_ref()
+                .map_or(true, |n| t.task_name.to_lowercase().contains(n))
+        })
+        .filter(|t| t.enabled || !filter.enabled_only.unwrap_or(false))
+        .filter(|t| kind.as_ref().map_or(true, |k| t.schedule_kind == *k))
+        .collect();
+
+    match sort_by.as_deref().map(str::trim).unwrap_or("name") {
+        "name" => tasks.sort_by_key(|t| t.task_name.to_lowercase()),
+        "next_run" => tasks.sort_by(|a, b| match (&a.next_run, &b.next_run) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a.task_name.cmp(&b.task_name),
+        }),
+        other => {
+            return Err(format!(
+                "Unknown sort key '{}': expected name or next_run",
+                other
+            ))
+        }
+    }
+    Ok(tasks)
+}
+
+#[derive(serde::Serialize)]
+struct TaskOverview {
+    name: String,
+    enabled: bool,
+    next_run: Option<String>,
+    minutes_until: Option<i64>,
+    last_result: Option<String>,
+    last_result_text: Option<String>,
+}
+
+/// Every AUI task with its next run and how many minutes away that is
+/// (rounded up), for the scheduling overview. Tasks with no upcoming run,
+/// or a next run the platform reports in an unrecognized format, have no
+/// `minutes_until`.
+#[tauri::command]
+fn scheduled_tasks_overview() -> Result<Vec<TaskOverview>, String> {
+    let now = chrono::Local::now().naive_local();
+    let mut overview: Vec<TaskOverview> = scheduled_tasks()?
+        .into_iter()
+        .map(|task| {
+            let minutes_until = task
+                .next_run
+                .as_deref()
+                .and_then(|t| chrono::NaiveDateTime::parse_from_str(t, "%Y-%m-%d %H:%M").ok())
+                .map(|next| {
+                    let seconds = (next - now).num_seconds().max(0);
+                    (seconds + 59) / 60
+                });
+            TaskOverview {
+                name: task.task_name,
+                enabled: task.enabled,
+                next_run: task.next_run,
+                minutes_until,
+                last_result: task.last_result,
+                last_result_text: task.last_result_text,
+            }
+        })
+        .collect();
+    overview.sort_by_key(|t| t.name.to_lowercase());
+    Ok(overview)
+}
+
+/// Enables or disables AUI tasks by name: `schtasks /Change` per task on
+/// Windows; on macOS/Linux their crontab lines are commented out or back in
+/// with a single edit. Returns the names that were changed and the errors
+/// for those that couldn't be. One-shot `at` jobs can't be disabled.
+fn set_tasks_enabled(names: &[String], enabled: bool) -> (Vec<String>, Vec<String>) {
+    #[cfg(target_os = "windows")]
+    {
+        let flag = if enabled { "/ENABLE" } else { "/DISABLE" };
+        let mut changed = Vec::new();
+        let mut errors = Vec::new();
+        for name in names {
+            let tn = format!("AUI\\{}", name);
+            match hidden_command("schtasks.exe")
+                .args(["/Change", "/TN", &tn, flag])
+                .output()
+            {
+                Ok(o) if o.status.success() => changed.push(name.clone()),
+                Ok(o) => errors.push(format!(
+                    "{}: {}",
+                    name,
+                    String::from_utf8_lossy(&o.stderr).trim()
+                )),
+                Err(e) => errors.push(format!("{}: failed to run schtasks: {}", name, e)),
+            }
+        }
+        (changed, errors)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let toggled = Mutex::new(Vec::new());
+        let result = modify_crontab(|existing| {
+            let mut changed = Vec::new();
+            let lines: Vec<String> = existing
+                .lines()
+                .map(|line| {
+                    let Some((_, name)) = line.rsplit_once("# AUI:") else {
+                        return line.to_string();
+                    };
+                    let name = name.trim();
+                    if !names.iter().any(|n| n == name) {
+                        return line.to_string();
+                    }
+                    let commented = line.starts_with('#');
+                    if enabled && commented {
+                        changed.push(name.to_string());
+                        line[1..].to_string()
+                    } else if !enabled && !commented {
+                        changed.push(name.to_string());
+                        format!("#{}", line)
+                    } else {
+                        line.to_string()
+                    }
+                })
+                .collect();
+            if let Ok(mut toggled) = toggled.lock() {
+                *toggled = changed;
+            }
+            with_trailing_newline(&lines.join("\n"))
+        });
+
+        let mut changed = toggled.into_inner().unwrap_or_default();
+        changed.sort();
+        changed.dedup();
+        match result {
+            Ok(_) => (changed, Vec::new()),
+            Err(e) => (Vec::new(), vec![e]),
+        }
+    }
+}
+
+/// Persisted maintenance mode: which tasks `set_all_tasks_enabled(false)`
+/// disabled, so turning it off re-enables exactly those.
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+struct MaintenanceState {
+    maintenance_mode: bool,
+    disabled_tasks: Vec<String>,
+}
+
+fn maintenance_file(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("maintenance.json"))
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))
+}
+
+fn load_maintenance(app: &tauri::AppHandle) -> Result<MaintenanceState, String> {
+    let path = maintenance_file(app)?;
+    match std::fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw)
+            .map_err(|e| format!("Invalid maintenance state {}: {}", path.display(), e)),
+        Err(_) => Ok(MaintenanceState::default()),
+    }
+}
+
+#[derive(serde::Serialize)]
+struct BulkToggleResult {
+    maintenance_mode: bool,
+    changed: usize,
+    unchanged: usize,
+    errors: Vec<String>,
+}
+
+/// Maintenance mode: `enabled: false` disables every enabled AUI task
+/// without deleting it and remembers which ones it disabled; `enabled:
+/// true` re-enables only those, so tasks that were already disabled stay
+/// that way. The flag survives restarts (see `get_maintenance_mode`).
+#[tauri::command]
+fn set_all_tasks_enabled(app: tauri::AppHandle, enabled: bool) -> Result<BulkToggleResult, String> {
+    let mut state = load_maintenance(&app)?;
+    let tasks = scheduled_tasks()?;
+
+    let targets: Vec<String> = if enabled {
+        state
+            .disabled_tasks
+            .iter()
+            .filter(|name| tasks.iter().any(|t| t.task_name == **name && !t.enabled))
+            .cloned()
+            .collect()
+    } else {
+        tasks
+            .iter()
+            .filter(|t| t.enabled && t.schedule_spec.split_whitespace().next() != Some("at"))
+            .map(|t| t.task_name.clone())
+            .collect()
+    };
+    let (changed, errors) = set_tasks_enabled(&targets, enabled);
+
+    if enabled {
+        // Keep anything that failed to re-enable for the next attempt
+        state.disabled_tasks.retain(|name| {
+            !changed.contains(name) && tasks.iter().any(|t| t.task_name == *name && !t.enabled)
+        });
+        state.maintenance_mode = !state.disabled_tasks.is_empty();
+    } else {
+        for name in &changed {
+            if !state.disabled_tasks.contains(name) {
+                state.disabled_tasks.push(name.clone());
+            }
+        }
+        state.maintenance_mode = true;
+    }
+
+    let path = maintenance_file(&app)?;
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let json = serde_json::to_string_pretty(&state)
+        .map_err(|e| format!("Failed to serialize maintenance state: {}", e))?;
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(BulkToggleResult {
+        maintenance_mode: state.maintenance_mode,
+        unchanged: tasks.len().saturating_sub(changed.len()),
+        changed: changed.len(),
+        errors,
+    })
+}
+
+/// Whether maintenance mode is on and which tasks it disabled.
+#[tauri::command]
+fn get_maintenance_mode(app: tauri::AppHandle) -> Result<MaintenanceState, String> {
+    load_maintenance(&app)
+}
+
+/// Outcome of `test_run_task`.
+#[derive(serde::Serialize)]
+struct TestRunResult {
+    task_name: String,
+    success: bool,
+    exit_code: Option<i32>,
+    log_path: String,
+    duration_ms: u64,
+}
+
+/// The per-task log `test_run_task` writes: `<app_log_dir>/tasks/<name>.log`.
+fn task_log_file(app: &tauri::AppHandle, task_name: &str) -> Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+
+    app.path()
+        .app_log_dir()
+        .map(|dir| {
+            dir.join("tasks")
+                .join(format!("{}.log", task_file_stem(task_name)))
+        })
+        .map_err(|e| format!("Failed to resolve app log dir: {}", e))
+}
+
+/// Where a task's runs are timed (see `timing_wrapper` and
+/// `test_run_task`): `<app_log_dir>/tasks/<name>.timing`. It is kept apart
+/// from the log, which holds whatever the script prints.
+fn task_timing_file(app: &tauri::AppHandle, task_name: &str) -> Result<std::path::PathBuf, String> {
+    let log = task_log_file(app, task_name)?;
+    Ok(log.with_extension("timing"))
+}
+
+/// Rehearses a scheduled task: runs the exact command the scheduler would
+/// (including any node wrapper) the way the scheduler would, appending its
+/// output to the task's log and emitting each line as a `task-output`
+/// event. On macOS/Linux that is `/bin/sh -c` with cron's environment (see
+/// `preview_task_environment`) in the home directory; on Windows the task's
+/// command line in System32, Task Scheduler's default start folder. Either
+/// way `AUI_TEST_RUN=1` is set, so a one-shot task's wrapper keeps its
+/// crontab line and the run is timed as a test rather than a scheduled
+/// one. Blocks until the script exits.
+#[tauri::command(async)]
+fn test_run_task(app: tauri::AppHandle, task_name: String) -> Result<TestRunResult, String> {
+    use std::io::{BufRead, Write};
+
+    validate_task_name(&task_name)?;
+    let task = scheduled_tasks()?
+        .into_iter()
+        .find(|t| t.task_name == task_name)
+        .ok_or_else(|| format!("No scheduled task named '{}'", task_name))?;
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = hidden_command("cmd.exe");
+        command.raw_arg(format!("/s /c \"{}\"", task.command));
+        let system32 = std::env::var("WINDIR")
+            .map(|w| std::path::PathBuf::from(w).join("System32"))
+            .unwrap_or_else(|_| std::path::PathBuf::from("C:\\Windows\\System32"));
+        command.current_dir(system32);
+        command.env("AUI_TEST_RUN", "1");
+        command
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut command = {
+        let mut command = StdCommand::new("/bin/sh");
+        command
+            .args(["-c", cron_task_command(&task.command)])
+            .env_clear();
+        for (name, value, _) in scheduler_base_env(Some(&task.task_name))? {
+            command.env(name, value);
+        }
+        command.env("AUI_TEST_RUN", "1");
+        if let Ok(home) = std::env::var("HOME") {
+            command.current_dir(home);
+        }
+        command
+    };
+    command
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+
+    let log_path = task_log_file(&app, &task.task_name)?;
+    if let Some(dir) = log_path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let mut log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| format!("Failed to open {}: {}", log_path.display(), e))?;
+    let started_at = chrono::Local::now();
+    let _ = writeln!(
+        log,
+        "=== test run {} ===",
+        started_at.format("%Y-%m-%d %H:%M:%S")
+    );
+    let log = Arc::new(Mutex::new(log));
+
+    let started = Instant::now();
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run task {}: {}", task.task_name, e))?;
+    let readers: Vec<_> = [
+        (
+            "stdout",
+            child
+                .stdout
+                .take()
+                .map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+        ),
+        (
+            "stderr",
+            child
+                .stderr
+                .take()
+                .map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(stream, pipe)| pipe.map(|p| (stream, p)))
+    .map(|(stream, pipe)| {
+        let app = app.clone();
+        let log = Arc::clone(&log);
+        let id = task.task_name.clone();
+        std::thread::spawn(move || {
+            for line in std::io::BufReader::new(pipe).lines().map_while(Result::ok) {
+                if let Ok(mut log) = log.lock() {
+                    let _ = writeln!(log, "{}", line);
+                }
+                let _ = app.emit(
+                    "task-output",
+                    ScriptOutput {
+                        id: id.clone(),
+                        stream: stream.to_string(),
+                        line,
+                        attempt: 1,
+                    },
+                );
+            }
+        })
+    })
+    .collect();
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for task {}: {}", task.task_name, e))?;
+    for reader in readers {
+        let _ = reader.join();
+    }
+    let duration_ms = started.elapsed().as_millis() as u64;
+    if let Ok(mut log) = log.lock() {
+        let code = status
+            .code()
+            .map_or_else(|| "none".to_string(), |c| c.to_string());
+        let _ = writeln!(log, "=== exit code {} ===", code);
+        let timing = task_timing_file(&app, &task.task_name)?;
+        let _ = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&timing)
+            .and_then(|mut timing| {
+                writeln!(
+                    timing,
+                    "started={} duration_ms={} exit={} source=test",
+                    started_at.format("%Y-%m-%dT%H:%M:%S"),
+                    duration_ms,
+                    code
+                )
+            });
+    }
+
+    Ok(TestRunResult {
+        task_name: task.task_name,
+        success: status.success(),
+        exit_code: status.code(),
+        log_path: log_path.to_string_lossy().to_string(),
+        duration_ms,
+    })
+}
+
+/// One timed run of a task, parsed from its timing file.
+#[derive(serde::Serialize)]
+struct TaskRunTiming {
+    started: String,
+    duration_ms: u64,
+    exit_code: Option<i32>,
+    /// `test` for `test_run_task`, `scheduled` for a run by the scheduler
+    /// (see `timing_wrapper`).
+    source: String,
+}
+
+#[derive(serde::Serialize)]
+struct TaskMetrics {
+    task_name: String,
+    runs: Vec<TaskRunTiming>,
+    average_ms: Option<u64>,
+    min_ms: Option<u64>,
+    max_ms: Option<u64>,
+}
+
+/// Parses a line of a task's timing file (see `task_timing_file`):
+/// `started=<time> duration_ms=<n> exit=<code> source=<source>`. `exit` and
+/// `source` may be missing; lines without a start or a duration are not a
+/// run.
+fn parse_task_timing(line: &str) -> Option<TaskRunTiming> {
+    let field = |key: &str| {
+        line.split_whitespace()
+            .find_map(|f| f.strip_prefix(key)?.strip_prefix('='))
+    };
+    Some(TaskRunTiming {
+        started: field("started")?.to_string(),
+        duration_ms: field("duration_ms")?.parse().ok()?,
+        exit_code: field("exit").and_then(|c| c.parse().ok()),
+        source: field("source").unwrap_or_default().to_string(),
+    })
+}
+
+/// How long a task's recent runs took, from its timing file (see
+/// `timing_wrapper` and `test_run_task`): the last
+/// `limit` runs (default 50), oldest first, with their average, fastest
+/// and slowest duration.
+#[tauri::command]
+fn get_task_metrics(
+    app: tauri::AppHandle,
+    task_name: String,
+    limit: Option<usize>,
+) -> Result<TaskMetrics, String> {
+    validate_task_name(&task_name)?;
+    let timing = task_timing_file(&app, &task_name)?;
+    let raw = match std::fs::read_to_string(&timing) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", timing.display(), e)),
+    };
+    let mut runs: Vec<TaskRunTiming> = raw.lines().filter_map(parse_task_timing).collect();
+    runs.drain(..runs.len().saturating_sub(limit.unwrap_or(50)));
+
+    let durations: Vec<u64> = runs.iter().map(|r| r.duration_ms).collect();
+    Ok(TaskMetrics {
+        task_name,
+        average_ms: (!durations.is_empty())
+            .then(|| durations.iter().sum::<u64>() / durations.len() as u64),
+        min_ms: durations.iter().min().copied(),
+        max_ms: durations.iter().max().copied(),
+        runs,
+    })
+}
+
+/// Where a monitor's check script lives (`<app_data_dir>/monitors`) and
+/// where it logs (`<app_log_dir>/monitors/<name>.log`).
+fn monitor_paths(
+    app: &tauri::AppHandle,
+    name: &str,
+) -> Result<(std::path::PathBuf, std::path::PathBuf), String> {
+    use tauri::Manager;
+
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    {
+        return Err(format!(
+            "Invalid monitor name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    let script = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))?
+        .join("monitors")
+        .join(if cfg!(target_os = "windows") {
+            format!("{}.ps1", name)
+        } else {
+            format!("{}.sh", name)
+        });
+    let log = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve app log dir: {}", e))?
+        .join("monitors")
+        .join(format!("{}.log", name));
+    Ok((script, log))
+}
+
+/// Creates an uptime monitor: a generated script that fetches each of
+/// `urls` with curl and appends one tab-separated row per URL (UTC
+/// timestamp, URL, HTTP status, latency in ms, curl exit code) to the
+/// monitor's log, scheduled as task `monitor-<name>` every
+/// `interval_minutes`. The interval has to divide an hour or a day evenly
+/// so it maps onto a cron step. Read the log back with
+/// `get_monitor_results`.
+#[tauri::command]
+fn create_monitor(
+    app: tauri::AppHandle,
+    name: String,
+    urls: Vec<String>,
+    interval_minutes: u32,
+) -> Result<CreateTaskResult, String> {
+    let (script, log) = monitor_paths(&app, &name)?;
+    if urls.is_empty() {
+        return Err("A monitor needs at least one URL".into());
+    }
+    let urls = urls
+        .iter()
+        .map(|u| parse_http_url(u).map(|u| u.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let cron = match interval_minutes {
+        n @ 1..=59 if 60 % n == 0 => format!("*/{} * * * *", n),
+        60 => "0 * * * *".to_string(),
+        n if n % 60 == 0 && 24 % (n / 60) == 0 => format!("0 */{} * * *", n / 60),
+        n => {
+            return Err(format!(
+                "Interval of {} minutes doesn't divide an hour or a day evenly",
+                n
+            ))
+        }
+    };
+    for dir in [script.parent(), log.parent()].into_iter().flatten() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let log = log.to_string_lossy().to_string();
+
+    #[cfg(target_os = "windows")]
+    let contents = {
+        let quote = |v: &str| format!("'{}'", v.replace('\'', "''"));
+        let urls: Vec<String> = urls.iter().map(|u| quote(u)).collect();
+        let lines = [
+            format!("$log = {}", quote(&log)),
+            format!("foreach ($url in @({})) {{", urls.join(", ")),
+            "    $result = curl.exe -s -o NUL -m 30 -w '%{http_code} %{time_total}' $url".to_string(),
+            "    $code = $LASTEXITCODE".to_string(),
+            "    $status, $time = \"$result\".Split(' ')".to_string(),
+            "    $ms = [int]([double]::Parse($time, [cultureinfo]::InvariantCulture) * 1000)".to_string(),
+            "    $stamp = (Get-Date).ToUniversalTime().ToString('yyyy-MM-ddTHH:mm:ssZ')".to_string(),
+            "    Add-Content -LiteralPath $log -Value (\"{0}`t{1}`t{2}`t{3}`t{4}\" -f $stamp, $url, $status, $ms, $code)".to_string(),
+            "}".to_string(),
+        ];
+        format!("\u{feff}{}\r\n", lines.join("\r\n"))
+    };
+    #[cfg(not(target_os = "windows"))]
+    let contents = {
+        let urls: Vec<String> = urls.iter().map(|u| shell_quote(u)).collect();
+        let lines = [
+            "#!/bin/sh".to_string(),
+            format!("log={}", shell_quote(&log)),
+            format!("for url in {}; do", urls.join(" ")),
+            "  result=$(curl -s -o /dev/null -m 30 -w '%{http_code} %{time_total}' \"$url\")".to_string(),
+            "  code=$?".to_string(),
+            "  ms=$(echo \"${result#* }\" | awk '{ printf \"%d\", $1 * 1000 }')".to_string(),
+            "  printf '%s\\t%s\\t%s\\t%s\\t%s\\n' \"$(date -u +%Y-%m-%dT%H:%M:%SZ)\" \"$url\" \"${result%% *}\" \"$ms\" \"$code\" >> \"$log\"".to_string(),
+            "done".to_string(),
+        ];
+        format!("{}\n", lines.join("\n"))
+    };
+
+    std::fs::write(&script, contents)
+        .map_err(|e| format!("Failed to write {}: {}", script.display(), e))?;
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755));
+    }
+    create_cron_task(
+        format!("monitor-{}", name),
+        script.to_string_lossy().to_string(),
+        cron,
+        None,
+    )
+}
+
+/// One check logged by a monitor. `status` is `None` when no HTTP response
+/// came back (`curl_exit` says why); `success` means a 2xx/3xx response.
+#[derive(serde::Serialize)]
+struct MonitorResult {
+    timestamp: String,
+    url: String,
+    status: Option<u16>,
+    latency_ms: u64,
+    curl_exit: i32,
+    success: bool,
+}
+
+/// Reads a monitor's log (see `create_monitor`) into rows, oldest first,
+/// keeping only the last `limit` when given. A monitor that hasn't run yet
+/// has no rows.
+#[tauri::command]
+fn get_monitor_results(
+    app: tauri::AppHandle,
+    name: String,
+    limit: Option<usize>,
+) -> Result<Vec<MonitorResult>, String> {
+    let (_, log) = monitor_paths(&app, &name)?;
+    let raw = match std::fs::read_to_string(&log) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", log.display(), e)),
+    };
+    let mut rows: Vec<MonitorResult> = raw
+        .lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.trim_end().split('\t').collect();
+            let [timestamp, url, status, latency, exit] = cols[..] else {
+                return None;
+            };
+            let status = status.parse().ok().filter(|s| *s != 0);
+            let curl_exit = exit.parse().ok()?;
+            Some(MonitorResult {
+                timestamp: timestamp.to_string(),
+                url: url.to_string(),
+                status,
+                latency_ms: latency.parse().unwrap_or(0),
+                curl_exit,
+                success: curl_exit == 0 && status.is_some_and(|s| (200..400).contains(&s)),
+            })
+        })
+        .collect();
+    if let Some(limit) = limit {
+        rows.drain(..rows.len().saturating_sub(limit));
+    }
+    Ok(rows)
+}
+
+/// Times `cron` fires in `(from, until]`.
+fn cron_runs_between(
+    cron: &CronExpr,
+    from: chrono::NaiveDateTime,
+    until: chrono::NaiveDateTime,
+) -> Vec<chrono::NaiveDateTime> {
+    let mut runs = Vec::new();
+    let mut after = from;
+    while let Some(next) = cron_next_run(cron, after).filter(|t| *t <= until) {
+        runs.push(next);
+        after = next;
+    }
+    runs
+}
+
+/// When a proposed `ScheduleSpec` would fire in `(from, until]`.
+fn spec_runs_between(
+    spec: &ScheduleSpec,
+    from: chrono::NaiveDateTime,
+    until: chrono::NaiveDateTime,
+) -> Result<Vec<chrono::NaiveDateTime>, String> {
+    let non_empty = |v: &Option<String>| {
+        v.as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    if let Some(cron) = non_empty(&spec.cron) {
+        return Ok(cron_runs_between(&parse_cron(&cron)?, from, until));
+    }
+    let once = |at: chrono::NaiveDateTime| {
+        if at > from && at <= until {
+            vec![at]
+        } else {
+            Vec::new()
+        }
+    };
+    if let Some(run_at) = non_empty(&spec.run_at) {
+        return Ok(once(parse_run_at(&run_at)?));
+    }
+
+    let start_time = non_empty(&spec.start_time)
+        .ok_or("Schedule needs a cron expression, run_at, or start_time")?;
+    let (hour, minute) = parse_time(&start_time)?;
+    let repeat = non_empty(&spec.repeat).unwrap_or_else(|| "daily".to_string());
+    match preset_cron(&repeat, hour, minute) {
+        Some(expr) => Ok(cron_runs_between(&parse_cron(&expr)?, from, until)),
+        None => {
+            let at = match non_empty(&spec.start_date) {
+                Some(date) => parse_run_at(&format!("{} {:02}:{:02}", date, hour, minute))?,
+                None => {
+                    let today = from
+                        .date()
+                        .and_hms_opt(hour, minute, 0)
+                        .ok_or_else(|| format!("Invalid time '{}'", start_time))?;
+                    if today > from {
+                        today
+                    } else {
+                        today + chrono::Duration::days(1)
+                    }
+                }
+            };
+            Ok(once(at))
+        }
+    }
+}
+
+/// When an existing task fires in `(from, until]`: every run of its cron
+/// schedule(s) where the schedule is cron, otherwise just its next run.
+fn task_runs_between(
+    task: &ScheduledTask,
+    from: chrono::NaiveDateTime,
+    until: chrono::NaiveDateTime,
+) -> Vec<chrono::NaiveDateTime> {
+    if !task.enabled {
+        return Vec::new();
+    }
+    let crons: Vec<CronExpr> = task
+        .schedule_spec
+        .split("; ")
+        .map(parse_cron)
+        .collect::<Result<_, _>>()
+        .unwrap_or_default();
+    // One-shot crontab lines only fire on the date their next run names
+    if !crons.is_empty() && task.schedule_kind != "once" {
+        let mut runs: Vec<chrono::NaiveDateTime> = crons
+            .iter()
+            .flat_map(|cron| cron_runs_between(cron, from, until))
+            .collect();
+        runs.sort();
+        runs.dedup();
+        return runs;
+    }
+    task.next_run
+        .as_deref()
+        .and_then(|t| chrono::NaiveDateTime::parse_from_str(t, "%Y-%m-%d %H:%M").ok())
+        .filter(|t| *t > from && *t <= until)
+        .into_iter()
+        .collect()
+}
+
+/// An existing task that would run close to a proposed schedule. `first`
+/// is the earliest clash (the proposed run and the existing task's run
+/// nearest to it) and `overlaps` how many proposed runs clash in total.
+#[derive(serde::Serialize)]
+struct ScheduleConflict {
+    task_name: String,
+    first_proposed_run: String,
+    first_existing_run: String,
+    overlaps: usize,
+}
+
+/// Warns about a proposed schedule colliding with existing AUI tasks: any
+/// task with a run within `window_minutes` (default 0, the same minute) of
+/// one of the proposed runs over the next 24 hours. Advisory only; Windows
+/// tasks are compared by their next run.
+#[tauri::command]
+fn detect_schedule_conflicts(
+    new_spec: ScheduleSpec,
+    window_minutes: Option<u32>,
+) -> Result<Vec<ScheduleConflict>, String> {
+    let window = chrono::Duration::minutes(window_minutes.unwrap_or(0).into());
+    let from = chrono::Local::now().naive_local();
+    let until = from + chrono::Duration::hours(24);
+    let proposed = spec_runs_between(&new_spec, from, until)?;
+    let format = |t: &chrono::NaiveDateTime| t.format("%Y-%m-%d %H:%M").to_string();
+
+    let mut conflicts = Vec::new();
+    for task in scheduled_tasks()? {
+        let existing = task_runs_between(&task, from, until);
+        let mut first = None;
+        let mut overlaps = 0;
+        for run in &proposed {
+            // Earliest existing run not before `run - window`
+            let i = existing.partition_point(|t| *t < *run - window);
+            let Some(near) = existing.get(i).filter(|t| **t <= *run + window) else {
+                continue;
+            };
+            overlaps += 1;
+            first.get_or_insert((*run, *near));
+        }
+        if let Some((proposed_run, existing_run)) = first {
+            conflicts.push(ScheduleConflict {
+                task_name: task.task_name,
+                first_proposed_run: format(&proposed_run),
+                first_existing_run: format(&existing_run),
+                overlaps,
+            });
+        }
+    }
+    Ok(conflicts)
+}
+
+/// One variable of a task's expected environment. `source` says where the
+/// scheduled value comes from; `differs` is set when it isn't what AUI's
+/// own environment has.
+#[derive(serde::Serialize)]
+struct TaskEnvVar {
+    name: String,
+    scheduled: Option<String>,
+    interactive: Option<String>,
+    source: String,
+    differs: bool,
+}
+
+/// The environment a scheduled task is expected to run with, compared to
+/// the interactive one. `missing_path_entries` are PATH directories the
+/// task won't see and `interactive_only` names variables it won't have.
+#[derive(serde::Serialize)]
+struct TaskEnvironment {
+    task_name: Option<String>,
+    script_path: Option<String>,
+    variables: Vec<TaskEnvVar>,
+    missing_path_entries: Vec<String>,
+    interactive_only: Vec<String>,
+}
+
+/// Variables a generated node wrapper (see `node_script`) exports before
+/// running the real script. Empty for scripts that aren't wrappers.
+fn wrapper_env(script_path: &str) -> Vec<(String, String)> {
+    let Ok(contents) = std::fs::read_to_string(script_path) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            #[cfg(target_os = "windows")]
+            {
+                let (name, value) = line.trim().strip_prefix("$env:")?.split_once(" = ")?;
+                let value = value.strip_prefix('\'')?.strip_suffix('\'')?;
+                Some((name.to_string(), value.replace("''", "'")))
+            }
+            #[cfg(not(target_os = "windows"))]
+            {
+                let (name, value) = line.trim().strip_prefix("export ")?.split_once('=')?;
+                Some((name.to_string(), shell_words(value).concat()))
+            }
+        })
+        .collect()
+}
+
+/// The base environment the platform scheduler starts tasks with, as
+/// (name, value, source).
+fn scheduler_base_env(task_name: Option<&str>) -> Result<Vec<(String, String, String)>, String> {
+    let mut env: Vec<(String, String, String)> = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = task_name;
+        // Tasks get the user's profile environment from the registry, not
+        // the environment of whatever process created them
+        let output = hidden_command(&resolve_powershell()?)
+            .args([
+                "-NoProfile",
+                "-Command",
+                "[Environment]::GetEnvironmentVariable('Path','Machine') + ';' + \
+                 [Environment]::GetEnvironmentVariable('Path','User')",
+            ])
+            .output()
+            .map_err(|e| format!("Failed to run PowerShell: {}", e))?;
+        let path = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .trim_matches(';')
+            .to_string();
+        env.push(("Path".into(), path, "user profile (registry)".into()));
+        for name in ["USERPROFILE", "USERNAME", "TEMP", "APPDATA"] {
+            if let Ok(value) = std::env::var(name) {
+                env.push((name.into(), value, "user profile".into()));
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        // What cron itself sets; vixie cron and cronie agree on these
+        let user = std::env::var("USER")
+            .or_else(|_| std::env::var("LOGNAME"))
+            .unwrap_or_default();
+        for (name, value) in [
+            ("PATH", "/usr/bin:/bin".to_string()),
+            ("SHELL", "/bin/sh".to_string()),
+            ("HOME", std::env::var("HOME").unwrap_or_default()),
+            ("LOGNAME", user.clone()),
+            ("USER", user),
+        ] {
+            env.push((name.into(), value, "cron default".into()));
+        }
+
+        // Assignments in the crontab apply to the lines below them
+        let crontab = read_crontab()?;
+        let marker = task_name.map(|n| format!("# AUI:{}", n));
+        for line in crontab.lines() {
+            let line = line.trim();
+            if marker
+                .as_ref()
+                .is_some_and(|m| line.ends_with(m.as_str()) && !line.starts_with('#'))
+            {
+                break;
+            }
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            let name = name.trim();
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                continue;
+            }
+            // cron only strips one pair of matching quotes
+            let value = value.trim();
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|q| value.strip_prefix(*q)?.strip_suffix(*q))
+                .unwrap_or(value)
+                .to_string();
+            env.retain(|(n, _, _)| n != name);
+            env.push((name.to_string(), value, "crontab".into()));
+        }
+    }
+
+    Ok(env)
+}
+
+/// The directories of the `interactive` PATH that the `scheduled` one
+/// lacks, in order and without repeats.
+fn missing_path_dirs(scheduled: &str, interactive: &str) -> Vec<String> {
+    let separator = if cfg!(target_os = "windows") {
+        ';'
+    } else {
+        ':'
+    };
+    let scheduled_dirs: Vec<String> = scheduled
+        .split(separator)
+        .map(|d| d.trim_end_matches(['/', '\\']).to_lowercase())
+        .collect();
+    let mut missing: Vec<String> = Vec::new();
+    for dir in interactive.split(separator) {
+        let normalized = dir.trim_end_matches(['/', '\\']).to_lowercase();
+        if !dir.is_empty()
+            && !scheduled_dirs.contains(&normalized)
+            && !missing.iter().any(|d| d == dir)
+        {
+            missing.push(dir.to_string());
+        }
+    }
+    missing
+}
+
+/// The PATH scheduled tasks start with next to AUI's own, see
+/// `get_scheduled_path`.
+#[derive(serde::Serialize)]
+struct ScheduledPath {
+    scheduled: String,
+    source: String,
+    interactive: String,
+    missing: Vec<String>,
+}
+
+/// The PATH a scheduled task will see, for the common "works in a terminal,
+/// can't find the tool when scheduled" failure: cron's minimal
+/// `/usr/bin:/bin` unless the crontab assigns PATH, or the user profile's
+/// PATH from the registry on Windows. `missing` lists the directories of
+/// the interactive PATH it lacks; `prepend_path` on `create_scheduled_task`
+/// adds them back for a task.
+#[tauri::command]
+fn get_scheduled_path() -> Result<ScheduledPath, String> {
+    let (scheduled, source) = scheduler_base_env(None)?
+        .into_iter()
+        .rev()
+        .find(|(n, _, _)| n.eq_ignore_ascii_case("PATH"))
+        .map(|(_, value, source)| (value, source))
+        .unwrap_or_default();
+    let interactive = std::env::var("PATH").unwrap_or_default();
+    Ok(ScheduledPath {
+        missing: missing_path_dirs(&scheduled, &interactive),
+        scheduled,
+        source,
+        interactive,
+    })
+}
+
+/// Previews the environment a scheduled task will run with, for diagnosing
+/// scripts that work in a terminal but not when scheduled. Pass an existing
+/// `task_name`, or a `script_path` to preview a task not created yet. The
+/// scheduler's base environment (cron's minimal defaults plus crontab
+/// assignments, or the Windows user profile) is combined with variables a
+/// node wrapper injects and compared against AUI's own environment.
+#[tauri::command]
+fn preview_task_environment(
+    task_name: Option<String>,
+    script_path: Option<String>,
+) -> Result<TaskEnvironment, String> {
+    let task_name = task_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+    if let Some(name) = &task_name {
+        validate_task_name(name)?;
+    }
+    let script_path = match &task_name {
+        Some(name) => Some(
+            scheduled_tasks()?
+                .into_iter()
+                .find(|t| t.task_name == *name)
+                .ok_or_else(|| format!("No scheduled task named '{}'", name))?
+                .script_path,
+        ),
+        None => script_path
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| expand_user_path(&p)),
+    };
+
+    let mut scheduled = scheduler_base_env(task_name.as_deref())?;
+    for (name, value) in script_path.as_deref().map(wrapper_env).unwrap_or_default() {
+        scheduled.retain(|(n, _, _)| *n != name);
+        scheduled.push((name, value, "AUI wrapper".into()));
+    }
+
+    let variables: Vec<TaskEnvVar> = scheduled
+        .iter()
+        .map(|(name, value, source)| {
+            let interactive = std::env::var(name).ok();
+            TaskEnvVar {
+                name: name.clone(),
+                differs: interactive.as_deref() != Some(value.as_str()),
+                scheduled: Some(value.clone()),
+                interactive,
+                source: source.clone(),
+            }
+        })
+        .collect();
+
+    let scheduled_path = scheduled
+        .iter()
+        .find(|(n, _, _)| n.eq_ignore_ascii_case("PATH"))
+        .map(|(_, v, _)| v.clone())
+        .unwrap_or_default();
+    let missing_path_entries =
+        missing_path_dirs(&scheduled_path, &std::env::var("PATH").unwrap_or_default());
+
+    // cron passes nothing else through; Windows tasks get the whole profile
+    let mut interactive_only: Vec<String> = if cfg!(target_os = "windows") {
+        Vec::new()
+    } else {
+        std::env::vars()
+            .map(|(name, _)| name)
+            .filter(|name| !scheduled.iter().any(|(n, _, _)| n == name))
+            .collect()
+    };
+    interactive_only.sort();
+
+    Ok(TaskEnvironment {
+        task_name,
+        script_path,
+        variables,
+        missing_path_entries,
+        interactive_only,
+    })
+}
+
+#[derive(serde::Serialize, Default)]
+struct ReconcileResult {
+    orphaned: Vec<String>,
+    missing: Vec<String>,
+    deleted: Vec<String>,
+    created: Vec<String>,
+    errors: Vec<String>,
+}
+
+/// Compares the tasks `schedule_node` created for a graph with the graph's
+/// current nodes. `orphaned` lists tasks whose node is gone and `missing`
+/// the ids of nodes that have a schedule but no task. With `fix`, orphans
+/// are deleted and missing tasks created; failures land in `errors`.
+#[tauri::command]
+fn reconcile_schedules(
+    app: tauri::AppHandle,
+    graph_name: String,
+    fix: Option<bool>,
+) -> Result<ReconcileResult, String> {
+    let graph_path = graph_file(&app, &graph_name)?;
+    let graph = load_graph(&graph_path)?;
+    let tasks = aui_task_names()?;
+
+    let prefix = node_task_name(&graph_path, "");
+    let node_tasks: HashMap<String, &GraphNode> = graph
+        .nodes
+        .iter()
+        .map(|node| (node_task_name(&graph_path, &node.id), node))
+        .collect();
+
+    let mut result = ReconcileResult {
+        orphaned: tasks
+            .iter()
+            .filter(|task| task.starts_with(&prefix) && !node_tasks.contains_key(*task))
+            .cloned()
+            .collect(),
+        ..Default::default()
+    };
+    let mut missing: Vec<(&String, &GraphNode)> = node_tasks
+        .iter()
+        .filter(|(task, node)| node.schedule.is_some() && !tasks.contains(task))
+        .map(|(task, node)| (task, *node))
+        .collect();
+    missing.sort_by(|a, b| a.0.cmp(b.0));
+    result.missing = missing.iter().map(|(_, node)| node.id.clone()).collect();
+
+    if fix.unwrap_or(false) {
+        for task in result.orphaned.clone() {
+            match delete_scheduled_task(app.clone(), task.clone()) {
+                Ok(_) => result.deleted.push(task),
+                Err(e) => result.errors.push(format!("{}: {}", task, e)),
+            }
+        }
+        for (_, node) in missing {
+            match schedule_node(app.clone(), graph_name.clone(), node.id.clone(), None) {
+                Ok(task) => result.created.push(task.task_name),
+                Err(e) => result.errors.push(format!("{}: {}", node.id, e)),
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// The login shell reported by `get_default_shell`, and where it came from
+/// (`env` for `$SHELL`, `passwd` for the user database, or `default`).
+#[derive(serde::Serialize)]
+struct ShellInfo {
+    path: String,
+    name: String,
+    source: String,
+}
+
+fn detect_default_shell() -> ShellInfo {
+    let info = |path: String, source: &str| ShellInfo {
+        name: std::path::Path::new(&path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone()),
+        path,
+        source: source.to_string(),
+    };
+
+    if cfg!(target_os = "windows") {
+        return info("powershell.exe".to_string(), "default");
+    }
+
+    if let Some(shell) = std::env::var("SHELL").ok().filter(|s| !s.trim().is_empty()) {
+        return info(shell, "env");
+    }
+
+    // Fall back to the user's login shell field in /etc/passwd
+    let user = std::env::var("USER").or_else(|_| std::env::var("LOGNAME"));
+    if let (Ok(user), Ok(passwd)) = (user, std::fs::read_to_string("/etc/passwd")) {
+        let shell = passwd.lines().find_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            (fields.len() >= 7 && fields[0] == user).then(|| fields[6].to_string())
+        });
+        if let Some(shell) = shell.filter(|s| !s.is_empty()) {
+            return info(shell, "passwd");
+        }
+    }
+
+    info("/bin/bash".to_string(), "default")
+}
+
+/// Reports the user's default shell from `$SHELL` or `/etc/passwd`.
+/// On Windows this is always PowerShell, which AUI uses for scripts.
+#[tauri::command]
+fn get_default_shell() -> ShellInfo {
+    detect_default_shell()
+}
+
+/// Validates the `shell` option of the terminal/scheduling commands. `None`
+/// means the script's detected interpreter is used (bash unless its
+/// shebang or extension says otherwise).
+fn resolve_shell(shell: Option<String>) -> Result<Option<String>, String> {
+    let shell = match shell.map(|s| s.trim().to_string()) {
+        Some(s) if !s.is_empty() => s,
+        _ => return Ok(None),
+    };
+    if !shell
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '.' | '_' | '-'))
+    {
+        return Err(format!("Invalid shell '{}'", shell));
+    }
+    Ok(Some(shell))
+}
+
+/// How a launched terminal is tracked. Windows and Linux keep the spawned
+/// process (on Windows the hidden `cmd /c start /wait` that parents the
+/// console); macOS keeps Terminal.app's window id.
+enum TerminalHandle {
+    #[cfg(not(target_os = "macos"))]
+    Process(std::process::Child),
+    #[cfg(target_os = "macos")]
+    MacWindow(String),
+}
+
+struct TerminalEntry {
+    info: TerminalInfo,
+    handle: TerminalHandle,
+}
+
+/// Terminals opened by `open_terminal`, keyed by terminal id.
+#[derive(Default)]
+struct Terminals {
+    next_id: std::sync::atomic::AtomicU64,
+    entries: Mutex<HashMap<String, TerminalEntry>>,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct TerminalInfo {
+    id: String,
+    pid: Option<u32>,
+    script_path: String,
+    launched_at: u64,
+}
+
+/// Kills `pid` and everything under it: `taskkill /T /F` on Windows, and
+/// `signal` sent to the process group led by `pid` elsewhere.
+fn kill_process_tree(pid: u32, signal: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = signal;
+        hidden_command("taskkill.exe")
+            .args(["/T", "/F", "/PID", &pid.to_string()])
+            .output()
+            .map_err(|e| format!("Failed to run taskkill: {}", e))?;
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        StdCommand::new("kill")
+            .args([&format!("-{}", signal), "--", &format!("-{}", pid)])
+            .output()
+            .map_err(|e| format!("Failed to run kill: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Politely asks `pid` and its children to exit: SIGTERM to its process
+/// group, or `taskkill /T` without `/F` (WM_CLOSE) on Windows. WM_CLOSE
+/// only reaches processes with a window, so for a script started without
+/// a console window taskkill refuses, and its message is the error.
+fn request_process_exit(pid: u32) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let output = hidden_command("taskkill.exe")
+            .args(["/T", "/PID", &pid.to_string()])
+            .output()
+            .map_err(|e| format!("Failed to run taskkill: {}", e))?;
+        if !output.status.success() {
+            let message = [&output.stderr, &output.stdout]
+                .iter()
+                .map(|out| String::from_utf8_lossy(out).trim().to_string())
+                .find(|out| !out.is_empty())
+                .unwrap_or_else(|| format!("taskkill exited with {}", output.status));
+            return Err(message);
+        }
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    kill_process_tree(pid, "TERM")
+}
+
+/// Whether `pid`, or on Unix any process in the group it leads, is running.
+fn process_alive(pid: u32) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        hidden_command("tasklist.exe")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH", "/FO", "CSV"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).contains(&format!("\"{}\"", pid)))
+            .unwrap_or(false)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        [format!("-{}", pid), pid.to_string()].iter().any(|target| {
+            StdCommand::new("kill")
+                .args(["-0", "--", target])
+                .stderr(std::process::Stdio::null())
+                .status()
+                .is_ok_and(|s| s.success())
+        })
+    }
+}
+
+/// When `pid` started, as the OS reports it, so a recorded pid can be told
+/// apart from a later process that reused it. `None` when it isn't running.
+fn process_start_time(pid: u32) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    let output = hidden_command(&resolve_powershell().ok()?)
+        .args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "(Get-Process -Id {} -ErrorAction Stop).StartTime.ToUniversalTime().ToString('o')",
+                pid
+            ),
+        ])
+        .output()
+        .ok()?;
+    #[cfg(not(target_os = "windows"))]
+    let output = StdCommand::new("ps")
+        .args(["-o", "lstart=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let started = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !started.is_empty()).then_some(started)
+}
+
+/// Whether the process a recorded run started is still the one running
+/// under its pid. On Unix a group whose leader has exited still counts, as
+/// its pid can't be reused while the group exists.
+fn run_process_matches(run: &RunInfo) -> bool {
+    match process_start_time(run.pid) {
+        Some(started) => run.process_started.as_deref() == Some(started.as_str()),
+        #[cfg(not(target_os = "windows"))]
+        None => process_alive(run.pid),
+        #[cfg(target_os = "windows")]
+        None => false,
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl TerminalHandle {
+    fn is_alive(&mut self) -> bool {
+        match self {
+            #[cfg(not(target_os = "macos"))]
+            TerminalHandle::Process(child) => matches!(child.try_wait(), Ok(None)),
+            #[cfg(target_os = "macos")]
+            TerminalHandle::MacWindow(window_id) => StdCommand::new("osascript")
+                .args([
+                    "-e",
+                    &format!(
+                        "tell application \"Terminal\" to exists window id {}",
+                        window_id
+                    ),
+                ])
+                .output()
+                .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "true")
+                .unwrap_or(false),
+        }
+    }
+
+    /// Closes the window and everything running in it.
+    fn close(self) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_os = "macos"))]
+            TerminalHandle::Process(mut child) => {
+                // The terminal was spawned as its own process group leader
+                kill_process_tree(child.id(), "TERM")?;
+                // Reap the child without blocking the caller
+                std::thread::spawn(move || {
+                    let _ = child.wait();
+                });
+                Ok(())
+            }
+            #[cfg(target_os = "macos")]
+            TerminalHandle::MacWindow(window_id) => {
+                StdCommand::new("osascript")
+                    .args([
+                        "-e",
+                        &format!(
+                            "tell application \"Terminal\" to close window id {}",
+                            window_id
+                        ),
+                    ])
+                    .output()
+                    .map_err(|e| format!("Failed to close terminal: {}", e))?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Terminal emulators `open_terminal` tries on Linux, in order, with the
+/// arguments that precede the command to run.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const LINUX_TERMINALS: [(&str, &[&str]); 7] = [
+    ("x-terminal-emulator", &["-e"]),
+    ("gnome-terminal", &["--"]),
+    ("konsole", &["-e"]),
+    ("kitty", &[]),
+    ("alacritty", &["-e"]),
+    ("wezterm", &["start", "--"]),
+    ("xterm", &["-e"]),
+];
+
+/// A terminal emulator known on this platform and where it was found.
+#[derive(serde::Serialize)]
+struct AvailableTerminal {
+    name: String,
+    installed: bool,
+    path: Option<String>,
+}
+
+/// Reports which of the terminal emulators known for this platform are
+/// installed, for the terminal picker in settings.
+#[tauri::command]
+fn list_available_terminals() -> Vec<AvailableTerminal> {
+    #[cfg(target_os = "windows")]
+    let candidates: Vec<(&str, Option<std::path::PathBuf>)> = vec![
+        ("Windows Terminal", find_in_path("wt.exe")),
+        ("Console Host", find_in_path("conhost.exe")),
+    ];
+
+    #[cfg(target_os = "macos")]
+    let candidates: Vec<(&str, Option<std::path::PathBuf>)> = {
+        let app = |paths: &[&str]| {
+            paths
+                .iter()
+                .map(std::path::PathBuf::from)
+                .find(|p| p.is_dir())
+        };
+        vec![
+            (
+                "Terminal",
+                app(&[
+                    "/System/Applications/Utilities/Terminal.app",
+                    "/Applications/Utilities/Terminal.app",
+                ]),
+            ),
+            ("iTerm", app(&["/Applications/iTerm.app"])),
+        ]
+    };
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let candidates: Vec<(&str, Option<std::path::PathBuf>)> = LINUX_TERMINALS
+        .iter()
+        .map(|(name, _)| (*name, find_in_path(name)))
+        .collect();
+
+    candidates
+        .into_iter()
+        .map(|(name, path)| AvailableTerminal {
+            name: name.to_string(),
+            installed: path.is_some(),
+            path: path.map(|p| p.to_string_lossy().to_string()),
+        })
+        .collect()
+}
+
+/// GUI text editors tried on Linux when neither `editor` nor `$VISUAL` is
+/// set.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const LINUX_EDITORS: [&str; 6] = [
+    "gnome-text-editor",
+    "gedit",
+    "kate",
+    "mousepad",
+    "xed",
+    "pluma",
+];
+
+/// Opens a file in a text editor: `editor` when given (e.g. from settings),
+/// otherwise Notepad on Windows, TextEdit (`open -t`) on macOS, and
+/// `$VISUAL`, a known GUI editor or `$EDITOR` in a terminal on Linux.
+/// Returns the editor command that was launched.
+#[tauri::command]
+fn edit_file(path: String, editor: Option<String>) -> Result<String, String> {
+    let path = expand_user_path(&path);
+    if !std::path::Path::new(&path).is_file() {
+        return Err(format!("File not found: {}", path));
+    }
+
+    let spawn = |program: &str, args: &[&str]| -> Result<String, String> {
+        let mut child = StdCommand::new(program)
+            .args(args)
+            .arg(&path)
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", program, e))?;
+        // Reap the editor when it closes without blocking the caller
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+        Ok(std::iter::once(program)
+            .chain(args.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" "))
+    };
+
+    if let Some(editor) = editor
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+    {
+        return spawn(&editor, &[]);
+    }
+
+    #[cfg(target_os = "windows")]
+    return spawn("notepad.exe", &[]);
+
+    #[cfg(target_os = "macos")]
+    return spawn("open", &["-t"]);
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        if let Some(visual) = std::env::var("VISUAL")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+        {
+            return spawn(visual.trim(), &[]);
+        }
+        if let Some(gui) = LINUX_EDITORS.iter().find(|e| find_in_path(e).is_some()) {
+            return spawn(gui, &[]);
+        }
+        // A terminal editor needs a terminal window to run in
+        let terminal_editor = std::env::var("EDITOR")
+            .ok()
+            .filter(|e| !e.trim().is_empty())
+            .ok_or("No text editor found; set $VISUAL or $EDITOR, or choose one in settings")?;
+        let (terminal, exec_args) = LINUX_TERMINALS
+            .iter()
+            .find(|(t, _)| find_in_path(t).is_some())
+            .ok_or("No terminal emulator found to run $EDITOR in")?;
+        let mut args: Vec<&str> = exec_args.to_vec();
+        args.push(terminal_editor.trim());
+        spawn(terminal, &args)
+    }
+}
+
+/// Opens a visible terminal window running the given script and returns the
+/// id it is tracked under for `list_terminals` / `close_terminal`.
+/// On Windows, uses CREATE_NEW_CONSOLE to bypass Tauri's CREATE_NO_WINDOW flag.
+/// On macOS/Linux the script runs under `shell`, or its detected interpreter.
+#[tauri::command]
+fn open_terminal(
+    terminals: tauri::State<'_, Terminals>,
+    script_path: String,
+    shell: Option<String>,
+) -> Result<TerminalInfo, String> {
+    let shell = resolve_shell(shell)?;
+    let script_path = expand_user_path(&script_path);
+
+    #[cfg(target_os = "windows")]
+    let handle = {
+        let _ = shell;
+        // Use `cmd /c start` to launch PowerShell as a fully independent process.
+        // Direct spawning via CREATE_NEW_CONSOLE gets killed in the Tauri context.
+        // `start` uses ShellExecuteEx internally which fully detaches the process.
+        // `/wait` keeps the hidden cmd.exe alive as the console's parent so the
+        // window can be tracked and closed with `taskkill /T`.
+        // We use raw_arg to control the exact command line (no Rust auto-quoting).
+        let raw = format!(
+            "/c start \"Deploy\" /wait {}",
+            windows_script_command(&script_path, true)?
+        );
+        let child = StdCommand::new("cmd.exe")
+            .raw_arg(raw)
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW for cmd.exe itself
+            .spawn()
+            .map_err(|e| format!("Failed to open terminal: {}", e))?;
+        TerminalHandle::Process(child)
+    };
+
+    #[cfg(target_os = "macos")]
+    let handle = {
+        let apple_script = format!(
+            r#"tell application "Terminal"
+            activate
+            do script "{}"
+        end tell"#,
+            unix_script_command(&script_path, shell.as_deref())
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+        );
+        let output = StdCommand::new("osascript")
+            .args(["-e", &apple_script])
+            .output()
+            .map_err(|e| format!("Failed to open terminal: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to open terminal: {}", stderr.trim()));
+        }
+        // `do script` answers with e.g. "tab 1 of window id 1234"
+        let reply = String::from_utf8_lossy(&output.stdout).to_string();
+        let window_id = reply
+            .rsplit("window id ")
+            .next()
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+            .ok_or_else(|| format!("Unexpected reply from Terminal: {}", reply.trim()))?;
+        TerminalHandle::MacWindow(window_id)
+    };
+
+    #[cfg(target_os = "linux")]
+    let handle = {
+        use std::os::unix::process::CommandExt;
+
+        // Try common terminal emulators in order. Each gets its own process
+        // group so closing it also stops the script running inside.
+        let argv = unix_script_argv(&script_path, shell.as_deref());
+        let child = LINUX_TERMINALS
+            .iter()
+            .find_map(|(term, exec_args)| {
+                StdCommand::new(term)
+                    .args(*exec_args)
+                    .args(&argv)
+                    .process_group(0)
+                    .spawn()
+                    .ok()
+            })
+            .ok_or("No terminal emulator found")?;
+        TerminalHandle::Process(child)
+    };
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    return Err("Unsupported: opening a terminal is not supported on this platform".into());
+
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    {
+        let id = format!(
+            "term-{}",
+            terminals.next_id.fetch_add(1, Ordering::Relaxed) + 1
+        );
+        let info = TerminalInfo {
+            id: id.clone(),
+            pid: match &handle {
+                #[cfg(not(target_os = "macos"))]
+                TerminalHandle::Process(child) => Some(child.id()),
+                #[cfg(target_os = "macos")]
+                TerminalHandle::MacWindow(_) => None,
+            },
+            script_path,
+            launched_at: unix_timestamp(),
+        };
+        terminals.entries.lock().map_err(|e| e.to_string())?.insert(
+            id,
+            TerminalEntry {
+                info: info.clone(),
+                handle,
+            },
+        );
+        Ok(info)
+    }
+}
+
+/// Lists terminals opened by AUI that are still running. Entries whose
+/// window has exited are pruned. Note that some emulators (gnome-terminal)
+/// hand off to a server process immediately and so can't be tracked.
+#[tauri::command]
+fn list_terminals(terminals: tauri::State<'_, Terminals>) -> Result<Vec<TerminalInfo>, String> {
+    let mut entries = terminals.entries.lock().map_err(|e| e.to_string())?;
+    entries.retain(|_, entry| entry.handle.is_alive());
+
+    let mut list: Vec<TerminalInfo> = entries.values().map(|e| e.info.clone()).collect();
+    list.sort_by_key(|t| t.launched_at);
+    Ok(list)
+}
+
+/// Closes a terminal opened by AUI, including the script running in it.
+/// Windows kills the whole process tree; Unix signals the process group.
+#[tauri::command]
+fn close_terminal(terminals: tauri::State<'_, Terminals>, id: String) -> Result<(), String> {
+    let entry = terminals
+        .entries
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&id)
+        .ok_or_else(|| format!("No terminal with id '{}'", id))?;
+    entry.handle.close()
+}
+
+/// Builds a headless invocation of `script_path` under its interpreter (or
+/// `shell` on macOS/Linux) with `args` after it, with no console window on
+/// Windows.
+fn script_command(
+    script_path: &str,
+    shell: Option<&str>,
+    args: &[String],
+) -> Result<StdCommand, String> {
+    if !std::path::Path::new(script_path).is_file() {
+        return Err(format!("Script not found: {}", script_path));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = shell;
+        let mut line = windows_script_command(script_path, false)?;
+        for arg in args {
+            if arg.contains(['"', '%', '^', '&', '|', '<', '>']) {
+                return Err(format!(
+                    "Unsupported: argument '{}' can't be passed through cmd.exe",
+                    arg
+                ));
+            }
+            line.push_str(&format!(" \"{}\"", arg));
+        }
+        // `/s` makes cmd.exe strip exactly the outer quotes, leaving the
+        // quoting inside the script command line intact
+        let mut command = hidden_command("cmd.exe");
+        command.raw_arg(format!("/s /c \"{}\"", line));
+        Ok(command)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let argv = unix_script_argv(script_path, shell);
+        let mut command = hidden_command(&argv[0]);
+        command.args(&argv[1..]).args(args);
+        Ok(command)
+    }
+}
+
+/// Outcome of a headless script run. `exit_code` is absent when the
+/// process was killed by a signal or for running past its timeout, in which
+/// case `timed_out` is set and the output is what arrived until then.
+/// `truncated` is set when stdout or stderr outgrew the capture limit and
+/// only its beginning and end were kept. A run retried on failure reports
+/// its last attempt, with `attempt_exit_codes` holding every attempt's.
+#[derive(serde::Serialize, Clone)]
+struct ScriptRun {
+    success: bool,
+    exit_code: Option<i32>,
+    timed_out: bool,
+    stdout: String,
+    stderr: String,
+    duration_ms: u64,
+    truncated: bool,
+    attempts: u32,
+    attempt_exit_codes: Vec<Option<i32>>,
+}
+
+/// How much of each of stdout and stderr a captured run keeps by default.
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+
+/// Marks where the middle of over-long output was dropped.
+const OUTPUT_TRUNCATED_MARKER: &str = "\n[output truncated]\n";
+
+/// Output captured from a pipe, bounded to `limit` bytes: the first half
+/// of the limit is kept as it arrives and the last half as a rolling tail,
+/// so a runaway script costs a fixed amount of memory.
+struct BoundedOutput {
+    limit: usize,
+    head: Vec<u8>,
+    tail: std::collections::VecDeque<u8>,
+    truncated: bool,
+}
+
+impl BoundedOutput {
+    fn new(limit: usize) -> Self {
+        BoundedOutput {
+            limit,
+            head: Vec::new(),
+            tail: std::collections::VecDeque::new(),
+            truncated: false,
+        }
+    }
+
+    fn push(&mut self, mut bytes: &[u8]) {
+        let head_room = (self.limit - self.limit / 2).saturating_sub(self.head.len());
+        let take = head_room.min(bytes.len());
+        self.head.extend_from_slice(&bytes[..take]);
+        bytes = &bytes[take..];
+
+        let tail_limit = self.limit / 2;
+        if bytes.len() > tail_limit {
+            self.truncated = true;
+            self.tail.clear();
+            bytes = &bytes[bytes.len() - tail_limit..];
+        }
+        self.tail.extend(bytes);
+        if self.tail.len() > tail_limit {
+            self.truncated = true;
+            let excess = self.tail.len() - tail_limit;
+            self.tail.drain(..excess);
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut bytes = self.head.clone();
+        if self.truncated {
+            bytes.extend_from_slice(OUTPUT_TRUNCATED_MARKER.as_bytes());
+        }
+        bytes.extend(self.tail.iter());
+        String::from_utf8_lossy(&bytes).to_string()
+    }
+}
+
+/// Runs `command` to completion, capturing its output. Past `timeout` the
+/// whole process tree is killed and the partial output returned.
+fn run_captured(
+    command: StdCommand,
+    label: &str,
+    timeout: Option<Duration>,
+) -> Result<ScriptRun, String> {
+    run_captured_with_stdin(
+        command,
+        label,
+        timeout,
+        std::process::Stdio::null(),
+        DEFAULT_MAX_OUTPUT_BYTES,
+    )
+}
+
+/// `run_captured`, with `stdin` fed to the process instead of nothing and
+/// at most `max_output` bytes of each of stdout and stderr kept (see
+/// `BoundedOutput`). Pipes are drained to the end either way, so a script
+/// with too much output keeps running instead of blocking on a full pipe.
+fn run_captured_with_stdin(
+    mut command: StdCommand,
+    label: &str,
+    timeout: Option<Duration>,
+    stdin: std::process::Stdio,
+    max_output: usize,
+) -> Result<ScriptRun, String> {
+    use std::io::Read;
+    #[cfg(not(target_os = "windows"))]
+    use std::os::unix::process::CommandExt;
+
+    command
+        .stdin(stdin)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    // Its own process group, so a timeout can kill the children too
+    #[cfg(not(target_os = "windows"))]
+    command.process_group(0);
+
+    let started = Instant::now();
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", label, e))?;
+
+    let capture = |pipe: Option<Box<dyn Read + Send>>| {
+        let buffer = Arc::new(Mutex::new(BoundedOutput::new(max_output)));
+        let sink = Arc::clone(&buffer);
+        let reader = std::thread::spawn(move || {
+            let Some(mut pipe) = pipe else {
+                return;
+            };
+            let mut chunk = [0u8; 8192];
+            while let Ok(read) = pipe.read(&mut chunk) {
+                if read == 0 {
+                    break;
+                }
+                if let Ok(mut sink) = sink.lock() {
+                    sink.push(&chunk[..read]);
+                }
+            }
+        });
+        (buffer, reader)
+    };
+    let (stdout, stdout_reader) = capture(
+        child
+            .stdout
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+    );
+    let (stderr, stderr_reader) = capture(
+        child
+            .stderr
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+    );
+
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {}
+            Err(e) => return Err(format!("Failed to wait for {}: {}", label, e)),
+        }
+        if timeout.is_some_and(|t| started.elapsed() >= t) {
+            timed_out = true;
+            let _ = kill_process_tree(child.id(), "KILL");
+            let _ = child.kill();
+            // Reap the child so it doesn't linger as a zombie
+            let _ = child.wait();
+            break None;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    let _ = stdout_reader.join();
+    let _ = stderr_reader.join();
+
+    let text = |buffer: &Mutex<BoundedOutput>| {
+        buffer
+            .lock()
+            .map(|b| (b.text(), b.truncated))
+            .unwrap_or_default()
+    };
+    let (stdout, stdout_truncated) = text(&stdout);
+    let (stderr, stderr_truncated) = text(&stderr);
+    Ok(ScriptRun {
+        success: status.is_some_and(|s| s.success()),
+        exit_code: status.and_then(|s| s.code()),
+        timed_out,
+        stdout,
+        stderr,
+        duration_ms: started.elapsed().as_millis() as u64,
+        truncated: stdout_truncated || stderr_truncated,
+        attempts: 1,
+        attempt_exit_codes: vec![status.and_then(|s| s.code())],
+    })
+}
+
+/// A script's Authenticode signature. `status` is PowerShell's
+/// `SignatureStatus` (`Valid`, `NotSigned`, `HashMismatch`, `UnknownError`
+/// ...), `signer` the signing certificate's subject, and `is_trusted` whether
+/// the signature is valid and chains to a trusted root.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ScriptSignature {
+    status: String,
+    signer: Option<String>,
+    message: Option<String>,
+    #[serde(default)]
+    is_trusted: bool,
+}
+
+/// Checks a script's digital signature with `Get-AuthenticodeSignature`
+/// (Windows only), so a scheduled deployment can be refused when its script
+/// was tampered with or isn't signed.
+#[tauri::command(async)]
+fn check_script_signature(script_path: String) -> Result<ScriptSignature, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let script_path = expand_user_path(&script_path);
+        if !std::path::Path::new(&script_path).is_file() {
+            return Err(format!("Script not found: {}", script_path));
+        }
+        let output = hidden_command(&resolve_powershell()?)
+            .args([
+                "-NoProfile",
+                "-Command",
+                &format!(
+                    "$sig = Get-AuthenticodeSignature -LiteralPath '{}'; \
+                     [pscustomobject]@{{ status = [string]$sig.Status; \
+                     signer = $sig.SignerCertificate.Subject; \
+                     message = $sig.StatusMessage }} | ConvertTo-Json -Compress",
+                    script_path.replace('\'', "''")
+                ),
+            ])
+            .output()
+            .map_err(|e| format!("Failed to run PowerShell: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Signature check failed: {}", stderr.trim()));
+        }
+        let mut signature: ScriptSignature = serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Unexpected Get-AuthenticodeSignature output: {}", e))?;
+        signature.is_trusted = signature.status == "Valid";
+        Ok(signature)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = script_path;
+        Err("Unsupported: script signatures are only checked on Windows".into())
+    }
+}
+
+/// Fails unless `script_path` carries a trusted signature, for commands
+/// called with `require_signed`.
+fn require_signed_script(script_path: &str) -> Result<(), String> {
+    let signature = check_script_signature(script_path.to_string())?;
+    if signature.is_trusted {
+        return Ok(());
+    }
+    Err(format!(
+        "Refusing to use {}: its signature is {}{}",
+        script_path,
+        signature.status,
+        signature
+            .message
+            .map(|m| format!(" ({})", m.trim()))
+            .unwrap_or_default()
+    ))
+}
+
+/// Commands in Windows scripts that need an elevated session, matched in
+/// lowercase, with what they do.
+const WINDOWS_ADMIN_PATTERNS: [(&str, &str); 19] = [
+    (
+        "#requires -runasadministrator",
+        "declares it must run as Administrator",
+    ),
+    ("start-service", "starts a Windows service"),
+    ("stop-service", "stops a Windows service"),
+    ("restart-service", "restarts a Windows service"),
+    ("set-service", "reconfigures a Windows service"),
+    ("new-service", "installs a Windows service"),
+    ("sc.exe ", "manages Windows services"),
+    ("net start ", "starts a Windows service"),
+    ("net stop ", "stops a Windows service"),
+    ("hklm:", "changes machine-wide registry settings"),
+    (
+        "hkey_local_machine",
+        "changes machine-wide registry settings",
+    ),
+    ("new-netfirewallrule", "changes firewall rules"),
+    ("netsh advfirewall", "changes firewall rules"),
+    (
+        "set-executionpolicy",
+        "changes the machine's execution policy",
+    ),
+    ("enable-windowsoptionalfeature", "installs Windows features"),
+    ("add-windowscapability", "installs Windows features"),
+    ("install-windowsfeature", "installs Windows features"),
+    ("bcdedit", "changes boot configuration"),
+    ("-scope allusers", "installs for all users"),
+];
+
+/// Commands in shell scripts that need root, as words they start with.
+const UNIX_ADMIN_PATTERNS: [(&str, &str); 14] = [
+    ("sudo ", "runs commands with sudo"),
+    ("systemctl start ", "starts a system service"),
+    ("systemctl stop ", "stops a system service"),
+    ("systemctl restart ", "restarts a system service"),
+    ("systemctl enable ", "enables a system service"),
+    ("service ", "manages a system service"),
+    ("apt-get ", "manages system packages"),
+    ("apt ", "manages system packages"),
+    ("dnf ", "manages system packages"),
+    ("yum ", "manages system packages"),
+    ("launchctl load /library", "loads a system launch daemon"),
+    ("iptables ", "changes firewall rules"),
+    ("ufw ", "changes firewall rules"),
+    ("mount ", "mounts filesystems"),
+];
+
+/// Commands that write into the directories they're used with.
+const WRITE_COMMANDS: [&str; 12] = [
+    "copy-item",
+    "move-item",
+    "set-content",
+    "add-content",
+    "out-file",
+    "new-item",
+    "remove-item",
+    "copy ",
+    "xcopy",
+    "robocopy",
+    "cp ",
+    "tee ",
+];
+
+/// A line of a script that likely needs elevated rights.
+#[derive(serde::Serialize)]
+struct PrivilegeFinding {
+    line: usize,
+    text: String,
+    reason: String,
+}
+
+/// What `analyze_script_privileges` found. `task_elevated` is whether the
+/// task would get those rights: run level "Highest" on Windows, root or
+/// password-less sudo elsewhere. `None` when no task was given and that
+/// couldn't be told.
+#[derive(serde::Serialize)]
+struct PrivilegeAnalysis {
+    script_path: String,
+    findings: Vec<PrivilegeFinding>,
+    task_elevated: Option<bool>,
+    warning: Option<String>,
+}
+
+/// The lines of a script that look like they need admin or root rights.
+/// Comment lines are skipped; `windows` picks PowerShell/batch patterns
+/// over shell ones.
+fn privileged_lines(contents: &str, windows: bool) -> Vec<PrivilegeFinding> {
+    let mut findings = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let text = line.trim();
+        let lower = text.to_lowercase();
+        let comment = if windows {
+            (lower.starts_with('#') && !lower.starts_with("#requires"))
+                || lower.starts_with("rem ")
+                || lower.starts_with("::")
+        } else {
+            lower.starts_with('#')
+        };
+        if text.is_empty() || comment {
+            continue;
+        }
+
+        let reason = if windows {
+            WINDOWS_ADMIN_PATTERNS
+                .iter()
+                .find(|(pattern, _)| lower.contains(pattern))
+                .map(|(_, reason)| reason.to_string())
+                .or_else(|| {
+                    let protected = [
+                        "program files",
+                        "c:\\windows",
+                        "$env:programfiles",
+                        "$env:windir",
+                    ]
+                    .iter()
+                    .any(|dir| lower.contains(dir));
+                    (protected && WRITE_COMMANDS.iter().any(|c| lower.contains(c)))
+                        .then(|| "writes to a protected system directory".to_string())
+                })
+        } else {
+            // Commands count at the start of the line or after a separator
+            let words: Vec<String> = lower
+                .split(['|', ';', '&', '('])
+                .map(|part| format!("{} ", part.trim()))
+                .collect();
+            UNIX_ADMIN_PATTERNS
+                .iter()
+                .find(|(pattern, _)| words.iter().any(|w| w.starts_with(pattern)))
+                .map(|(_, reason)| reason.to_string())
+                .or_else(|| {
+                    let protected = ["/etc/", "/usr/bin/", "/usr/lib/", "/opt/", "/var/lib/"]
+                        .iter()
+                        .any(|dir| lower.contains(dir));
+                    let writes =
+                        lower.contains('>') || WRITE_COMMANDS.iter().any(|c| lower.contains(c));
+                    (protected && writes).then(|| "writes to a system directory".to_string())
+                })
+        };
+        if let Some(reason) = reason {
+            findings.push(PrivilegeFinding {
+                line: number + 1,
+                text: text.to_string(),
+                reason,
+            });
+        }
+    }
+    findings
+}
+
+/// Scans a script for steps that need admin or root rights (service
+/// control, machine-wide registry or system directory writes, `sudo`,
+/// package managers ...) and warns when the task running it won't have
+/// them, since such tasks otherwise fail silently when scheduled. AUI
+/// creates Windows tasks at the limited run level, and cron can't answer a
+/// sudo password prompt. Pass `task_name` to check an existing task, or
+/// leave it out to check before scheduling. This is a heuristic: a clean
+/// result doesn't prove the script needs no privileges.
+#[tauri::command]
+fn analyze_script_privileges(
+    script_path: String,
+    task_name: Option<String>,
+) -> Result<PrivilegeAnalysis, String> {
+    let task_name = task_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+    if let Some(name) = &task_name {
+        validate_task_name(name)?;
+    }
+    let script_path = expand_user_path(&script_path);
+    let contents = std::fs::read_to_string(&script_path)
+        .map_err(|e| format!("Failed to read {}: {}", script_path, e))?;
+    let extension = std::path::Path::new(&script_path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let windows_script = matches!(extension.as_str(), "ps1" | "psm1" | "bat" | "cmd");
+    let findings = privileged_lines(&contents, windows_script);
+
+    #[cfg(target_os = "windows")]
+    let task_elevated = match &task_name {
+        Some(name) => {
+            Some(export_task_definition(name)?.contains("<RunLevel>HighestAvailable</RunLevel>"))
+        }
+        // schtasks tasks AUI creates run at the limited level
+        None => Some(false),
+    };
+    #[cfg(not(target_os = "windows"))]
+    let task_elevated = {
+        let _ = &task_name;
+        let root = StdCommand::new("id")
+            .arg("-u")
+            .output()
+            .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).trim() == "0");
+        // Without a terminal only password-less sudo works
+        let sudo = || {
+            StdCommand::new("sudo")
+                .args(["-n", "true"])
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .is_ok_and(|s| s.success())
+        };
+        Some(root || (!findings.is_empty() && sudo()))
+    };
+
+    let warning = (!findings.is_empty() && task_elevated == Some(false)).then(|| {
+        let target = task_name
+            .as_deref()
+            .map_or_else(|| "the task".to_string(), |n| format!("task '{}'", n));
+        if cfg!(target_os = "windows") {
+            format!(
+                "{} line(s) look like they need Administrator rights, but {} doesn't run \
+                 with highest privileges, so they will likely fail when scheduled",
+                findings.len(),
+                target
+            )
+        } else {
+            format!(
+                "{} line(s) look like they need root, but {} runs as your user and sudo \
+                 can't prompt for a password from cron, so they will likely fail when scheduled",
+                findings.len(),
+                target
+            )
+        }
+    });
+
+    Ok(PrivilegeAnalysis {
+        script_path,
+        findings,
+        task_elevated,
+        warning,
+    })
+}
+
+/// Whether `name` can be an environment variable name in every shell AUI
+/// writes wrappers for.
+fn valid_env_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Where named environment profiles are kept: one JSON object of
+/// variables per profile under `<app_config_dir>/env-profiles`.
+fn env_profiles_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join("env-profiles"))
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+fn env_profile_file(app: &tauri::AppHandle, name: &str) -> Result<std::path::PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        || name.starts_with('.')
+    {
+        return Err(format!(
+            "Invalid profile name '{}': use letters, digits, '-', '_' and '.'",
+            name
+        ));
+    }
+    Ok(env_profiles_dir(app)?.join(format!("{}.json", name)))
+}
+
+/// A saved environment profile as `list_env_profiles` shows it, with
+/// secret-looking values masked.
+#[derive(serde::Serialize)]
+struct EnvProfile {
+    name: String,
+    vars: std::collections::BTreeMap<String, String>,
+}
+
+/// Saves (or replaces) a named set of environment variables, e.g. `dev`,
+/// `staging` and `prod`, to apply to script runs and scheduled tasks with
+/// their `env_profile` option.
+#[tauri::command]
+fn save_env_profile(
+    app: tauri::AppHandle,
+    name: String,
+    vars: HashMap<String, String>,
+) -> Result<(), String> {
+    if let Some(key) = vars.keys().find(|k| !valid_env_name(k)) {
+        return Err(format!("Invalid environment variable name '{}'", key));
+    }
+    let path = env_profile_file(&app, &name)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let sorted: std::collections::BTreeMap<_, _> = vars.into_iter().collect();
+    let json = serde_json::to_string_pretty(&sorted).map_err(|e| e.to_string())?;
+    write_private_file(&path, &json)
+}
+
+/// The variables of a saved environment profile, unmasked.
+#[tauri::command]
+fn load_env_profile(
+    app: tauri::AppHandle,
+    name: String,
+) -> Result<HashMap<String, String>, String> {
+    let path = env_profile_file(&app, &name)?;
+    let contents = std::fs::read_to_string(&path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            format!("No environment profile named '{}'", name.trim())
+        } else {
+            format!("Failed to read {}: {}", path.display(), e)
+        }
+    })?;
+    serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid profile {}: {}", path.display(), e))
+}
+
+/// All saved environment profiles by name. Values of variables whose names
+/// look secret (`*_TOKEN`, `*PASSWORD*`, `*API_KEY*` ...) are masked; use
+/// `load_env_profile` for the real values.
+#[tauri::command]
+fn list_env_profiles(app: tauri::AppHandle) -> Result<Vec<EnvProfile>, String> {
+    let Ok(entries) = std::fs::read_dir(env_profiles_dir(&app)?) else {
+        return Ok(Vec::new());
+    };
+    let mut profiles: Vec<EnvProfile> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry
+                .file_name()
+                .to_string_lossy()
+                .strip_suffix(".json")?
+                .to_string();
+            let vars = load_env_profile(app.clone(), name.clone()).ok()?;
+            let vars = vars
+                .into_iter()
+                .map(|(key, value)| {
+                    let lower = key.to_lowercase();
+                    let secret = SECRET_KEYS
+                        .iter()
+                        .any(|k| lower.contains(&k.replace('-', "_")));
+                    (key, if secret { "********".into() } else { value })
+                })
+                .collect();
+            Some(EnvProfile { name, vars })
+        })
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+/// Deletes a saved environment profile.
+#[tauri::command]
+fn delete_env_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let path = env_profile_file(&app, &name)?;
+    std::fs::remove_file(&path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            format!("No environment profile named '{}'", name.trim())
+        } else {
+            format!("Failed to delete {}: {}", path.display(), e)
+        }
+    })
+}
+
+/// The variables of `profile`, when one is named.
+fn resolve_env_profile(
+    app: &tauri::AppHandle,
+    profile: Option<String>,
+) -> Result<HashMap<String, String>, String> {
+    match profile.filter(|p| !p.trim().is_empty()) {
+        Some(name) => load_env_profile(app.clone(), name),
+        None => Ok(HashMap::new()),
+    }
+}
+
+/// Writes `contents` to `path` readable only by the current user on Unix,
+/// for files that may hold secrets.
+fn write_private_file(path: &std::path::Path, contents: &str) -> Result<(), String> {
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        // mode() only applies to new files
+        let _ = file.set_permissions(std::fs::Permissions::from_mode(0o600));
+        file.write_all(contents.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+    #[cfg(target_os = "windows")]
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// How `run_script` runs a script. Every field is optional.
+#[derive(serde::Deserialize, Default)]
+struct RunOptions {
+    #[serde(default)]
+    shell: Option<String>,
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    #[serde(default)]
+    dry_run: Option<bool>,
+    #[serde(default)]
+    dry_run_flag: Option<String>,
+    #[serde(default)]
+    require_signed: Option<bool>,
+    #[serde(default)]
+    env_profile: Option<String>,
+    #[serde(default)]
+    max_output_bytes: Option<usize>,
+    #[serde(default)]
+    retries: Option<u32>,
+    #[serde(default)]
+    retry_delay_secs: Option<u64>,
+}
+
+/// Runs a script without a window and returns its exit status and captured
+/// output once it finishes. With `timeout_secs`, a script still running
+/// after that long is killed along with its children.
+///
+/// `dry_run` asks the script to preview its changes instead of making them.
+/// AUI can't enforce that, so it is a convention scripts opt into: the
+/// script runs with `AUI_DRY_RUN=1` in its environment, and with
+/// `dry_run_flag` (e.g. `--dry-run`, or `-WhatIf` for PowerShell) as its
+/// argument when given. Scripts should check either and skip anything
+/// destructive, printing what they would have done.
+///
+/// With `require_signed` the script only runs if it carries a trusted
+/// signature (see `check_script_signature`). `env_profile` adds the
+/// variables of a saved profile (see `save_env_profile`) to its environment.
+///
+/// Each of stdout and stderr is capped at `max_output_bytes` (4 MiB by
+/// default); past that the middle is dropped and `truncated` set. A
+/// relative `script_path` is inside the scripts directory (see
+/// `ensure_scripts_dir`).
+///
+/// A script that fails (or times out) is run again up to `retries` times,
+/// `retry_delay_secs` apart, for flaky steps that pass on a second try.
+/// The result is the last attempt's, plus how many attempts were made.
+#[tauri::command(async)]
+fn run_script(
+    app: tauri::AppHandle,
+    script_path: String,
+    options: Option<RunOptions>,
+) -> Result<ScriptRun, String> {
+    run_script_with(&app, script_path, options.unwrap_or_default())
+}
+
+/// `run_script` for callers in the backend.
+fn run_script_with(
+    app: &tauri::AppHandle,
+    script_path: String,
+    options: RunOptions,
+) -> Result<ScriptRun, String> {
+    let RunOptions {
+        shell,
+        timeout_secs,
+        dry_run,
+        dry_run_flag,
+        require_signed,
+        env_profile,
+        max_output_bytes,
+        retries,
+        retry_delay_secs,
+    } = options;
+    let env = resolve_env_profile(app, env_profile)?;
+    let shell = resolve_shell(shell)?;
+    let script_path = resolve_script_path(app, &script_path)?;
+    if require_signed == Some(true) {
+        require_signed_script(&script_path)?;
+    }
+    let dry_run = dry_run.unwrap_or(false);
+
+    let mut args = Vec::new();
+    if let Some(flag) = dry_run_flag
+        .map(|f| f.trim().to_string())
+        .filter(|f| dry_run && !f.is_empty())
+    {
+        let valid = flag.starts_with(['-', '/'])
+            && flag
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | '_' | '=' | ':'));
+        if !valid {
+            return Err(format!("Invalid dry-run flag '{}'", flag));
+        }
+        args.push(flag);
+    }
+
+    let retries = retries.unwrap_or(0);
+    let mut exit_codes = Vec::new();
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let mut command = script_command(&script_path, shell.as_deref(), &args)?;
+        command.envs(&env);
+        if dry_run {
+            command.env("AUI_DRY_RUN", "1");
+        }
+        let mut run = run_captured_with_stdin(
+            command,
+            &script_path,
+            timeout_secs.map(Duration::from_secs),
+            std::process::Stdio::null(),
+            max_output_bytes.unwrap_or(DEFAULT_MAX_OUTPUT_BYTES).max(1),
+        )?;
+        exit_codes.push(run.exit_code);
+        if run.success || attempt > retries {
+            run.attempts = attempt;
+            run.attempt_exit_codes = exit_codes;
+            return Ok(run);
+        }
+        std::thread::sleep(Duration::from_secs(retry_delay_secs.unwrap_or(0)));
+    }
+}
+
+/// Outcome of `run_sandboxed`: the run itself, the sandbox it ran in
+/// ("bwrap", "firejail", "sandbox-exec", or "none" when no sandboxing tool
+/// was available) and the restrictions that were actually applied.
+#[derive(serde::Serialize)]
+struct SandboxedRun {
+    mechanism: String,
+    restrictions: Vec<String>,
+    run: ScriptRun,
+}
+
+/// Runs a script with reduced privileges, for vetting third-party scripts
+/// before scheduling them. It always gets a minimal environment (no
+/// inherited variables beyond the essentials) and a lower CPU priority.
+/// On Linux it additionally runs under bubblewrap (read-only filesystem,
+/// private `/tmp`, no network) or firejail (no network, private `/tmp`,
+/// read-only home, no capabilities) when installed, and under
+/// `sandbox-exec` on macOS (no network, writes only to temp dirs). Windows
+/// has no way to start a low-integrity process without one, so there the
+/// environment and priority are all that's restricted.
+#[tauri::command(async)]
+fn run_sandboxed(
+    script_path: String,
+    shell: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<SandboxedRun, String> {
+    let shell = resolve_shell(shell)?;
+    let script_path = expand_user_path(&script_path);
+    if !std::path::Path::new(&script_path).is_file() {
+        return Err(format!("Script not found: {}", script_path));
+    }
+    let mut restrictions = vec!["minimal environment".to_string()];
+
+    #[cfg(target_os = "windows")]
+    let (mechanism, mut command) = {
+        let mut command = script_command(&script_path, shell.as_deref(), &[])?;
+        command.env_clear();
+        for key in [
+            "SystemRoot",
+            "SystemDrive",
+            "windir",
+            "ComSpec",
+            "PATHEXT",
+            "TEMP",
+            "TMP",
+            "USERPROFILE",
+        ] {
+            if let Some(value) = std::env::var_os(key) {
+                command.env(key, value);
+            }
+        }
+        let root = std::env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".into());
+        command.env(
+            "PATH",
+            format!(
+                r"{0}\System32;{0};{0}\System32\WindowsPowerShell\v1.0",
+                root
+            ),
+        );
+        // CREATE_NO_WINDOW | BELOW_NORMAL_PRIORITY_CLASS
+        command.creation_flags(0x08000000 | 0x00004000);
+        restrictions.push("below-normal priority".into());
+        ("none".to_string(), command)
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let (mechanism, mut command) = {
+        let mut argv = vec!["nice".to_string(), "-n".to_string(), "10".to_string()];
+        argv.extend(unix_script_argv(&script_path, shell.as_deref()));
+        restrictions.push("nice 10".into());
+
+        // Installed isn't enough: bwrap needs user namespaces, which
+        // containers and hardened kernels often disable
+        let usable = |tool: &str, args: &[&str]| {
+            find_in_path(tool).is_some()
+                && StdCommand::new(tool)
+                    .args(args)
+                    .arg("true")
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .status()
+                    .is_ok_and(|s| s.success())
+        };
+        let mechanism = if cfg!(target_os = "macos") {
+            usable("sandbox-exec", &["-p", "(version 1)(allow default)"]).then_some("sandbox-exec")
+        } else if usable("bwrap", &["--ro-bind", "/", "/", "--unshare-all", "--"]) {
+            Some("bwrap")
+        } else if usable("firejail", &["--quiet", "--noprofile", "--"]) {
+            Some("firejail")
+        } else {
+            None
+        };
+        let prefix: Vec<&str> = match mechanism {
+            Some("bwrap") => {
+                restrictions.extend(
+                    ["read-only filesystem", "private /tmp", "no network"].map(String::from),
+                );
+                vec![
+                    "bwrap",
+                    "--ro-bind",
+                    "/",
+                    "/",
+                    "--dev",
+                    "/dev",
+                    "--proc",
+                    "/proc",
+                    "--tmpfs",
+                    "/tmp",
+                    "--unshare-all",
+                    "--die-with-parent",
+                    "--new-session",
+                    "--",
+                ]
+            }
+            Some("firejail") => {
+                restrictions.extend(
+                    [
+                        "no network",
+                        "private /tmp",
+                        "read-only home",
+                        "no capabilities",
+                    ]
+                    .map(String::from),
+                );
+                vec![
+                    "firejail",
+                    "--quiet",
+                    "--noprofile",
+                    "--net=none",
+                    "--private-tmp",
+                    "--read-only=~",
+                    "--caps.drop=all",
+                    "--nonewprivs",
+                    "--",
+                ]
+            }
+            Some(_) => {
+                restrictions.extend(["no network", "writes only to temp dirs"].map(String::from));
+                vec![
+                    "sandbox-exec",
+                    "-p",
+                    "(version 1)(allow default)(deny network*)(deny file-write*)\
+                     (allow file-write* (subpath \"/private/tmp\") (subpath \"/private/var/folders\") (literal \"/dev/null\"))",
+                ]
+            }
+            None => Vec::new(),
+        };
+        let argv: Vec<String> = prefix.into_iter().map(str::to_string).chain(argv).collect();
+
+        // Resolved now, since the cleared PATH is the one used to find it
+        let program = find_in_path(&argv[0])
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| argv[0].clone());
+        let mut command = hidden_command(&program);
+        command.args(&argv[1..]).env_clear();
+        command.env("PATH", "/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin");
+        for key in ["HOME", "USER", "LOGNAME", "LANG", "TMPDIR"] {
+            if let Some(value) = std::env::var_os(key) {
+                command.env(key, value);
+            }
+        }
+        (mechanism.unwrap_or("none").to_string(), command)
+    };
+
+    command.env("AUI_SANDBOXED", "1");
+    let run = run_captured(command, &script_path, timeout_secs.map(Duration::from_secs))?;
+    Ok(SandboxedRun {
+        mechanism,
+        restrictions,
+        run,
+    })
+}
+
+/// Decodes wsl.exe output, which is UTF-16LE (without a BOM) unless
+/// `WSL_UTF8` is honored.
+#[cfg(target_os = "windows")]
+fn decode_wsl_output(bytes: &[u8]) -> String {
+    if bytes.len() >= 2 && bytes[1] == 0 {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units).replace('\0', "")
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn wsl_command() -> StdCommand {
+    let mut command = hidden_command("wsl.exe");
+    command.env("WSL_UTF8", "1");
+    command
+}
+
+/// Runs a bash script inside a WSL distro from Windows, translating its
+/// Windows path with `wslpath`, and returns the captured output.
+#[tauri::command(async)]
+fn run_in_wsl(distro: String, script_path: String, args: Vec<String>) -> Result<ScriptRun, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let script_path = expand_user_path(&script_path);
+        if !std::path::Path::new(&script_path).is_file() {
+            return Err(format!("Script not found: {}", script_path));
+        }
+
+        let listing = wsl_command()
+            .args(["--list", "--quiet"])
+            .output()
+            .map_err(|_| {
+                "DependencyMissing: WSL is not installed (wsl.exe not found)".to_string()
+            })?;
+        let installed: Vec<String> = decode_wsl_output(&listing.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect();
+        if !listing.status.success() || installed.is_empty() {
+            return Err("DependencyMissing: no WSL distros are installed".into());
+        }
+        if !installed
+            .iter()
+            .any(|d| d.eq_ignore_ascii_case(distro.trim()))
+        {
+            return Err(format!(
+                "No WSL distro named '{}' (installed: {})",
+                distro,
+                installed.join(", ")
+            ));
+        }
+
+        let translated = wsl_command()
+            .args(["-d", distro.trim(), "--exec", "wslpath", "-a", &script_path])
+            .output()
+            .map_err(|e| format!("Failed to run wslpath: {}", e))?;
+        if !translated.status.success() {
+            return Err(format!(
+                "Failed to translate {} to a WSL path: {}",
+                script_path,
+                decode_wsl_output(&translated.stderr).trim()
+            ));
+        }
+        let wsl_path = decode_wsl_output(&translated.stdout).trim().to_string();
+
+        // --exec skips the distro's login shell so arguments arrive verbatim
+        let mut command = wsl_command();
+        command
+            .args(["-d", distro.trim(), "--exec", "bash", &wsl_path])
+            .args(&args);
+        run_captured(command, "wsl.exe", None)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (distro, script_path, args);
+        Err("Unsupported: run_in_wsl is only available on Windows".into())
+    }
+}
+
+/// ssh's exit code when the connection itself failed rather than the script.
+const SSH_CONNECTION_FAILED: i32 = 255;
+
+/// One host's outcome from `deploy_to_hosts`: the captured run, or why it
+/// couldn't happen.
+#[derive(serde::Serialize)]
+struct HostDeployment {
+    host: String,
+    run: Option<ScriptRun>,
+    error: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct DeployProgress {
+    host: String,
+    status: String,
+    success: Option<bool>,
+}
+
+/// A host or user name safe to hand to ssh: no option injection via a
+/// leading `-` and nothing a shell or ssh_config would reinterpret.
+fn valid_ssh_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':' | '[' | ']'))
+}
+
+/// Runs a local bash script on one host over `ssh <user>@<host> bash -s`,
+/// piping the script to the remote shell's stdin.
+fn deploy_to_host(
+    script_path: &str,
+    host: &str,
+    ssh_user: Option<&str>,
+    timeout: Option<Duration>,
+) -> Result<ScriptRun, String> {
+    let script =
+        std::fs::File::open(script_path).map_err(|e| format!("Failed to open script: {}", e))?;
+    let target = match ssh_user {
+        Some(user) => format!("{}@{}", user, host),
+        None => host.to_string(),
+    };
+    let mut command = hidden_command("ssh");
+    // BatchMode fails fast instead of hanging on a password prompt
+    command.args([
+        "-o",
+        "BatchMode=yes",
+        "-o",
+        "ConnectTimeout=10",
+        "--",
+        &target,
+        "bash",
+        "-s",
+    ]);
+    let run = run_captured_with_stdin(
+        command,
+        "ssh",
+        timeout,
+        script.into(),
+        DEFAULT_MAX_OUTPUT_BYTES,
+    )
+    .map_err(|e| {
+        if e.starts_with("Failed to run ssh:") && e.contains("os error 2") {
+            "DependencyMissing: ssh is not installed".to_string()
+        } else {
+            e
+        }
+    })?;
+    if run.exit_code == Some(SSH_CONNECTION_FAILED) {
+        return Err(format!(
+            "SSH to {} failed: {}",
+            host,
+            run.stderr
+                .trim()
+                .lines()
+                .last()
+                .unwrap_or("connection error")
+        ));
+    }
+    Ok(run)
+}
+
+/// Runs a bash script on several hosts over SSH, at most `max_concurrency`
+/// at a time, returning one result per host in input order. A host that
+/// can't be reached is reported in its result without stopping the rest.
+/// Emits `deploy-progress` as each host starts and finishes.
+#[tauri::command(async)]
+fn deploy_to_hosts(
+    app: tauri::AppHandle,
+    script_path: String,
+    hosts: Vec<String>,
+    ssh_user: Option<String>,
+    max_concurrency: Option<usize>,
+    timeout_secs: Option<u64>,
+) -> Result<Vec<HostDeployment>, String> {
+    let script_path = expand_user_path(&script_path);
+    if !std::path::Path::new(&script_path).is_file() {
+        return Err(format!("Script not found: {}", script_path));
+    }
+    let ssh_user = ssh_user
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty());
+    if let Some(user) = &ssh_user {
+        if !valid_ssh_name(user) {
+            return Err(format!("Invalid SSH user: {}", user));
+        }
+    }
+    let hosts: Vec<String> = hosts.iter().map(|h| h.trim().to_string()).collect();
+    if hosts.is_empty() {
+        return Err("No hosts given".into());
+    }
+    let timeout = timeout_secs.map(Duration::from_secs);
+
+    let workers = max_concurrency.unwrap_or(4).clamp(1, 32).min(hosts.len());
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<Result<ScriptRun, String>>>> =
+        hosts.iter().map(|_| Mutex::new(None)).collect();
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(host) = hosts.get(i) else {
+                    break;
+                };
+                let _ = app.emit(
+                    "deploy-progress",
+                    DeployProgress {
+                        host: host.clone(),
+                        status: "started".into(),
+                        success: None,
+                    },
+                );
+                let result = if valid_ssh_name(host) {
+                    deploy_to_host(&script_path, host, ssh_user.as_deref(), timeout)
+                } else {
+                    Err(format!("Invalid host: {}", host))
+                };
+                let _ = app.emit(
+                    "deploy-progress",
+                    DeployProgress {
+                        host: host.clone(),
+                        status: "finished".into(),
+                        success: Some(result.as_ref().is_ok_and(|r| r.success)),
+                    },
+                );
+                if let Ok(mut slot) = results[i].lock() {
+                    *slot = Some(result);
+                }
+            });
+        }
+    });
+
+    Ok(hosts
+        .into_iter()
+        .zip(results)
+        .map(|(host, slot)| {
+            match slot
+                .into_inner()
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| Err("Deployment did not complete".to_string()))
+            {
+                Ok(run) => HostDeployment {
+                    host,
+                    run: Some(run),
+                    error: None,
+                },
+                Err(e) => HostDeployment {
+                    host,
+                    run: None,
+                    error: Some(e),
+                },
+            }
+        })
+        .collect())
+}
+
+/// Writes `contents` to a new file in the temp directory that only the
+/// current user can read. `create_new` means an existing file or symlink
+/// planted at the name is never followed.
+fn write_private_temp_file(extension: &str, contents: &str) -> Result<std::path::PathBuf, String> {
+    use std::io::Write;
+    use std::sync::atomic::AtomicU64;
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let path = std::env::temp_dir().join(format!(
+        "aui-inline-{}-{}-{}.{}",
+        std::process::id(),
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+        extension
+    ));
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o700);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    file.write_all(contents.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// A leftover AUI working file (or directory, `bytes` being its total size)
+/// in the OS temp directory. `modified` is seconds since the Unix epoch.
+#[derive(serde::Serialize)]
+struct TempFile {
+    path: String,
+    bytes: u64,
+    modified: Option<u64>,
+    in_use: bool,
+}
+
+/// Size of `path` without following symlinks, directories included.
+fn disk_usage(path: &std::path::Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| disk_usage(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// AUI's entries in the temp directory: only names starting with `aui-`,
+/// so nothing else there is ever touched. Entries named for this process
+/// (`aui-<kind>-<pid>-...`) are `in_use`, since a run may still need them.
+fn aui_temp_files() -> Vec<TempFile> {
+    let own = format!("-{}-", std::process::id());
+    let mut files: Vec<TempFile> = std::fs::read_dir(std::env::temp_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().starts_with("aui-"))
+                .map(|e| {
+                    let path = e.path();
+                    let name = e.file_name().to_string_lossy().to_string();
+                    TempFile {
+                        bytes: disk_usage(&path),
+                        modified: std::fs::symlink_metadata(&path)
+                            .and_then(|m| m.modified())
+                            .ok()
+                            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                            .map(|d| d.as_secs()),
+                        in_use: name.contains(&own),
+                        path: path.to_string_lossy().to_string(),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// Lists the working files AUI left in the OS temp directory (inline
+/// scripts, task XML round-trips ...), e.g. after a crash.
+#[tauri::command]
+fn list_temp_files() -> Vec<TempFile> {
+    aui_temp_files()
+}
+
+#[derive(serde::Serialize)]
+struct TempCleanup {
+    removed: Vec<String>,
+    freed_bytes: u64,
+    errors: Vec<String>,
+}
+
+/// Deletes AUI's leftover working files from the OS temp directory (see
+/// `list_temp_files`), skipping the ones this session may still be using,
+/// and reports how many bytes that freed.
+#[tauri::command]
+fn clear_temp_files() -> TempCleanup {
+    let mut cleanup = TempCleanup {
+        removed: Vec::new(),
+        freed_bytes: 0,
+        errors: Vec::new(),
+    };
+    for file in aui_temp_files().into_iter().filter(|f| !f.in_use) {
+        let path = std::path::Path::new(&file.path);
+        // Symlinks are removed themselves, never what they point to
+        let removed = match std::fs::symlink_metadata(path) {
+            Ok(m) if m.is_dir() => std::fs::remove_dir_all(path),
+            _ => std::fs::remove_file(path),
+        };
+        match removed {
+            Ok(()) => {
+                cleanup.freed_bytes += file.bytes;
+                cleanup.removed.push(file.path);
+            }
+            Err(e) => cleanup.errors.push(format!("{}: {}", file.path, e)),
+        }
+    }
+    cleanup
+}
+
+/// Runs a snippet of code without saving it as a script first. `shell` is
+/// powershell/pwsh, cmd, bash/sh/zsh, python/python3 or node (PowerShell on
+/// Windows and bash elsewhere by default). The code goes to a private temp
+/// file that is removed afterwards, and `args`, `env` and `working_dir` are
+/// handed to the process directly rather than spliced into a command line.
+#[tauri::command(async)]
+fn run_inline(
+    shell: Option<String>,
+    code: String,
+    args: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+    working_dir: Option<String>,
+) -> Result<ScriptRun, String> {
+    let shell = resolve_shell(shell)?.unwrap_or_else(|| {
+        if cfg!(target_os = "windows") {
+            "powershell".into()
+        } else {
+            "bash".into()
+        }
+    });
+    let name = std::path::Path::new(&shell)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let extension = match name.as_str() {
+        "powershell" | "pwsh" => "ps1",
+        "cmd" => "cmd",
+        "bash" | "sh" | "zsh" | "dash" => "sh",
+        "python" | "python3" => "py",
+        "node" => "js",
+        _ => {
+            return Err(format!(
+                "Unsupported: can't run inline code with '{}'",
+                shell
+            ))
+        }
+    };
+    let env = env.unwrap_or_default();
+    if let Some(key) = env.keys().find(|k| k.is_empty() || k.contains(['=', '\0'])) {
+        return Err(format!("Invalid environment variable name '{}'", key));
+    }
+    let working_dir = working_dir
+        .filter(|d| !d.trim().is_empty())
+        .map(|d| expand_user_path(&d));
+    if let Some(dir) = &working_dir {
+        if !std::path::Path::new(dir).is_dir() {
+            return Err(format!("Working directory {} does not exist", dir));
+        }
+    }
+
+    let code = normalize_line_endings(&format!("inline.{}", extension), &code, LineEnding::Auto);
+    let script = write_private_temp_file(extension, &code)?;
+    let script_path = script.to_string_lossy().to_string();
+    let mut command = match extension {
+        "ps1" => {
+            // PowerShell outside Windows is only ever pwsh
+            #[cfg(target_os = "windows")]
+            let program = if name == "pwsh" {
+                "pwsh".to_string()
+            } else {
+                resolve_powershell()?
+            };
+            #[cfg(not(target_os = "windows"))]
+            let program = "pwsh".to_string();
+            let mut command = hidden_command(&program);
+            command.args([
+                "-NoProfile",
+                "-ExecutionPolicy",
+                "Bypass",
+                "-File",
+                &script_path,
+            ]);
+            command
+        }
+        // Run directly so std quotes the arguments for cmd.exe safely
+        "cmd" => hidden_command(&script_path),
+        _ => {
+            let mut command = hidden_command(&shell);
+            command.arg(&script_path);
+            command
+        }
+    };
+    command.args(args.unwrap_or_default()).envs(&env);
+    if let Some(dir) = &working_dir {
+        command.current_dir(dir);
+    }
+
+    let result = run_captured(command, &shell, None);
+    let _ = std::fs::remove_file(&script);
+    result
+}
+
+#[derive(serde::Serialize)]
+struct RunAndScheduleResult {
+    run: ScriptRun,
+    schedule: CreateTaskResult,
+}
+
+/// Runs a script once, then schedules it like `create_scheduled_task`. With
+/// `require_success` a failing run is reported as an error carrying its
+/// stderr and nothing is scheduled.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command(async)]
+fn run_and_schedule(
+    app: tauri::AppHandle,
+    task_name: String,
+    script_path: String,
+    start_time: String,
+    start_date: String,
+    repeat: String,
+    shell: Option<String>,
+    require_success: bool,
+) -> Result<RunAndScheduleResult, String> {
+    validate_task_name(&task_name)?;
+    let run = run_script_with(
+        &app,
+        script_path.clone(),
+        RunOptions {
+            shell: shell.clone(),
+            ..Default::default()
+        },
+    )?;
+    if require_success && !run.success {
+        let code = run
+            .exit_code
+            .map_or_else(|| "a signal".to_string(), |c| format!("exit code {}", c));
+        return Err(format!(
+            "Script failed with {}; not scheduled: {}",
+            code,
+            run.stderr.trim()
+        ));
+    }
+
+    let schedule = schedule_timed(&app, &task_name, &script_path, shell, |script, shell| {
+        create_preset_task(
+            &app,
+            task_name.clone(),
+            script,
+            start_time,
+            start_date,
+            repeat,
+            shell,
+            None,
+            None,
+            None,
+            None,
+        )
+    })?;
+    Ok(RunAndScheduleResult { run, schedule })
+}
+
+/// Outcome of `deploy_with_verification`. `verify` is absent when the deploy
+/// itself failed, since there is nothing to verify.
+#[derive(serde::Serialize)]
+struct VerifiedDeployment {
+    success: bool,
+    failed_step: Option<String>,
+    deploy: ScriptRun,
+    verify: Option<ScriptRun>,
+}
+
+/// Runs a deploy script and, if it succeeds, a verification script after
+/// it, reporting both outputs. A failing verification fails the whole
+/// deployment even though the deploy exited 0. Both scripts run with the
+/// same shell, timeout and `env_profile`.
+#[tauri::command(async)]
+fn deploy_with_verification(
+    app: tauri::AppHandle,
+    deploy_script: String,
+    verify_script: String,
+    shell: Option<String>,
+    timeout_secs: Option<u64>,
+    env_profile: Option<String>,
+) -> Result<VerifiedDeployment, String> {
+    let env = resolve_env_profile(&app, env_profile)?;
+    let shell = resolve_shell(shell)?;
+    let timeout = timeout_secs.map(Duration::from_secs);
+    let run = |path: &str| {
+        let path = expand_user_path(path);
+        let mut command = script_command(&path, shell.as_deref(), &[])?;
+        command.envs(&env);
+        run_captured(command, &path, timeout)
+    };
+
+    let deploy = run(&deploy_script)?;
+    if !deploy.success {
+        return Ok(VerifiedDeployment {
+            success: false,
+            failed_step: Some("deploy".into()),
+            deploy,
+            verify: None,
+        });
+    }
+
+    let verify = run(&verify_script)?;
+    Ok(VerifiedDeployment {
+        success: verify.success,
+        failed_step: (!verify.success).then(|| "verify".into()),
+        deploy,
+        verify: Some(verify),
+    })
+}
+
+/// Outcome of `run_and_report`. The script's own result is in `run`;
+/// `report_error` is set when posting it failed or the report endpoint
+/// answered with an error status, in which case `report` holds that answer
+/// if there was one.
+#[derive(serde::Serialize)]
+struct RunReport {
+    run: ScriptRun,
+    report: Option<HttpResponse>,
+    report_error: Option<String>,
+}
+
+/// POSTs a script run to `url` with curl, as either a JSON document with
+/// the output inline, or a multipart form with the run's details as a
+/// `metadata` JSON part and its output attached as a `log` file.
+fn post_run_report(
+    url: &str,
+    script_path: &str,
+    run: &ScriptRun,
+    multipart: bool,
+) -> Result<HttpResponse, String> {
+    use std::io::Write;
+
+    let mut details = serde_json::json!({
+        "script_path": script_path,
+        "success": run.success,
+        "exit_code": run.exit_code,
+        "timed_out": run.timed_out,
+        "duration_ms": run.duration_ms,
+    });
+    throttle_fetch()?;
+    let mut command = curl_command();
+    command.args(["-sS", "--max-time", "30", "-X", "POST"]);
+    let mut log_file = None;
+    if multipart {
+        let mut log = run.stdout.clone();
+        if !run.stderr.is_empty() {
+            log.push_str("\n--- stderr ---\n");
+            log.push_str(&run.stderr);
+        }
+        let path = write_private_temp_file("log", &log)?;
+        let stem = std::path::Path::new(script_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().replace(['"', ';', '\\'], "_"))
+            .unwrap_or_else(|| "script".into());
+        command.args([
+            "-F".to_string(),
+            "metadata=<-;type=application/json".to_string(),
+            "-F".to_string(),
+            format!(
+                "log=@\"{}\";filename={}.log;type=text/plain",
+                path.to_string_lossy()
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\""),
+                stem
+            ),
+        ]);
+        log_file = Some(path);
+    } else {
+        details["stdout"] = run.stdout.clone().into();
+        details["stderr"] = run.stderr.clone().into();
+        command.args([
+            "-H",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+        ]);
+    }
+    command.args(["-w", &format!("{}%{{http_code}}", CURL_STATUS_MARKER), url]);
+
+    let started = Instant::now();
+    let output = command
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(details.to_string().as_bytes())?;
+            }
+            child.wait_with_output()
+        });
+    if let Some(path) = log_file {
+        let _ = std::fs::remove_file(path);
+    }
+    let output = output.map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout
+        .rsplit_once(CURL_STATUS_MARKER)
+        .ok_or_else(|| "Report request failed: no response from curl".to_string())?;
+    let status: u16 = status.trim().parse().unwrap_or(0);
+    if !output.status.success() || status == 0 {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Report request failed: {}", stderr.trim()));
+    }
+    Ok(HttpResponse {
+        url: url.to_string(),
+        status,
+        body: body.to_string(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Runs a script and POSTs its output to `report_url`, e.g. a ticket
+/// comment endpoint or a chat webhook. `format` is "json" (the default) or
+/// "multipart" (see `post_run_report`). The report is sent whether or not
+/// the script succeeded; a failed report doesn't make this an error, it
+/// comes back as `report_error` next to the script's own result.
+#[tauri::command(async)]
+fn run_and_report(
+    app: tauri::AppHandle,
+    script_path: String,
+    report_url: String,
+    format: Option<String>,
+    shell: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<RunReport, String> {
+    let report_url = parse_http_url(&report_url)?.to_string();
+    let multipart = match format.as_deref().map(str::trim).unwrap_or("json") {
+        "json" => false,
+        "multipart" => true,
+        other => {
+            return Err(format!(
+                "Unsupported report format '{}': expected json or multipart",
+                other
+            ))
+        }
+    };
+
+    let script_path = resolve_script_path(&app, &script_path)?;
+    let run = run_script_with(
+        &app,
+        script_path.clone(),
+        RunOptions {
+            shell,
+            timeout_secs,
+            ..Default::default()
+        },
+    )?;
+    let (report, report_error) = match post_run_report(&report_url, &script_path, &run, multipart) {
+        Ok(response) if response.status >= 400 => {
+            let error = format!("Report endpoint answered with status {}", response.status);
+            (Some(response), Some(error))
+        }
+        Ok(response) => (Some(response), None),
+        Err(e) => (None, Some(e)),
+    };
+    Ok(RunReport {
+        run,
+        report,
+        report_error,
+    })
+}
+
+/// Outcome of `run_script_json`: `json` is the script's parsed output, or
+/// `None` with `parse_error` saying why. `run` keeps the raw stdout either
+/// way, for debugging.
+#[derive(serde::Serialize)]
+struct ScriptJsonRun {
+    json: Option<serde_json::Value>,
+    parse_error: Option<String>,
+    run: ScriptRun,
+}
+
+/// Runs a script that reports structured results and parses its stdout as
+/// JSON, so a node can hand data back to the graph without the frontend
+/// picking apart text. When stdout as a whole isn't JSON its last non-empty
+/// line is tried, for scripts that log before printing their result. A
+/// failed run is still parsed; check `run.success` as well.
+#[tauri::command(async)]
+fn run_script_json(
+    app: tauri::AppHandle,
+    script_path: String,
+    shell: Option<String>,
+    timeout_secs: Option<u64>,
+    env_profile: Option<String>,
+) -> Result<ScriptJsonRun, String> {
+    let run = run_script_with(
+        &app,
+        script_path,
+        RunOptions {
+            shell,
+            timeout_secs,
+            env_profile,
+            ..Default::default()
+        },
+    )?;
+    let stdout = run.stdout.trim();
+    let parsed = serde_json::from_str::<serde_json::Value>(stdout).or_else(|whole| {
+        stdout
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .filter(|last| last.trim() != stdout)
+            .and_then(|last| serde_json::from_str(last.trim()).ok())
+            .ok_or(whole)
+    });
+    let (json, parse_error) = match parsed {
+        Ok(json) => (Some(json), None),
+        Err(_) if stdout.is_empty() => (None, Some("The script printed nothing".to_string())),
+        Err(e) if run.truncated => (
+            None,
+            Some(format!("Output was truncated and isn't valid JSON: {}", e)),
+        ),
+        Err(e) => (None, Some(format!("Output isn't valid JSON: {}", e))),
+    };
+    Ok(ScriptJsonRun {
+        json,
+        parse_error,
+        run,
+    })
+}
+
+/// Scripts started by `run_script_streaming` that are still running, keyed
+/// by run id, plus runs left over from an earlier session of AUI.
+#[derive(Default)]
+struct Runs {
+    next_id: std::sync::atomic::AtomicU64,
+    table: Arc<RunTable>,
+}
+
+/// The run registry shared with the threads watching each run. It is
+/// mirrored to `processes.json` so runs survive an AUI restart.
+#[derive(Default)]
+struct RunTable {
+    entries: Mutex<HashMap<String, RunInfo>>,
+    orphans: Mutex<HashMap<String, RunInfo>>,
+    store: std::sync::OnceLock<std::path::PathBuf>,
+    /// Live runs stopped with `kill_process` or `stop_process`, so they
+    /// aren't retried.
+    killed: Mutex<std::collections::HashSet<String>>,
+}
+
+impl RunTable {
+    fn insert(&self, info: RunInfo) -> Result<(), String> {
+        self.entries
+            .lock()
+            .map_err(|e| e.to_string())?
+            .insert(info.id.clone(), info);
+        self.persist();
+        Ok(())
+    }
+
+    fn remove(&self, id: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.remove(id);
+        }
+        if let Ok(mut killed) = self.killed.lock() {
+            killed.remove(id);
+        }
+        self.persist();
+    }
+
+    /// Records that a live run is being stopped on purpose.
+    fn mark_killed(&self, id: &str) {
+        if let Ok(mut killed) = self.killed.lock() {
+            killed.insert(id.to_string());
+        }
+    }
+
+    fn was_killed(&self, id: &str) -> bool {
+        self.killed.lock().is_ok_and(|k| k.contains(id))
+    }
+
+    /// The pid of a live or orphaned run. An orphan whose pid now belongs to
+    /// another process is dropped rather than returned, so it can't be
+    /// killed by mistake.
+    fn pid(&self, id: &str) -> Result<u32, String> {
+        let live = self
+            .entries
+            .lock()
+            .map_err(|e| e.to_string())?
+            .get(id)
+            .map(|r| r.pid);
+        if let Some(pid) = live {
+            return Ok(pid);
+        }
+        let orphan = self
+            .orphans
+            .lock()
+            .map_err(|e| e.to_string())?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("No running script with id '{}'", id))?;
+        if run_process_matches(&orphan) {
+            return Ok(orphan.pid);
+        }
+        if let Ok(mut orphans) = self.orphans.lock() {
+            orphans.remove(id);
+        }
+        self.persist();
+        Err(format!("Script '{}' is no longer running", id))
+    }
+
+    /// Writes live and orphaned runs to the store, best effort.
+    fn persist(&self) {
+        let Some(path) = self.store.get() else {
+            return;
+        };
+        let mut all: Vec<RunInfo> = Vec::new();
+        if let Ok(entries) = self.entries.lock() {
+            all.extend(entries.values().cloned());
+        }
+        if let Ok(orphans) = self.orphans.lock() {
+            all.extend(orphans.values().cloned());
+        }
+        all.sort_by_key(|r| r.started_at);
+        if let Ok(json) = serde_json::to_string_pretty(&all) {
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            if let Err(e) = std::fs::write(path, json) {
+                log::warn!("Failed to save {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+impl Runs {
+    /// Loads the runs recorded by an earlier session from `path`, keeps the
+    /// ones whose process is still running as orphans and persists from then
+    /// on. A pid now held by a different process (see `run_process_matches`),
+    /// or recorded without a start time, isn't adopted.
+    fn restore(&self, path: std::path::PathBuf) {
+        let recorded: Vec<RunInfo> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+
+        let mut max_id = 0;
+        if let Ok(mut orphans) = self.table.orphans.lock() {
+            for run in recorded.into_iter().filter(run_process_matches) {
+                if let Some(n) = run.id.strip_prefix("run-").and_then(|n| n.parse().ok()) {
+                    max_id = max_id.max(n);
+                }
+                orphans.insert(run.id.clone(), run);
+            }
+        }
+        // New runs must not reuse an orphan's id
+        self.next_id.fetch_max(max_id, Ordering::Relaxed);
+
+        let _ = self.table.store.set(path);
+        self.table.persist();
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+struct RunInfo {
+    id: String,
+    pid: u32,
+    script_path: String,
+    started_at: u64,
+    #[serde(default)]
+    log_file: Option<String>,
+    /// The process's start time (see `process_start_time`).
+    #[serde(default)]
+    process_started: Option<String>,
+}
+
+/// The file a streaming run's output is copied to, shared by its readers.
+type RunLog = Arc<Mutex<std::fs::File>>;
+
+#[derive(serde::Serialize, Clone)]
+struct ScriptOutput {
+    id: String,
+    stream: String,
+    line: String,
+    attempt: u32,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct ScriptExit {
+    id: String,
+    success: bool,
+    exit_code: Option<i32>,
+    duration_ms: u64,
+    attempt: u32,
+    attempt_exit_codes: Vec<Option<i32>>,
+    stats: Option<ProcessStatsSummary>,
+}
+
+/// A `process-stats` sample of a streaming run: its processes' combined
+/// memory and their CPU use since the previous sample, as a percentage of
+/// one core.
+#[derive(serde::Serialize, Clone)]
+struct ProcessStats {
+    id: String,
+    pid: u32,
+    elapsed_ms: u64,
+    cpu_percent: f64,
+    memory_bytes: u64,
+    processes: usize,
+}
+
+/// A streaming run's resource use over its lifetime, from the samples
+/// taken. CPU time of a process that started and exited between two
+/// samples isn't seen.
+#[derive(serde::Serialize, Clone, Default)]
+struct ProcessStatsSummary {
+    samples: u32,
+    peak_memory_bytes: u64,
+    total_cpu_ms: u64,
+}
+
+/// The CPU time `ps` prints, `[[dd-]hh:]mm:ss[.cc]`, in milliseconds.
+#[cfg(not(target_os = "windows"))]
+fn parse_ps_cpu_time(value: &str) -> Option<u64> {
+    let (days, clock) = match value.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, value),
+    };
+    let mut ms = days * 86_400_000;
+    let mut parts: Vec<&str> = clock.split(':').collect();
+    let seconds: f64 = parts.pop()?.parse().ok()?;
+    for (part, unit) in parts.iter().rev().zip([60_000, 3_600_000]) {
+        ms += part.parse::<u64>().ok()? * unit;
+    }
+    Some(ms + (seconds * 1000.0) as u64)
+}
+
+/// A process's CPU time from `/proc/<pid>/stat`, finer than the whole
+/// seconds Linux `ps` prints. Ticks are USER_HZ, 100 a second.
+#[cfg(not(target_os = "windows"))]
+fn proc_cpu_ms(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name may hold spaces; fields resume after its ')'
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    let ticks = fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?;
+    Some(ticks * 10)
+}
+
+/// Memory and CPU time of every process in the tree under `root`, as
+/// (pid, memory bytes, cpu ms). On Unix that is the run's process group.
+fn sample_process_tree(root: u32) -> Result<Vec<(u32, u64, u64)>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let output = hidden_command(&resolve_powershell()?)
+            .args([
+                "-NoProfile",
+                "-Command",
+                "Get-CimInstance Win32_Process | ForEach-Object { \"$($_.ProcessId) $($_.ParentProcessId) \
+                 $($_.WorkingSetSize) $($_.KernelModeTime + $_.UserModeTime)\" }",
+            ])
+            .output()
+            .map_err(|e| format!("Failed to list processes: {}", e))?;
+        let all: Vec<(u32, u32, u64, u64)> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace().map(|f| f.parse::<u64>().ok());
+                let pid = fields.next()?? as u32;
+                let parent = fields.next()?? as u32;
+                // CPU times are in 100ns units
+                Some((pid, parent, fields.next()??, fields.next()?? / 10_000))
+            })
+            .collect();
+        let mut tree: Vec<(u32, u64, u64)> = all
+            .iter()
+            .filter(|p| p.0 == root)
+            .map(|p| (p.0, p.2, p.3))
+            .collect();
+        let mut next = 0;
+        while next < tree.len() {
+            let parent = tree[next].0;
+            for (pid, parent_pid, memory, cpu) in &all {
+                if *parent_pid == parent && !tree.iter().any(|p| p.0 == *pid) {
+                    tree.push((*pid, *memory, *cpu));
+                }
+            }
+            next += 1;
+        }
+        Ok(tree)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let output = StdCommand::new("ps")
+            .args(["-A", "-o", "pid=,pgid=,rss=,time="])
+            .output()
+            .map_err(|e| format!("Failed to list processes: {}", e))?;
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() != 4 || fields[1].parse::<u32>().ok()? != root {
+                    return None;
+                }
+                let pid: u32 = fields[0].parse().ok()?;
+                Some((
+                    pid,
+                    fields[2].parse::<u64>().ok()? * 1024,
+                    proc_cpu_ms(pid).or_else(|| parse_ps_cpu_time(fields[3]))?,
+                ))
+            })
+            .collect())
+    }
+}
+
+/// Samples run `id` every `interval`, emitting `process-stats`, until
+/// `stop` is dropped. Follows a retried run to its new pid.
+fn sample_run_stats(
+    app: &tauri::AppHandle,
+    table: &RunTable,
+    id: &str,
+    interval: Duration,
+    stop: mpsc::Receiver<()>,
+) -> ProcessStatsSummary {
+    let started = Instant::now();
+    let mut summary = ProcessStatsSummary::default();
+    let mut cpu_seen: HashMap<u32, u64> = HashMap::new();
+    let mut last_sample = Instant::now();
+    while let Err(mpsc::RecvTimeoutError::Timeout) = stop.recv_timeout(interval) {
+        let Ok(pid) = table.pid(id) else {
+            break;
+        };
+        let Ok(processes) = sample_process_tree(pid) else {
+            continue;
+        };
+        if processes.is_empty() {
+            continue;
+        }
+        let mut cpu_ms = 0;
+        for (process, _, cpu) in &processes {
+            let seen = cpu_seen.entry(*process).or_insert(0);
+            cpu_ms += cpu.saturating_sub(*seen);
+            *seen = (*seen).max(*cpu);
+        }
+        let memory_bytes: u64 = processes.iter().map(|p| p.1).sum();
+        let wall_ms = last_sample.elapsed().as_millis().max(1) as f64;
+        last_sample = Instant::now();
+
+        summary.samples += 1;
+        summary.total_cpu_ms += cpu_ms;
+        summary.peak_memory_bytes = summary.peak_memory_bytes.max(memory_bytes);
+        let _ = app.emit(
+            "process-stats",
+            ProcessStats {
+                id: id.to_string(),
+                pid,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                cpu_percent: (cpu_ms as f64 / wall_ms * 1000.0).round() / 10.0,
+                memory_bytes,
+                processes: processes.len(),
+            },
+        );
+    }
+    summary
+}
+
+/// Starts one attempt of a streaming run, emitting each output line as a
+/// `script-output` event tagged with `attempt` and appending it to `log`.
+/// Returns the child and the threads reading its pipes.
+fn spawn_streaming_attempt(
+    app: &tauri::AppHandle,
+    id: &str,
+    attempt: u32,
+    script_path: &str,
+    shell: Option<&str>,
+    log: Option<&RunLog>,
+) -> Result<(std::process::Child, Vec<std::thread::JoinHandle<()>>), String> {
+    use std::io::{BufRead, Write};
+    #[cfg(not(target_os = "windows"))]
+    use std::os::unix::process::CommandExt;
+
+    let mut command = script_command(script_path, shell, &[])?;
+    command
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    // Its own process group, so kill_process reaches the children too
+    #[cfg(not(target_os = "windows"))]
+    command.process_group(0);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", script_path, e))?;
+
+    let readers = [
+        (
+            "stdout",
+            child
+                .stdout
+                .take()
+                .map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+        ),
+        (
+            "stderr",
+            child
+                .stderr
+                .take()
+                .map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(stream, pipe)| pipe.map(|p| (stream, p)))
+    .map(|(stream, pipe)| {
+        let app = app.clone();
+        let id = id.to_string();
+        let log = log.cloned();
+        std::thread::spawn(move || {
+            for line in std::io::BufReader::new(pipe).lines().map_while(Result::ok) {
+                // Unbuffered, so every line is on disk before the next
+                if let Some(mut log) = log.as_ref().and_then(|l| l.lock().ok()) {
+                    let _ = writeln!(log, "{}", line);
+                }
+                let _ = app.emit(
+                    "script-output",
+                    ScriptOutput {
+                        id: id.clone(),
+                        stream: stream.to_string(),
+                        line,
+                        attempt,
+                    },
+                );
+            }
+        })
+    })
+    .collect();
+    Ok((child, readers))
+}
+
+/// Starts a script without a window and returns immediately. Each output
+/// line is emitted as a `script-output` event and completion as
+/// `script-exit`; the run can be stopped with `kill_process`.
+///
+/// A script that exits unsuccessfully is started again up to `retries`
+/// times, `retry_delay_secs` apart, under the same run id; each event
+/// carries the `attempt` it belongs to. A run stopped with `kill_process`
+/// isn't retried.
+///
+/// With `stats_interval_secs` (at least 1) the run's processes are sampled
+/// that often and each sample emitted as a `process-stats` event, for
+/// spotting an unexpectedly heavy deploy step; `script-exit` then carries
+/// the peak memory and total CPU time seen.
+///
+/// With `log_file` every output line is also appended to that file as it
+/// streams, so there's a record even if AUI exits mid-run. A relative path
+/// is inside `<app_log_dir>/runs` and can't climb out of it with `..`; the
+/// resolved path is returned in `log_file`.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+fn run_script_streaming(
+    app: tauri::AppHandle,
+    runs: tauri::State<'_, Runs>,
+    script_path: String,
+    shell: Option<String>,
+    retries: Option<u32>,
+    retry_delay_secs: Option<u64>,
+    stats_interval_secs: Option<u64>,
+    log_file: Option<String>,
+) -> Result<RunInfo, String> {
+    use std::io::Write;
+    use tauri::Manager;
+
+    let shell = resolve_shell(shell)?;
+    let script_path = expand_user_path(&script_path);
+    let id = format!("run-{}", runs.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+
+    let log_path = match log_file
+        .map(|f| expand_user_path(f.trim()))
+        .filter(|f| !f.is_empty())
+    {
+        Some(path) if std::path::Path::new(&path).is_absolute() => {
+            Some(std::path::PathBuf::from(path))
+        }
+        Some(path) => {
+            let runs_dir = app
+                .path()
+                .app_log_dir()
+                .map_err(|e| format!("Failed to resolve log directory: {}", e))?
+                .join("runs");
+            // `..` (or a drive-relative prefix on Windows) would leave it
+            if !std::path::Path::new(&path).components().all(|c| {
+                matches!(
+                    c,
+                    std::path::Component::Normal(_) | std::path::Component::CurDir
+                )
+            }) {
+                return Err(format!(
+                    "Log file '{}' has to stay inside {}; give an absolute path for anywhere else",
+                    path,
+                    runs_dir.display()
+                ));
+            }
+            Some(runs_dir.join(path))
+        }
+        None => None,
+    };
+    let log: Option<RunLog> = match &log_path {
+        Some(path) => {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+            let _ = writeln!(
+                file,
+                "=== {} {} {} ===",
+                id,
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                script_path
+            );
+            Some(Arc::new(Mutex::new(file)))
+        }
+        None => None,
+    };
+
+    let started = Instant::now();
+    let (mut child, mut readers) =
+        spawn_streaming_attempt(&app, &id, 1, &script_path, shell.as_deref(), log.as_ref())?;
+    let mut info = RunInfo {
+        id: id.clone(),
+        pid: child.id(),
+        script_path,
+        started_at: unix_timestamp(),
+        log_file: log_path.map(|p| p.to_string_lossy().to_string()),
+        process_started: process_start_time(child.id()),
+    };
+    runs.table.insert(info.clone())?;
+    let run = info.clone();
+
+    let table = Arc::clone(&runs.table);
+    let sampler = stats_interval_secs.map(|secs| {
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let (app, table, id) = (app.clone(), Arc::clone(&table), id.clone());
+        let interval = Duration::from_secs(secs.max(1));
+        let handle =
+            std::thread::spawn(move || sample_run_stats(&app, &table, &id, interval, stop_rx));
+        (stop_tx, handle)
+    });
+    let retries = retries.unwrap_or(0);
+    let retry_delay = Duration::from_secs(retry_delay_secs.unwrap_or(0));
+    std::thread::spawn(move || {
+        let mut attempt = 1;
+        let mut exit_codes = Vec::new();
+        let status = loop {
+            let status = child.wait();
+            for reader in readers.drain(..) {
+                let _ = reader.join();
+            }
+            exit_codes.push(status.as_ref().ok().and_then(|s| s.code()));
+            let failed = !status.as_ref().is_ok_and(|s| s.success());
+            if !failed || attempt > retries || table.was_killed(&id) {
+                break status;
+            }
+            std::thread::sleep(retry_delay);
+            if table.was_killed(&id) {
+                break status;
+            }
+            attempt += 1;
+            if let Some(mut log) = log.as_ref().and_then(|l| l.lock().ok()) {
+                let _ = writeln!(log, "=== attempt {} ===", attempt);
+            }
+            match spawn_streaming_attempt(
+                &app,
+                &id,
+                attempt,
+                &info.script_path,
+                shell.as_deref(),
+                log.as_ref(),
+            ) {
+                Ok((next, next_readers)) => {
+                    child = next;
+                    readers = next_readers;
+                    info.pid = child.id();
+                    info.process_started = process_start_time(info.pid);
+                    let _ = table.insert(info.clone());
+                }
+                Err(e) => {
+                    log::warn!("Retry {} of {} failed to start: {}", attempt, id, e);
+                    attempt -= 1;
+                    break status;
+                }
+            }
+        };
+        if let Some(mut log) = log.as_ref().and_then(|l| l.lock().ok()) {
+            let code = status
+                .as_ref()
+                .ok()
+                .and_then(|s| s.code())
+                .map_or_else(|| "none".to_string(), |c| c.to_string());
+            let _ = writeln!(log, "=== exit code {} ===", code);
+        }
+        // Dropping the sender stops the sampler before the run is forgotten
+        let stats = sampler.and_then(|(stop, handle)| {
+            drop(stop);
+            handle.join().ok()
+        });
+        table.remove(&id);
+        let _ = app.emit(
+            "script-exit",
+            ScriptExit {
+                id,
+                success: status.as_ref().is_ok_and(|s| s.success()),
+                exit_code: status.ok().and_then(|s| s.code()),
+                duration_ms: started.elapsed().as_millis() as u64,
+                attempt,
+                attempt_exit_codes: exit_codes,
+                stats,
+            },
+        );
+    });
+
+    Ok(run)
+}
+
+/// Lists the scripts started by `run_script_streaming` that are still
+/// running.
+#[tauri::command]
+fn list_runs(runs: tauri::State<'_, Runs>) -> Result<Vec<RunInfo>, String> {
+    let mut list: Vec<RunInfo> = runs
+        .table
+        .entries
+        .lock()
+        .map_err(|e| e.to_string())?
+        .values()
+        .cloned()
+        .collect();
+    list.sort_by_key(|r| r.started_at);
+    Ok(list)
+}
+
+/// Immediately kills a running script and every process it started.
+#[tauri::command]
+fn kill_process(runs: tauri::State<'_, Runs>, run_id: String) -> Result<(), String> {
+    let pid = runs.table.pid(&run_id)?;
+    runs.table.mark_killed(&run_id);
+    kill_process_tree(pid, "KILL")
+}
+
+/// Suspends (or resumes) `pid` and every process it started without killing
+/// them: SIGSTOP/SIGCONT to its process group, or `NtSuspendProcess` /
+/// `NtResumeProcess` on each process of the tree on Windows. Returns
+/// whether every process was signalled.
+fn set_process_tree_suspended(pid: u32, suspended: bool) -> Result<bool, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let call = if suspended {
+            "NtSuspendProcess"
+        } else {
+            "NtResumeProcess"
+        };
+        let script = format!(
+            "$nt = Add-Type -Name Nt -Namespace Aui -PassThru -MemberDefinition '\
+             [DllImport(\"ntdll.dll\")] public static extern int NtSuspendProcess(IntPtr h); \
+             [DllImport(\"ntdll.dll\")] public static extern int NtResumeProcess(IntPtr h);'; \
+             $all = @(Get-CimInstance Win32_Process | Select-Object ProcessId, ParentProcessId); \
+             $ids = @({pid}); $i = 0; \
+             while ($i -lt $ids.Count) {{ \
+               $ids += @($all | Where-Object ParentProcessId -eq $ids[$i] | ForEach-Object ProcessId); $i++ }}; \
+             $failed = 0; \
+             foreach ($id in $ids) {{ \
+               try {{ $p = [Diagnostics.Process]::GetProcessById($id); \
+                 if ($nt::{call}($p.Handle) -ne 0) {{ $failed++ }} }} catch {{ $failed++ }} }}; \
+             exit $failed"
+        );
+        let status = hidden_command(&resolve_powershell()?)
+            .args(["-NoProfile", "-Command", &script])
+            .status()
+            .map_err(|e| format!("Failed to run PowerShell: {}", e))?;
+        Ok(status.success())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let signal = if suspended { "-STOP" } else { "-CONT" };
+        let status = StdCommand::new("kill")
+            .args([signal, "--", &format!("-{}", pid)])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .map_err(|e| format!("Failed to run kill: {}", e))?;
+        Ok(status.success())
+    }
+}
+
+/// Pauses a running script and everything it started, e.g. to free up the
+/// machine during a heavy deployment; `resume_process` continues it.
+/// Returns whether the processes could be suspended.
+#[tauri::command]
+fn suspend_process(runs: tauri::State<'_, Runs>, run_id: String) -> Result<bool, String> {
+    set_process_tree_suspended(runs.table.pid(&run_id)?, true)
+}
+
+/// Continues a script paused with `suspend_process`. Returns whether the
+/// processes could be resumed.
+#[tauri::command]
+fn resume_process(runs: tauri::State<'_, Runs>, run_id: String) -> Result<bool, String> {
+    set_process_tree_suspended(runs.table.pid(&run_id)?, false)
+}
+
+/// Lists runs from an earlier AUI session whose processes are still alive,
+/// so the UI can offer to stop them. Orphans that have since exited are
+/// dropped.
+#[tauri::command]
+fn list_orphaned_processes(runs: tauri::State<'_, Runs>) -> Result<Vec<RunInfo>, String> {
+    let mut orphans = runs.table.orphans.lock().map_err(|e| e.to_string())?;
+    let before = orphans.len();
+    orphans.retain(|_, run| run_process_matches(run));
+    let mut list: Vec<RunInfo> = orphans.values().cloned().collect();
+    let pruned = orphans.len() != before;
+    drop(orphans);
+
+    if pruned {
+        runs.table.persist();
+    }
+    list.sort_by_key(|r| r.started_at);
+    Ok(list)
+}
+
+#[derive(serde::Serialize)]
+struct StopResult {
+    run_id: String,
+    /// `terminated` when the script exited within the grace period,
+    /// `killed` when it had to be force-killed.
+    method: String,
+    waited_ms: u64,
+    /// Why the script was killed without a grace period: the exit request
+    /// couldn't be delivered (see `request_process_exit`).
+    note: Option<String>,
+}
+
+/// Asks a running script to exit (SIGTERM / WM_CLOSE), giving it
+/// `grace_secs` to clean up before force-killing the whole tree. When the
+/// request can't be delivered, as WM_CLOSE can't to a windowless script on
+/// Windows, the tree is killed straight away and `note` says why.
+#[tauri::command(async)]
+fn stop_process(
+    runs: tauri::State<'_, Runs>,
+    run_id: String,
+    grace_secs: u64,
+) -> Result<StopResult, String> {
+    let pid = runs.table.pid(&run_id)?;
+    runs.table.mark_killed(&run_id);
+
+    let started = Instant::now();
+    let refused = request_process_exit(pid).err();
+    let deadline = started + Duration::from_secs(grace_secs);
+    while refused.is_none() && Instant::now() < deadline {
+        if !process_alive(pid) {
+            return Ok(StopResult {
+                run_id,
+                method: "terminated".to_string(),
+                waited_ms: started.elapsed().as_millis() as u64,
+                note: None,
+            });
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    kill_process_tree(pid, "KILL")?;
+    Ok(StopResult {
+        run_id,
+        method: "killed".to_string(),
+        waited_ms: started.elapsed().as_millis() as u64,
+        note: refused.map(|e| format!("Couldn't ask the script to exit, so it was killed: {}", e)),
+    })
+}
+
+#[derive(serde::Serialize, Clone)]
+struct ProcessNode {
+    pid: u32,
+    parent_pid: u32,
+    name: String,
+    depth: u32,
+}
+
+/// Snapshot of every process as (pid, parent pid, name).
+fn process_table() -> Result<Vec<(u32, u32, String)>, String> {
+    #[cfg(target_os = "windows")]
+    let output = hidden_command(&resolve_powershell()?)
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_Process | ForEach-Object { \"$($_.ProcessId) $($_.ParentProcessId) $($_.Name)\" }",
+        ])
+        .output();
+    #[cfg(not(target_os = "windows"))]
+    let output = StdCommand::new("ps")
+        .args(["-A", "-o", "pid=,ppid=,comm="])
+        .output();
+
+    let output = output.map_err(|e| format!("Failed to list processes: {}", e))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let parent_pid = fields.next()?.parse().ok()?;
+            Some((pid, parent_pid, fields.collect::<Vec<_>>().join(" ")))
+        })
+        .collect())
+}
+
+/// Returns the process tree under a run started by `run_script_streaming`
+/// (or a terminal from `open_terminal`), root first. Children that exit
+/// while the table is read simply don't appear.
+#[tauri::command]
+fn list_process_tree(
+    runs: tauri::State<'_, Runs>,
+    terminals: tauri::State<'_, Terminals>,
+    run_id: String,
+) -> Result<Vec<ProcessNode>, String> {
+    let root = match runs.table.pid(&run_id).ok() {
+        Some(pid) => pid,
+        None => terminals
+            .entries
+            .lock()
+            .map_err(|e| e.to_string())?
+            .get(&run_id)
+            .ok_or_else(|| format!("No running script with id '{}'", run_id))?
+            .info
+            .pid
+            .ok_or_else(|| format!("'{}' has no process to inspect", run_id))?,
+    };
+
+    let table = process_table()?;
+    let Some((_, root_parent, root_name)) = table.iter().find(|(pid, _, _)| *pid == root) else {
+        return Err(format!("Run '{}' has already exited", run_id));
+    };
+
+    let mut tree = vec![ProcessNode {
+        pid: root,
+        parent_pid: *root_parent,
+        name: root_name.clone(),
+        depth: 0,
+    }];
+    let mut next = 0;
+    while next < tree.len() {
+        let (parent, depth) = (tree[next].pid, tree[next].depth);
+        for (pid, parent_pid, name) in &table {
+            // A reused pid can't be its own ancestor; guard against cycles
+            if *parent_pid == parent && !tree.iter().any(|n| n.pid == *pid) {
+                tree.push(ProcessNode {
+                    pid: *pid,
+                    parent_pid: *parent_pid,
+                    name: name.clone(),
+                    depth: depth + 1,
+                });
+            }
+        }
+        next += 1;
+    }
+    Ok(tree)
+}
+
+/// Most requests that may wait for the fetch rate limit at once; further
+/// ones fail instead of queueing.
+const FETCH_QUEUE_MAX: u32 = 64;
+
+/// The token bucket every outbound fetch passes through, see
+/// `set_fetch_rate_limit`. Unlimited until a rate is set.
+static FETCH_RATE_LIMIT: Mutex<Option<TokenBucket>> = Mutex::new(None);
+
+/// Allows `rate` requests a second with bursts of up to `capacity`.
+/// Waiting requests reserve their token up front, driving `tokens` below
+/// zero, so they go out in the order they arrived.
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+/// Waits until the fetch rate limit lets another request through. Errors
+/// when `FETCH_QUEUE_MAX` requests are already waiting.
+fn throttle_fetch() -> Result<(), String> {
+    let wait = {
+        let mut limit = FETCH_RATE_LIMIT.lock().map_err(|e| e.to_string())?;
+        let Some(bucket) = limit.as_mut() else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * bucket.rate).min(bucket.capacity);
+        bucket.refilled = now;
+        if bucket.tokens - 1.0 < -f64::from(FETCH_QUEUE_MAX) {
+            return Err(format!(
+                "Rate limited: {} requests are already waiting to be sent",
+                FETCH_QUEUE_MAX
+            ));
+        }
+        bucket.tokens -= 1.0;
+        (bucket.tokens < 0.0).then(|| Duration::from_secs_f64(-bucket.tokens / bucket.rate))
+    };
+    if let Some(wait) = wait {
+        std::thread::sleep(wait);
+    }
+    Ok(())
+}
+
+/// Caps outbound requests (`fetch_url`, `fetch_urls`, downloads, health
+/// probes, run reports, emails, SSE connects ...) at `requests_per_second`
+/// across the whole app, so a busy dashboard can't trip an API's rate
+/// limiting. Requests over the limit wait their turn, short bursts up to
+/// one second's worth pass straight through, and requests past a queue of
+/// 64 fail. `None` or 0 removes the limit.
+///
+/// Completion webhooks and emails sent by scheduled tasks aren't limited:
+/// their wrapper scripts run curl from cron or Task Scheduler, outside the
+/// app.
+#[tauri::command]
+fn set_fetch_rate_limit(requests_per_second: Option<f64>) -> Result<(), String> {
+    let rate = requests_per_second.filter(|r| *r != 0.0);
+    if let Some(rate) = rate {
+        if !rate.is_finite() || rate < 0.0 {
+            return Err(format!("Invalid rate limit {}", rate));
+        }
+    }
+    let mut limit = FETCH_RATE_LIMIT.lock().map_err(|e| e.to_string())?;
+    *limit = rate.map(|rate| {
+        let capacity = rate.max(1.0);
+        TokenBucket {
+            rate,
+            capacity,
+            tokens: capacity,
+            refilled: Instant::now(),
+        }
+    });
+    Ok(())
+}
+
+/// Builds a curl invocation with its console window suppressed on Windows.
+/// Requests identify as `AUI/<version>` unless a later `-A` (see
+/// `user_agent_args`) overrides it.
+fn curl_command() -> StdCommand {
+    let mut command = hidden_command("curl");
+    command.args(["-A", &default_user_agent()]);
+    command
+}
+
+fn default_user_agent() -> String {
+    format!("AUI/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// curl arguments sending `user_agent` instead of the `AUI/<version>`
+/// default, for endpoints that gate on it. Control characters are rejected
+/// since they would let the value smuggle in extra headers.
+fn user_agent_args(user_agent: Option<&str>) -> Result<Vec<String>, String> {
+    let Some(user_agent) = user_agent.map(str::trim).filter(|u| !u.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    if user_agent.chars().any(char::is_control) {
+        return Err("User agent can't contain control characters".into());
+    }
+    Ok(vec!["-A".to_string(), user_agent.to_string()])
+}
+
+/// Schemes the HTTP commands will hand to curl; anything else (`file:`,
+/// `javascript:`, `ftp:` ...) is rejected up front.
+const ALLOWED_URL_SCHEMES: [&str; 2] = ["http", "https"];
+
+/// Result of `validate_url`. On failure `valid` is false and `reason` says
+/// why; the parsed parts are filled in whenever the URL parsed at all.
+#[derive(serde::Serialize)]
+struct UrlValidation {
+    valid: bool,
+    scheme: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    reason: Option<String>,
+}
+
+/// Parses `url` and checks it is an http(s) URL with a host.
+fn parse_http_url(url: &str) -> Result<url::Url, String> {
+    let parsed = url::Url::parse(url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !ALLOWED_URL_SCHEMES.contains(&parsed.scheme()) {
+        return Err(format!(
+            "Unsupported URL scheme '{}' (expected http or https)",
+            parsed.scheme()
+        ));
+    }
+    if parsed.host_str().map_or(true, str::is_empty) {
+        return Err("URL has no host".to_string());
+    }
+    Ok(parsed)
+}
+
+/// Validates a URL for the fetch/probe commands so the UI can flag typos
+/// inline instead of surfacing a curl error.
+#[tauri::command]
+fn validate_url(url: String) -> UrlValidation {
+    let parsed = url::Url::parse(url.trim()).ok();
+    let reason = parse_http_url(&url).err();
+    UrlValidation {
+        valid: reason.is_none(),
+        scheme: parsed.as_ref().map(|u| u.scheme().to_string()),
+        host: parsed
+            .as_ref()
+            .and_then(|u| u.host_str())
+            .map(str::to_string),
+        port: parsed.as_ref().and_then(|u| u.port_or_known_default()),
+        reason,
+    }
+}
+
+/// curl arguments binding the request to a local interface name or source
+/// address (`--interface`), for checking egress on multi-homed hosts.
+fn interface_args(interface: Option<&str>) -> Result<Vec<String>, String> {
+    let Some(interface) = interface.map(str::trim).filter(|i| !i.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let valid = interface
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | ':' | '-' | '_' | '%'));
+    if !valid {
+        return Err(format!(
+            "Invalid interface or local address '{}'",
+            interface
+        ));
+    }
+    Ok(vec!["--interface".to_string(), interface.to_string()])
+}
+
+/// curl exit code for "failed binding local connection end".
+const CURL_INTERFACE_FAILED: i32 = 45;
+
+/// Proxy schemes curl understands: HTTP(S) proxies and SOCKS tunnels
+/// (`socks5h` resolves names through the proxy, `socks5` locally).
+const PROXY_SCHEMES: [&str; 6] = ["http", "https", "socks4", "socks4a", "socks5", "socks5h"];
+
+/// curl arguments sending the request through `proxy`, e.g.
+/// `socks5h://127.0.0.1:1080` for an `ssh -D` tunnel.
+fn proxy_args(proxy: Option<&str>) -> Result<Vec<String>, String> {
+    let Some(proxy) = proxy.map(str::trim).filter(|p| !p.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let parsed = url::Url::parse(proxy).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    if !PROXY_SCHEMES.contains(&parsed.scheme()) {
+        return Err(format!(
+            "Unsupported proxy scheme '{}' (expected one of {})",
+            parsed.scheme(),
+            PROXY_SCHEMES.join(", ")
+        ));
+    }
+    if !parsed.host_str().is_some_and(|h| !h.is_empty()) {
+        return Err(format!("Proxy URL '{}' has no host", proxy));
+    }
+    Ok(vec!["--proxy".to_string(), proxy.to_string()])
+}
+
+/// curl arguments pinning the HTTP version: "1.0", "1.1" or "2" (over
+/// http:// this tries an h2c upgrade and falls back to 1.1).
+fn http_version_args(version: Option<&str>) -> Result<Vec<String>, String> {
+    let flag = match version.map(str::trim).filter(|v| !v.is_empty()) {
+        None => return Ok(Vec::new()),
+        Some("1.0") => "--http1.0",
+        Some("1.1") => "--http1.1",
+        Some("2") | Some("2.0") => "--http2",
+        Some(other) => {
+            return Err(format!(
+                "Unsupported HTTP version '{}' (expected 1.0, 1.1 or 2)",
+                other
+            ))
+        }
+    };
+    Ok(vec![flag.to_string()])
+}
+
+/// Explains curl failing because it was built without the requested HTTP
+/// version.
+fn http_version_error(code: Option<i32>, version: Option<&str>, stderr: &str) -> Option<String> {
+    let version = version.map(str::trim).filter(|v| !v.is_empty())?;
+    (code == Some(CURL_NOT_BUILT_IN)).then(|| {
+        format!(
+            "This curl was built without HTTP/{} support: {}",
+            version,
+            stderr.trim()
+        )
+    })
+}
+
+/// curl exit codes for a proxy scheme this curl was built without, a host
+/// that doesn't resolve or accept connections, and a failed proxy (e.g.
+/// SOCKS) handshake.
+const CURL_NOT_BUILT_IN: i32 = 4;
+const CURL_UNREACHABLE: [i32; 3] = [5, 6, 7];
+const CURL_PROXY_FAILED: i32 = 97;
+
+/// Explains the curl exit codes that mean `proxy` rather than the target
+/// was the problem.
+fn proxy_error(code: Option<i32>, proxy: Option<&str>, stderr: &str) -> Option<String> {
+    let proxy = proxy.map(str::trim).filter(|p| !p.is_empty())?;
+    let host = url::Url::parse(proxy).ok()?.host_str()?.to_string();
+    match code? {
+        CURL_NOT_BUILT_IN => Some(format!(
+            "This curl was built without support for the proxy '{}': {}",
+            proxy,
+            stderr.trim()
+        )),
+        // Only when curl names the proxy, not the target behind it
+        c if CURL_UNREACHABLE.contains(&c) && stderr.contains(&host) => Some(format!(
+            "Could not reach proxy '{}': {}",
+            proxy,
+            stderr.trim()
+        )),
+        CURL_PROXY_FAILED => Some(format!(
+            "Proxy '{}' refused or failed the connection: {}",
+            proxy,
+            stderr.trim()
+        )),
+        _ => None,
+    }
+}
+
+/// curl exit codes for a client certificate or CA bundle it couldn't use.
+const CURL_CLIENT_CERT_FAILED: i32 = 58;
+const CURL_CA_CERT_FAILED: i32 = 77;
+
+/// Client certificate options for endpoints that require mutual TLS. Paths
+/// are PEM files, or for `client_cert_path` a `.p12`/`.pfx` bundle; the key
+/// may be left out when the certificate file also holds it.
+#[derive(serde::Deserialize, Clone, Default)]
+struct TlsOptions {
+    #[serde(default)]
+    client_cert_path: Option<String>,
+    #[serde(default)]
+    client_key_path: Option<String>,
+    #[serde(default)]
+    ca_cert_path: Option<String>,
+}
+
+/// curl arguments for `tls`, after checking each file can be read and
+/// looks like what it is supposed to be.
+fn tls_args(tls: &TlsOptions) -> Result<Vec<String>, String> {
+    let path = |p: &Option<String>| {
+        p.as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(expand_user_path)
+    };
+    let read = |kind: &str, path: &str| {
+        std::fs::read(path).map_err(|e| format!("Can't read {} {}: {}", kind, path, e))
+    };
+    let pem_has = |bytes: &[u8], label: &str| String::from_utf8_lossy(bytes).contains(label);
+
+    let mut args = Vec::new();
+    let cert = path(&tls.client_cert_path);
+    let key = path(&tls.client_key_path);
+    if let Some(cert) = &cert {
+        let bytes = read("client certificate", cert)?;
+        let lower = cert.to_lowercase();
+        if lower.ends_with(".p12") || lower.ends_with(".pfx") {
+            args.extend(["--cert-type".to_string(), "P12".to_string()]);
+        } else if !pem_has(&bytes, "-----BEGIN CERTIFICATE-----") {
+            return Err(format!("{} is not a PEM certificate", cert));
+        } else if key.is_none() && !pem_has(&bytes, "PRIVATE KEY-----") {
+            return Err(format!(
+                "{} has no private key; pass client_key_path as well",
+                cert
+            ));
+        }
+        args.extend(["--cert".to_string(), cert.clone()]);
+    }
+    if let Some(key) = &key {
+        if cert.is_none() {
+            return Err("client_key_path needs client_cert_path".into());
+        }
+        let bytes = read("client key", key)?;
+        if !pem_has(&bytes, "PRIVATE KEY-----") {
+            return Err(format!("{} is not a PEM private key", key));
+        }
+        args.extend(["--key".to_string(), key.clone()]);
+    }
+    if let Some(ca) = path(&tls.ca_cert_path) {
+        let bytes = read("CA certificate", &ca)?;
+        if !pem_has(&bytes, "-----BEGIN CERTIFICATE-----") {
+            return Err(format!("{} is not a PEM CA certificate", ca));
+        }
+        args.extend(["--cacert".to_string(), ca]);
+    }
+    Ok(args)
+}
+
+/// Explains the curl exit codes that mean one of the `tls` files was
+/// rejected, e.g. a key that doesn't match its certificate.
+fn tls_error(code: Option<i32>, stderr: &str) -> Option<String> {
+    match code {
+        Some(CURL_CLIENT_CERT_FAILED) => Some(format!(
+            "Client certificate or key could not be used: {}",
+            stderr.trim()
+        )),
+        Some(CURL_CA_CERT_FAILED) => Some(format!(
+            "CA certificate could not be loaded: {}",
+            stderr.trim()
+        )),
+        _ => None,
+    }
+}
+
+/// Responses `fetch_url` cached for a `cache_ttl_secs`, keyed by method,
+/// URL and every option that changes who asks or how the request is routed
+/// (interface, TLS files, proxy, HTTP version, user agent), so a response
+/// is never served to a request made with other credentials. Kept in
+/// memory only, for the lifetime of the app.
+#[derive(Default)]
+struct FetchCache(Mutex<HashMap<String, CachedResponse>>);
+
+struct CachedResponse {
+    body: String,
+    stored_at: Instant,
+    ttl: Duration,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Most responses `FetchCache` holds; the oldest is dropped past that.
+const FETCH_CACHE_MAX_ENTRIES: usize = 256;
+
+/// Splits `-D -` output into the final response's headers (lowercased
+/// names) and the body. Redirects and `100 Continue` put several header
+/// blocks in front of the body.
+fn split_response_headers(raw: &str) -> (Vec<(String, String)>, &str) {
+    let mut rest = raw;
+    let mut headers = Vec::new();
+    while rest.starts_with("HTTP/") {
+        let Some((block, body)) = rest.split_once("\r\n\r\n") else {
+            break;
+        };
+        headers = block
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect();
+        rest = body;
+    }
+    (headers, rest)
+}
+
+/// Fetches a URL and returns its body as a string.
+/// Bypasses webview CORS/CSP restrictions by running in Rust.
+/// `interface` optionally binds the request to a local interface or address.
+/// `client_cert_path`/`client_key_path` authenticate with a client
+/// certificate and `ca_cert_path` trusts a private CA (see `TlsOptions`).
+/// `proxy` routes the request through an HTTP or SOCKS proxy (see
+/// `proxy_args`) and `http_version` pins "1.1" or "2" for servers with
+/// protocol quirks. `user_agent` replaces the `AUI/<version>` default.
+///
+/// With `cache_ttl_secs`, a successful response is kept for that long and
+/// served again without a request, for endpoints polled often that rarely
+/// change. A shorter `Cache-Control: max-age` wins and `no-store` isn't
+/// cached; once an entry expires it is revalidated with its `ETag` or
+/// `Last-Modified`, so an unchanged resource only costs a 304.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command(async)]
+fn fetch_url(
+    cache: tauri::State<'_, FetchCache>,
+    url: String,
+    interface: Option<String>,
+    client_cert_path: Option<String>,
+    client_key_path: Option<String>,
+    ca_cert_path: Option<String>,
+    proxy: Option<String>,
+    http_version: Option<String>,
+    user_agent: Option<String>,
+    cache_ttl_secs: Option<u64>,
+) -> Result<String, String> {
+    let url = parse_http_url(&url)?.to_string();
+    let tls = TlsOptions {
+        client_cert_path,
+        client_key_path,
+        ca_cert_path,
+    };
+    let key = serde_json::json!([
+        "GET",
+        url,
+        interface,
+        tls.client_cert_path,
+        tls.client_key_path,
+        tls.ca_cert_path,
+        proxy,
+        http_version,
+        user_agent,
+    ])
+    .to_string();
+    let cache_ttl = cache_ttl_secs.filter(|t| *t > 0).map(Duration::from_secs);
+
+    let mut conditional = Vec::new();
+    if cache_ttl.is_some() {
+        let entries = cache.0.lock().map_err(|e| e.to_string())?;
+        if let Some(entry) = entries.get(&key) {
+            if entry.stored_at.elapsed() < entry.ttl {
+                return Ok(entry.body.clone());
+            }
+            if let Some(etag) = &entry.etag {
+                conditional.push(format!("If-None-Match: {}", etag));
+            }
+            if let Some(modified) = &entry.last_modified {
+                conditional.push(format!("If-Modified-Since: {}", modified));
+            }
+        }
+    }
+
+    throttle_fetch()?;
+    let mut command = curl_command();
+    command
+        .args(["-sSL", "--max-time", "15"])
+        .args(interface_args(interface.as_deref())?)
+        .args(tls_args(&tls)?)
+        .args(proxy_args(proxy.as_deref())?)
+        .args(http_version_args(http_version.as_deref())?)
+        .args(user_agent_args(user_agent.as_deref())?);
+    if cache_ttl.is_some() {
+        command.args([
+            "-D",
+            "-",
+            "-w",
+            &format!("{}%{{http_code}}", CURL_STATUS_MARKER),
+        ]);
+        for header in conditional
+            .iter()
+            .filter(|h| !h.chars().any(char::is_control))
+        {
+            command.args(["-H", header]);
+        }
+    }
+    let output = command
+        .arg(&url)
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if output.status.code() == Some(CURL_INTERFACE_FAILED) {
+        return Err(format!(
+            "Invalid interface or local address '{}'",
+            interface.unwrap_or_default().trim()
+        ));
+    }
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(error) = tls_error(output.status.code(), &stderr)
+            .or_else(|| proxy_error(output.status.code(), proxy.as_deref(), &stderr))
+            .or_else(|| http_version_error(output.status.code(), http_version.as_deref(), &stderr))
+        {
+            return Err(error);
+        }
+        return Err(format!("HTTP request failed: {}", stderr));
+    }
+
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|e| format!("Invalid UTF-8 in response: {}", e))?;
+    let Some(ttl) = cache_ttl else {
+        return Ok(stdout);
+    };
+
+    let (response, status) = stdout
+        .rsplit_once(CURL_STATUS_MARKER)
+        .ok_or_else(|| "HTTP request failed: no response from curl".to_string())?;
+    let status: u16 = status.trim().parse().unwrap_or(0);
+    let (headers, body) = split_response_headers(response);
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.clone())
+    };
+    let cache_control = header("cache-control")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let directives: Vec<&str> = cache_control.split(',').map(str::trim).collect();
+    let ttl = directives
+        .iter()
+        .find_map(|d| d.strip_prefix("max-age=")?.parse::<u64>().ok())
+        .map_or(ttl, |max_age| ttl.min(Duration::from_secs(max_age)));
+    // no-cache may be stored but must be revalidated every time
+    let ttl = if directives.contains(&"no-cache") {
+        Duration::ZERO
+    } else {
+        ttl
+    };
+
+    let mut entries = cache.0.lock().map_err(|e| e.to_string())?;
+    if status == 304 {
+        if let Some(entry) = entries.get_mut(&key) {
+            entry.stored_at = Instant::now();
+            entry.ttl = ttl;
+            return Ok(entry.body.clone());
+        }
+    }
+    if (200..300).contains(&status) && !directives.contains(&"no-store") {
+        if entries.len() >= FETCH_CACHE_MAX_ENTRIES && !entries.contains_key(&key) {
+            if let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, e)| e.stored_at)
+                .map(|(k, _)| k.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key,
+            CachedResponse {
+                body: body.to_string(),
+                stored_at: Instant::now(),
+                ttl,
+                etag: header("etag"),
+                last_modified: header("last-modified"),
+            },
+        );
+    }
+    Ok(body.to_string())
+}
+
+/// Empties the `fetch_url` response cache, returning how many responses
+/// were dropped.
+#[tauri::command]
+fn clear_fetch_cache(cache: tauri::State<'_, FetchCache>) -> Result<usize, String> {
+    let mut entries = cache.0.lock().map_err(|e| e.to_string())?;
+    let dropped = entries.len();
+    entries.clear();
+    Ok(dropped)
+}
+
+/// One request of a `fetch_urls` batch. `timeout_secs` defaults to 15;
+/// `http_version` and `user_agent` are as for `fetch_url`.
+#[derive(serde::Deserialize, Clone, Default)]
+struct FetchRequest {
+    url: String,
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    #[serde(default)]
+    interface: Option<String>,
+    #[serde(default)]
+    http_version: Option<String>,
+    #[serde(default)]
+    user_agent: Option<String>,
+    #[serde(default, flatten)]
+    tls: TlsOptions,
+}
+
+#[derive(serde::Serialize)]
+struct HttpResponse {
+    url: String,
+    status: u16,
+    body: String,
+    duration_ms: u64,
+}
+
+/// Separates the body from curl's `-w` status trailer.
+const CURL_STATUS_MARKER: &str = "\n__AUI_HTTP_STATUS__";
+
+fn fetch_response(request: &FetchRequest) -> Result<HttpResponse, String> {
+    let url = parse_http_url(&request.url)?.to_string();
+    let timeout = request.timeout_secs.unwrap_or(15).max(1);
+    throttle_fetch()?;
+    let started = Instant::now();
+    let output = curl_command()
+        .args(["-sSL", "--max-time", &timeout.to_string()])
+        .args(interface_args(request.interface.as_deref())?)
+        .args(tls_args(&request.tls)?)
+        .args(http_version_args(request.http_version.as_deref())?)
+        .args(user_agent_args(request.user_agent.as_deref())?)
+        .args(["-w", &format!("{}%{{http_code}}", CURL_STATUS_MARKER), &url])
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if output.status.code() == Some(CURL_INTERFACE_FAILED) {
+        return Err(format!(
+            "Invalid interface or local address '{}'",
+            request.interface.as_deref().unwrap_or_default().trim()
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout
+        .rsplit_once(CURL_STATUS_MARKER)
+        .ok_or_else(|| "HTTP request failed: no response from curl".to_string())?;
+    let status: u16 = status.trim().parse().unwrap_or(0);
+    if !output.status.success() || status == 0 {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(error) = tls_error(output.status.code(), &stderr).or_else(|| {
+            http_version_error(
+                output.status.code(),
+                request.http_version.as_deref(),
+                &stderr,
+            )
+        }) {
+            return Err(error);
+        }
+        return Err(format!("HTTP request failed: {}", stderr.trim()));
+    }
+
+    Ok(HttpResponse {
+        url,
+        status,
+        body: body.to_string(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Fetches several URLs concurrently, at most `max_concurrency` at a time,
+/// returning one result per request in input order.
+#[tauri::command(async)]
+fn fetch_urls(
+    requests: Vec<FetchRequest>,
+    max_concurrency: Option<usize>,
+) -> Vec<Result<HttpResponse, String>> {
+    let workers = max_concurrency
+        .unwrap_or(8)
+        .clamp(1, 32)
+        .min(requests.len());
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<Result<HttpResponse, String>>>> =
+        requests.iter().map(|_| Mutex::new(None)).collect();
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(request) = requests.get(i) else {
+                    break;
+                };
+                let result = fetch_response(request);
+                if let Ok(mut slot) = results[i].lock() {
+                    *slot = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|slot| {
+            slot.into_inner()
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| Err("Request did not complete".to_string()))
+        })
+        .collect()
+}
+
+/// Fetches a URL and parses the body as JSON. `http_version` and
+/// `user_agent` are as for `fetch_url`.
+#[tauri::command(async)]
+fn fetch_json(
+    url: String,
+    http_version: Option<String>,
+    user_agent: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let response = fetch_response(&FetchRequest {
+        url,
+        http_version,
+        user_agent,
+        ..Default::default()
+    })?;
+    if response.status >= 400 {
+        return Err(format!(
+            "HTTP request failed with status {}",
+            response.status
+        ));
+    }
+    serde_json::from_str(&response.body).map_err(|e| format!("Invalid JSON in response: {}", e))
+}
+
+/// One step of `diagnose_endpoint`. Steps after a failure aren't attempted
+/// and come back `skipped`.
+#[derive(serde::Serialize)]
+struct DiagnosticStage {
+    stage: String,
+    success: bool,
+    skipped: bool,
+    latency_ms: u64,
+    detail: Option<String>,
+    error: Option<String>,
+}
+
+/// The result of `diagnose_endpoint`: the `dns`, `tcp` and `http` stages in
+/// order, and which one failed first, if any.
+#[derive(serde::Serialize)]
+struct EndpointDiagnosis {
+    url: String,
+    host: String,
+    port: u16,
+    stages: Vec<DiagnosticStage>,
+    failed_stage: Option<String>,
+}
+
+/// Checks an http(s) endpoint one layer at a time: resolves its host,
+/// opens a TCP connection to the port, then makes the HTTP request, so a
+/// failure can be pinned on DNS, the network or the application. A 4xx or
+/// 5xx response fails the HTTP stage. `timeout_secs` (default 5) bounds the
+/// TCP connect; the request gets three times as long.
+#[tauri::command(async)]
+fn diagnose_endpoint(url: String, timeout_secs: Option<u64>) -> Result<EndpointDiagnosis, String> {
+    use std::net::ToSocketAddrs;
+
+    let parsed = parse_http_url(&url)?;
+    let host = parsed
+        .host_str()
+        .unwrap_or_default()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = parsed
+        .port_or_known_default()
+        .ok_or_else(|| "URL has no port".to_string())?;
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(5).max(1));
+
+    let stage = |name: &str, started: Instant, result: Result<String, String>| {
+        let latency_ms = started.elapsed().as_millis() as u64;
+        let (detail, error) = match result {
+            Ok(detail) => (Some(detail), None),
+            Err(error) => (None, Some(error)),
+        };
+        DiagnosticStage {
+            stage: name.to_string(),
+            success: error.is_none(),
+            skipped: false,
+            latency_ms,
+            detail,
+            error,
+        }
+    };
+    let mut stages = Vec::new();
+
+    let started = Instant::now();
+    let addresses: Vec<std::net::SocketAddr> = (host.as_str(), port)
+        .to_socket_addrs()
+        .map(|a| a.collect())
+        .unwrap_or_default();
+    stages.push(stage(
+        "dns",
+        started,
+        if addresses.is_empty() {
+            Err(format!("Could not resolve {}", host))
+        } else {
+            Ok(addresses
+                .iter()
+                .map(|a| a.ip().to_string())
+                .collect::<Vec<_>>()
+                .join(", "))
+        },
+    ));
+
+    if !addresses.is_empty() {
+        let started = Instant::now();
+        let mut last_error = String::new();
+        let connected =
+            addresses.iter().find(|address| {
+                match std::net::TcpStream::connect_timeout(address, timeout) {
+                    Ok(_) => true,
+                    Err(e) => {
+                        last_error = e.to_string();
+                        false
+                    }
+                }
+            });
+        stages.push(stage(
+            "tcp",
+            started,
+            match connected {
+                Some(address) => Ok(format!("Connected to {}", address)),
+                None => Err(format!(
+                    "Could not connect to port {}: {}",
+                    port, last_error
+                )),
+            },
+        ));
+    }
+
+    if stages.iter().all(|s| s.success) {
+        let started = Instant::now();
+        let response = fetch_response(&FetchRequest {
+            url: parsed.to_string(),
+            timeout_secs: Some(timeout.as_secs() * 3),
+            ..Default::default()
+        });
+        stages.push(stage(
+            "http",
+            started,
+            match response {
+                Ok(r) if r.status >= 400 => Err(format!("HTTP {}", r.status)),
+                Ok(r) => Ok(format!("HTTP {}", r.status)),
+                Err(e) => Err(e),
+            },
+        ));
+    }
+
+    let failed_stage = stages.iter().find(|s| !s.success).map(|s| s.stage.clone());
+    for name in ["dns", "tcp", "http"].iter().skip(stages.len()) {
+        stages.push(DiagnosticStage {
+            stage: name.to_string(),
+            success: false,
+            skipped: true,
+            latency_ms: 0,
+            detail: None,
+            error: None,
+        });
+    }
+    Ok(EndpointDiagnosis {
+        url: parsed.to_string(),
+        host,
+        port,
+        stages,
+        failed_stage,
+    })
+}
+
+/// The result of `check_tcp_port`. `detail` says why a closed port is
+/// closed: "Connection refused" means the host answered but nothing is
+/// listening, "Timed out ..." that nothing answered at all (host down or
+/// a firewall dropping packets). `address` is the address that accepted.
+#[derive(serde::Serialize)]
+struct TcpPortCheck {
+    host: String,
+    port: u16,
+    open: bool,
+    latency_ms: Option<u64>,
+    address: Option<String>,
+    detail: String,
+}
+
+/// Tries a plain TCP connection to `host:port`, e.g. to check a service is
+/// listening after a deploy. A closed or unreachable port is a normal
+/// result, not an error. Every address the host resolves to is tried until
+/// one accepts, all within `timeout_ms` (default 3000).
+#[tauri::command(async)]
+fn check_tcp_port(
+    host: String,
+    port: u16,
+    timeout_ms: Option<u64>,
+) -> Result<TcpPortCheck, String> {
+    use std::io::ErrorKind;
+    use std::net::ToSocketAddrs;
+
+    let host = host
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    if host.is_empty() {
+        return Err("Host is required".into());
+    }
+    if port == 0 {
+        return Err("Port must be between 1 and 65535".into());
+    }
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(3000).max(1));
+    let closed = |detail: String| TcpPortCheck {
+        host: host.clone(),
+        port,
+        open: false,
+        latency_ms: None,
+        address: None,
+        detail,
+    };
+
+    let started = Instant::now();
+    let addresses: Vec<std::net::SocketAddr> = match (host.as_str(), port).to_socket_addrs() {
+        Ok(addresses) => addresses.collect(),
+        Err(e) => return Ok(closed(format!("Could not resolve {}: {}", host, e))),
+    };
+    if addresses.is_empty() {
+        return Ok(closed(format!("Could not resolve {}", host)));
+    }
+
+    let mut refused = false;
+    let mut last_error = None;
+    for address in &addresses {
+        let Some(remaining) = timeout
+            .checked_sub(started.elapsed())
+            .filter(|r| !r.is_zero())
+        else {
+            break;
+        };
+        match std::net::TcpStream::connect_timeout(address, remaining) {
+            Ok(_) => {
+                return Ok(TcpPortCheck {
+                    host: host.clone(),
+                    port,
+                    open: true,
+                    latency_ms: Some(started.elapsed().as_millis() as u64),
+                    address: Some(address.to_string()),
+                    detail: "Open".into(),
+                })
+            }
+            Err(e) if e.kind() == ErrorKind::ConnectionRefused => refused = true,
+            Err(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {}
+            Err(e) => last_error = Some(e.to_string()),
+        }
+    }
+
+    // A refusal from any address beats a timeout: that host is up
+    Ok(closed(if refused {
+        "Connection refused".to_string()
+    } else if let Some(error) = last_error {
+        error
+    } else {
+        format!("Timed out after {} ms", timeout.as_millis())
+    }))
+}
+
+/// A semantic version: `major.minor.patch` plus prerelease identifiers.
+/// Build metadata is ignored, as semver precedence requires.
+#[derive(PartialEq, Eq)]
+struct SemVer {
+    core: [u64; 3],
+    pre: Vec<String>,
+}
+
+impl SemVer {
+    /// Parses "1.2.3", "v1.2", "1.2.3-beta.1+build.5" and the like.
+    fn parse(version: &str) -> Option<SemVer> {
+        let version = version.trim();
+        let version = version
+            .strip_prefix('v')
+            .or_else(|| version.strip_prefix('V'))
+            .unwrap_or(version);
+        let version = version.split('+').next()?;
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, pre.split('.').map(str::to_string).collect()),
+            None => (version, Vec::new()),
+        };
+
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.is_empty() || parts.len() > 3 {
+            return None;
+        }
+        let mut numbers = [0u64; 3];
+        for (slot, part) in numbers.iter_mut().zip(&parts) {
+            *slot = part.parse().ok()?;
+        }
+        Some(SemVer { core: numbers, pre })
+    }
+
+    fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemVer {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering as Cmp;
+
+        self.core.cmp(&other.core).then_with(|| {
+            match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Cmp::Equal,
+                // A release outranks its prereleases
+                (true, false) => Cmp::Greater,
+                (false, true) => Cmp::Less,
+                (false, false) => {
+                    for (a, b) in self.pre.iter().zip(&other.pre) {
+                        let order = match (a.parse::<u64>(), b.parse::<u64>()) {
+                            (Ok(a), Ok(b)) => a.cmp(&b),
+                            (Ok(_), Err(_)) => Cmp::Less,
+                            (Err(_), Ok(_)) => Cmp::Greater,
+                            (Err(_), Err(_)) => a.cmp(b),
+                        };
+                        if order != Cmp::Equal {
+                            return order;
+                        }
+                    }
+                    self.pre.len().cmp(&other.pre.len())
+                }
+            }
+        })
+    }
+}
+
+#[derive(serde::Serialize)]
+struct UpdateInfo {
+    update_available: bool,
+    current_version: String,
+    latest_version: Option<String>,
+    download_url: Option<String>,
+    notes: Option<String>,
+}
+
+/// Reads one release from a feed entry: Tauri updater style (`version`,
+/// `url`/`download_url`, `notes`) or a GitHub release (`tag_name`,
+/// `assets`, `html_url`, `body`). Drafts and unparseable versions yield
+/// `None`.
+fn feed_release(entry: &serde_json::Value) -> Option<(SemVer, UpdateInfo)> {
+    let text = |key: &str| entry.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    if entry.get("draft").and_then(|d| d.as_bool()) == Some(true) {
+        return None;
+    }
+
+    let raw_version = text("version").or_else(|| text("tag_name"))?;
+    let mut version = SemVer::parse(&raw_version)?;
+    if entry.get("prerelease").and_then(|p| p.as_bool()) == Some(true) && version.pre.is_empty() {
+        version.pre.push("pre".to_string());
+    }
+    let asset_url = entry
+        .get("assets")
+        .and_then(|a| a.as_array())
+        .and_then(|assets| assets.first())
+        .and_then(|asset| asset.get("browser_download_url"))
+        .and_then(|u| u.as_str())
+        .map(str::to_string);
+
+    let info = UpdateInfo {
+        update_available: false,
+        current_version: String::new(),
+        latest_version: Some(
+            raw_version
+                .trim()
+                .trim_start_matches(['v', 'V'])
+                .to_string(),
+        ),
+        download_url: text("download_url")
+            .or_else(|| text("url"))
+            .or(asset_url)
+            .or_else(|| text("html_url")),
+        notes: text("notes").or_else(|| text("body")),
+    };
+    Some((version, info))
+}
+
+/// Checks a JSON release feed (a single release, a list of releases, or an
+/// object with a `releases` list) for a version newer than
+/// `current_version`. Prereleases are only offered to prerelease builds;
+/// feed entries with malformed versions are skipped.
+#[tauri::command(async)]
+fn check_for_update(feed_url: String, current_version: String) -> Result<UpdateInfo, String> {
+    let current = SemVer::parse(&current_version)
+        .ok_or_else(|| format!("Invalid current version '{}'", current_version))?;
+    let feed = fetch_json(feed_url, None, None)?;
+
+    let entries: Vec<&serde_json::Value> = match &feed {
+        serde_json::Value::Array(list) => list.iter().collect(),
+        other => match other.get("releases").and_then(|r| r.as_array()) {
+            Some(list) => list.iter().collect(),
+            None => vec![other],
+        },
+    };
+    let latest = entries
+        .into_iter()
+        .filter_map(feed_release)
+        .filter(|(version, _)| current.is_prerelease() || !version.is_prerelease())
+        .max_by(|a, b| a.0.cmp(&b.0));
+
+    let Some((version, info)) = latest else {
+        return Err("The release feed has no valid releases".into());
+    };
+    Ok(UpdateInfo {
+        update_available: version > current,
+        current_version,
+        ..info
+    })
+}
+
+/// Outcome of a single reachability probe. `reachable` means the server
+/// answered with a 2xx/3xx status; `status` is absent when no HTTP response
+/// arrived at all (DNS failure, refused connection, timeout).
+#[derive(serde::Serialize, Clone, PartialEq)]
+struct Reachability {
+    reachable: bool,
+    status: Option<u16>,
+    latency_ms: Option<u64>,
+}
+
+fn probe_url(
+    url: &str,
+    timeout_secs: u64,
+    interface: Option<&str>,
+) -> Result<Reachability, String> {
+    let null_device = if cfg!(target_os = "windows") {
+        "NUL"
+    } else {
+        "/dev/null"
+    };
+    throttle_fetch()?;
+    let output = curl_command()
+        .args(interface_args(interface)?)
+        .args([
+            "-sL",
+            "-o",
+            null_device,
+            "-w",
+            "%{http_code} %{time_total}",
+            "--max-time",
+            &timeout_secs.to_string(),
+            url,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if output.status.code() == Some(CURL_INTERFACE_FAILED) {
+        return Err(format!(
+            "Invalid interface or local address '{}'",
+            interface.unwrap_or_default().trim()
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.split_whitespace();
+    let status = fields
+        .next()
+        .and_then(|c| c.parse::<u16>().ok())
+        .filter(|c| *c != 0);
+    let latency_ms = fields
+        .next()
+        .and_then(|t| t.parse::<f64>().ok())
+        .filter(|_| status.is_some())
+        .map(|t| (t * 1000.0).round() as u64);
+
+    Ok(Reachability {
+        reachable: matches!(status, Some(200..=399)),
+        status,
+        latency_ms,
+    })
+}
+
+/// Checks whether a URL answers, returning its HTTP status and latency.
+/// `interface` optionally binds the probe to a local interface or address.
+#[tauri::command(async)]
+fn check_url_reachable(url: String, interface: Option<String>) -> Result<Reachability, String> {
+    probe_url(parse_http_url(&url)?.as_str(), 10, interface.as_deref())
+}
+
+/// Result of `measure_download_speed`. `complete` is false when the
+/// duration ran out before the whole body arrived.
+#[derive(serde::Serialize)]
+struct DownloadSpeed {
+    bytes_per_sec: u64,
+    total_bytes: u64,
+    duration_ms: u64,
+    complete: bool,
+}
+
+/// Downloads from `url` for up to `duration_secs` (or until the body is
+/// complete), discarding the data, and reports the observed throughput.
+#[tauri::command(async)]
+fn measure_download_speed(url: String, duration_secs: u64) -> Result<DownloadSpeed, String> {
+    if duration_secs == 0 {
+        return Err("Duration must be at least 1 second".into());
+    }
+    let url = parse_http_url(&url)?.to_string();
+    let null_device = if cfg!(target_os = "windows") {
+        "NUL"
+    } else {
+        "/dev/null"
+    };
+    throttle_fetch()?;
+    let output = curl_command()
+        .args([
+            "-sL",
+            "-o",
+            null_device,
+            "-w",
+            "%{http_code} %{size_download} %{time_total}",
+            "--max-time",
+            &duration_secs.to_string(),
+            &url,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.split_whitespace().collect();
+    let status = fields
+        .first()
+        .and_then(|c| c.parse::<u16>().ok())
+        .unwrap_or(0);
+    if status == 0 {
+        return Err(format!("URL unreachable: {}", url));
+    }
+    if status >= 400 {
+        return Err(format!("HTTP request failed with status {}", status));
+    }
+    let total_bytes = fields
+        .get(1)
+        .and_then(|b| b.parse::<u64>().ok())
+        .unwrap_or(0);
+    let seconds = fields
+        .get(2)
+        .and_then(|t| t.parse::<f64>().ok())
+        .unwrap_or(0.0);
+
+    // curl exits with 28 when --max-time cut the transfer short
+    let complete = output.status.success();
+    if !complete && output.status.code() != Some(28) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("HTTP request failed: {}", stderr.trim()));
+    }
+
+    Ok(DownloadSpeed {
+        bytes_per_sec: if seconds > 0.0 {
+            (total_bytes as f64 / seconds).round() as u64
+        } else {
+            0
+        },
+        total_bytes,
+        duration_ms: (seconds * 1000.0).round() as u64,
+        complete,
+    })
+}
+
+#[derive(serde::Serialize)]
+struct DownloadResult {
+    path: String,
+    bytes: u64,
+}
+
+/// Downloads `url` to `dest_path` via a `.part` file that is only renamed
+/// into place once the transfer succeeded, optionally through `proxy`.
+fn download_to(url: &str, dest_path: &str, proxy: Option<&str>) -> Result<DownloadResult, String> {
+    let url = parse_http_url(url)?.to_string();
+    let partial = format!("{}.part", dest_path);
+    throttle_fetch()?;
+    let output = curl_command()
+        .args(proxy_args(proxy)?)
+        .args(["-fsSL", "-o", &partial, &url])
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&partial);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(error) = proxy_error(output.status.code(), proxy, &stderr) {
+            return Err(error);
+        }
+        return Err(format!("Download failed: {}", stderr.trim()));
+    }
+    std::fs::rename(&partial, dest_path)
+        .map_err(|e| format!("Failed to move download to {}: {}", dest_path, e))?;
+
+    let bytes = std::fs::metadata(dest_path).map(|m| m.len()).unwrap_or(0);
+    Ok(DownloadResult {
+        path: dest_path.to_string(),
+        bytes,
+    })
+}
+
+/// Downloads a URL to a file, through an HTTP or SOCKS `proxy` when given
+/// (see `proxy_args`).
+#[tauri::command(async)]
+fn download_file(
+    url: String,
+    dest_path: String,
+    proxy: Option<String>,
+) -> Result<DownloadResult, String> {
+    download_to(&url, &expand_user_path(&dest_path), proxy.as_deref())
+}
+
+/// Lowercase hex SHA-256 of a file's contents.
+fn sha256_file(path: &str) -> Result<String, String> {
+    use sha2::Digest;
+    use std::io::Read;
+
+    let mut file =
+        std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let mut hasher = sha2::Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Returns the SHA-256 of a file as lowercase hex.
+#[tauri::command(async)]
+fn file_sha256(path: String) -> Result<String, String> {
+    sha256_file(&expand_user_path(&path))
+}
+
+#[derive(serde::Serialize)]
+struct VerifiedDownload {
+    path: String,
+    bytes: u64,
+    sha256: String,
+}
+
+/// Downloads a URL and checks the file's SHA-256 against `expected_sha256`.
+/// On a mismatch the file is deleted and the error carries the computed
+/// hash.
+#[tauri::command(async)]
+fn download_and_verify(
+    url: String,
+    dest_path: String,
+    expected_sha256: String,
+) -> Result<VerifiedDownload, String> {
+    let expected = expected_sha256.trim().to_lowercase();
+    if expected.len() != 64 || !expected.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "Invalid SHA-256 '{}': expected 64 hex digits",
+            expected_sha256
+        ));
+    }
+
+    let download = download_to(&url, &expand_user_path(&dest_path), None)?;
+    let sha256 = match sha256_file(&download.path) {
+        Ok(hash) => hash,
+        Err(e) => {
+            let _ = std::fs::remove_file(&download.path);
+            return Err(e);
+        }
+    };
+    if sha256 != expected {
+        let _ = std::fs::remove_file(&download.path);
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            download.path, expected, sha256
+        ));
+    }
+
+    Ok(VerifiedDownload {
+        path: download.path,
+        bytes: download.bytes,
+        sha256,
+    })
+}
+
+/// curl's exit code when `-C` was refused because the server ignored the
+/// Range request.
+const CURL_RANGE_ERROR: i32 = 33;
+
+/// A download started with `start_download`. `state` is `downloading`,
+/// `paused`, `completed` or `failed`; paused and failed downloads keep their
+/// `.part` file so `resume_download` can continue from it.
+struct DownloadJob {
+    url: String,
+    dest_path: String,
+    proxy: Option<String>,
+    total_bytes: Option<u64>,
+    state: String,
+    error: Option<String>,
+    pid: Option<u32>,
+}
+
+/// Background downloads, keyed by caller-chosen id.
+#[derive(Default)]
+struct Downloads(Arc<Mutex<HashMap<String, DownloadJob>>>);
+
+/// A `download-progress` event, and a row of `list_downloads`. `bytes` is
+/// what has arrived so far, including what a resumed download already had.
+#[derive(serde::Serialize, Clone)]
+struct DownloadProgress {
+    id: String,
+    url: String,
+    dest_path: String,
+    state: String,
+    bytes: u64,
+    total_bytes: Option<u64>,
+    error: Option<String>,
+}
+
+fn download_progress(id: &str, job: &DownloadJob) -> DownloadProgress {
+    let partial = format!("{}.part", job.dest_path);
+    let path = if job.state == "completed" {
+        &job.dest_path
+    } else {
+        &partial
+    };
+    DownloadProgress {
+        id: id.to_string(),
+        url: job.url.clone(),
+        dest_path: job.dest_path.clone(),
+        state: job.state.clone(),
+        bytes: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        total_bytes: job.total_bytes,
+        error: job.error.clone(),
+    }
+}
+
+/// What a server says about a URL without sending its body. Headers the
+/// server didn't send are `None`; `accept_ranges` is whether it serves byte
+/// ranges (so an interrupted download can resume).
+#[derive(serde::Serialize)]
+struct UrlMetadata {
+    url: String,
+    status: u16,
+    content_length: Option<u64>,
+    content_type: Option<String>,
+    last_modified: Option<String>,
+    etag: Option<String>,
+    accept_ranges: bool,
+}
+
+/// Fetches a URL's headers with a HEAD request, or with a GET of its first
+/// byte for servers that reject or mishandle HEAD. Only the final response
+/// after redirects counts.
+fn url_metadata(url: &str, proxy: Option<&str>) -> Result<UrlMetadata, String> {
+    let request = |args: &[&str]| -> Result<(u16, Vec<(String, String)>), String> {
+        throttle_fetch()?;
+        let output = curl_command()
+            .args(proxy_args(proxy)?)
+            .args(["-sSL", "--max-time", "15"])
+            .args(args)
+            .arg(url)
+            .output()
+            .map_err(|e| format!("Failed to run curl: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("HTTP request failed: {}", stderr.trim()));
+        }
+        // Each redirect hop adds a block of headers
+        let headers = String::from_utf8_lossy(&output.stdout);
+        let last = headers
+            .split("\r\n\r\n")
+            .filter(|block| block.trim_start().starts_with("HTTP/"))
+            .last()
+            .unwrap_or_default();
+        let mut lines = last.lines();
+        let status = lines
+            .next()
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|code| code.parse().ok())
+            .unwrap_or(0);
+        let fields = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect();
+        Ok((status, fields))
+    };
+
+    let null = if cfg!(target_os = "windows") {
+        "NUL"
+    } else {
+        "/dev/null"
+    };
+    let (status, fields, ranged) = match request(&["-I"]) {
+        Ok((status, fields)) if (200..400).contains(&status) => (status, fields, false),
+        head => {
+            match request(&["-r", "0-0", "-D", "-", "-o", null]) {
+                Ok((status, fields)) => (status, fields, true),
+                // Neither worked: report what HEAD got, it was asked first
+                Err(_) => {
+                    let (status, fields) = head?;
+                    (status, fields, false)
+                }
+            }
+        }
+    };
+    let header = |name: &str| {
+        fields
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.clone())
+            .filter(|v| !v.is_empty())
+    };
+
+    // A 206 to the ranged GET carries the full size as "bytes 0-0/<total>"
+    let partial = ranged && status == 206;
+    let content_length = if partial {
+        header("content-range")
+            .and_then(|r| r.rsplit_once('/').and_then(|(_, total)| total.parse().ok()))
+    } else {
+        header("content-length").and_then(|l| l.parse().ok())
+    };
+    let accept_ranges =
+        partial || header("accept-ranges").is_some_and(|v| v.eq_ignore_ascii_case("bytes"));
+    Ok(UrlMetadata {
+        url: url.to_string(),
+        status,
+        content_length,
+        content_type: header("content-type"),
+        last_modified: header("last-modified"),
+        etag: header("etag"),
+        accept_ranges,
+    })
+}
+
+/// Reads a URL's size, type, modification date, ETag and range support
+/// without downloading it, e.g. to show how big a download will be before
+/// starting it. `proxy` is as for `start_download`.
+#[tauri::command(async)]
+fn get_url_metadata(url: String, proxy: Option<String>) -> Result<UrlMetadata, String> {
+    let url = parse_http_url(&url)?.to_string();
+    url_metadata(&url, proxy.as_deref())
+}
+
+/// The file's size and whether the server serves byte ranges (see
+/// `url_metadata`), or nothing known when it can't be asked.
+fn remote_file_info(url: &str, proxy: Option<&str>) -> (Option<u64>, bool) {
+    url_metadata(url, proxy).map_or((None, false), |m| (m.content_length, m.accept_ranges))
+}
+
+/// Runs (or resumes) download `id` to completion, pause or failure,
+/// emitting `download-progress` as it goes. A partial file is continued
+/// with a Range request when the server supports it, and started over
+/// when it doesn't.
+fn run_download(
+    app: tauri::AppHandle,
+    table: Arc<Mutex<HashMap<String, DownloadJob>>>,
+    id: String,
+) {
+    #[cfg(not(target_os = "windows"))]
+    use std::os::unix::process::CommandExt;
+
+    let update = |edit: &dyn Fn(&mut DownloadJob)| {
+        let Ok(mut table) = table.lock() else {
+            return;
+        };
+        if let Some(job) = table.get_mut(&id) {
+            edit(job);
+            let _ = app.emit("download-progress", download_progress(&id, job));
+        }
+    };
+    let Some((url, dest_path, proxy)) = table.lock().ok().and_then(|table| {
+        let job = table.get(&id)?;
+        Some((job.url.clone(), job.dest_path.clone(), job.proxy.clone()))
+    }) else {
+        return;
+    };
+    let partial = format!("{}.part", dest_path);
+
+    let (total_bytes, accepts_ranges) = remote_file_info(&url, proxy.as_deref());
+    update(&|job| job.total_bytes = total_bytes);
+    let mut offset = std::fs::metadata(&partial).map(|m| m.len()).unwrap_or(0);
+    if offset > 0 && !accepts_ranges {
+        let _ = std::fs::remove_file(&partial);
+        offset = 0;
+    }
+
+    let result = loop {
+        if let Err(e) = throttle_fetch() {
+            break Err(e);
+        }
+        let mut command = curl_command();
+        command
+            .args(proxy_args(proxy.as_deref()).unwrap_or_default())
+            .args(["-fsSL", "-o", &partial])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped());
+        if offset > 0 {
+            command.args(["-C", &offset.to_string()]);
+        }
+        command.arg(&url);
+        #[cfg(not(target_os = "windows"))]
+        command.process_group(0);
+        // Spawned under the lock `pause_download` takes: a pause that came
+        // while the size was fetched or the fetch throttled stops the
+        // download here, and a later one finds the pid to kill
+        let mut child = {
+            let mut jobs = match table.lock() {
+                Ok(jobs) => jobs,
+                Err(e) => break Err(e.to_string()),
+            };
+            match jobs.get_mut(&id) {
+                Some(job) if job.state != "paused" => match command.spawn() {
+                    Ok(child) => {
+                        job.pid = Some(child.id());
+                        child
+                    }
+                    Err(e) => break Err(format!("Failed to run curl: {}", e)),
+                },
+                _ => break Err(format!("Download '{}' was paused", id)),
+            }
+        };
+        update(&|_| {});
+
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) => {}
+                Err(e) => break Err(format!("curl process error: {}", e)),
+            }
+            std::thread::sleep(Duration::from_millis(500));
+            update(&|_| {});
+        };
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            let _ = std::io::Read::read_to_string(&mut pipe, &mut stderr);
+        }
+        let status = match status {
+            Ok(status) => status,
+            Err(e) => break Err(e),
+        };
+        if status.success() {
+            break Ok(());
+        }
+        // The HEAD request can promise ranges the GET then ignores
+        if status.code() == Some(CURL_RANGE_ERROR) && offset > 0 {
+            let _ = std::fs::remove_file(&partial);
+            offset = 0;
+            continue;
+        }
+        break Err(proxy_error(status.code(), proxy.as_deref(), &stderr)
+            .unwrap_or_else(|| format!("Download failed: {}", stderr.trim())));
+    };
+
+    let paused = table
+        .lock()
+        .ok()
+        .and_then(|table| table.get(&id).map(|job| job.state == "paused"))
+        .unwrap_or(false);
+    match result {
+        Err(_) if paused => update(&|job| job.pid = None),
+        Ok(()) => match std::fs::rename(&partial, &dest_path) {
+            Ok(()) => update(&|job| {
+                job.pid = None;
+                job.state = "completed".into();
+                job.error = None;
+            }),
+            Err(e) => {
+                let error = format!("Failed to move download to {}: {}", dest_path, e);
+                update(&|job| {
+                    job.pid = None;
+                    job.state = "failed".into();
+                    job.error = Some(error.clone());
+                })
+            }
+        },
+        Err(error) => update(&|job| {
+            job.pid = None;
+            job.state = "failed".into();
+            job.error = Some(error.clone());
+        }),
+    }
+}
+
+/// Downloads `url` to `dest_path` in the background under `id`, emitting
+/// `download-progress` events, so a large file can be paused and resumed
+/// (`pause_download`, `resume_download`) or continued after a dropped
+/// connection. Unlike `download_file` the `.part` file is kept on failure.
+/// Reusing the id of a finished, failed or paused download replaces it.
+#[tauri::command]
+fn start_download(
+    app: tauri::AppHandle,
+    downloads: tauri::State<'_, Downloads>,
+    id: String,
+    url: String,
+    dest_path: String,
+    proxy: Option<String>,
+) -> Result<(), String> {
+    let url = parse_http_url(&url)?.to_string();
+    proxy_args(proxy.as_deref())?;
+    let dest_path = expand_user_path(&dest_path);
+    {
+        let mut table = downloads.0.lock().map_err(|e| e.to_string())?;
+        if table.get(&id).is_some_and(|job| job.state == "downloading") {
+            return Err(format!("Conflict: download '{}' is already running", id));
+        }
+        // A fresh start never continues someone else's leftover partial file
+        let _ = std::fs::remove_file(format!("{}.part", dest_path));
+        table.insert(
+            id.clone(),
+            DownloadJob {
+                url,
+                dest_path,
+                proxy,
+                total_bytes: None,
+                state: "downloading".into(),
+                error: None,
+                pid: None,
+            },
+        );
+    }
+    let table = Arc::clone(&downloads.0);
+    std::thread::spawn(move || run_download(app, table, id));
+    Ok(())
+}
+
+/// Pauses a running download, keeping what has arrived for `resume_download`.
+#[tauri::command]
+fn pause_download(downloads: tauri::State<'_, Downloads>, id: String) -> Result<(), String> {
+    let mut table = downloads.0.lock().map_err(|e| e.to_string())?;
+    let job = table
+        .get_mut(&id)
+        .ok_or_else(|| format!("No download with id '{}'", id))?;
+    if job.state != "downloading" {
+        return Err(format!(
+            "Download '{}' is {}, not downloading",
+            id, job.state
+        ));
+    }
+    job.state = "paused".into();
+    if let Some(pid) = job.pid {
+        kill_process_tree(pid, "TERM")?;
+    }
+    Ok(())
+}
+
+/// Continues a paused or failed download from the bytes already on disk,
+/// or from the start when the server doesn't support ranges.
+#[tauri::command]
+fn resume_download(
+    app: tauri::AppHandle,
+    downloads: tauri::State<'_, Downloads>,
+    id: String,
+) -> Result<(), String> {
+    {
+        let mut table = downloads.0.lock().map_err(|e| e.to_string())?;
+        let job = table
+            .get_mut(&id)
+            .ok_or_else(|| format!("No download with id '{}'", id))?;
+        if !matches!(job.state.as_str(), "paused" | "failed") {
+            return Err(format!("Download '{}' is {}, not paused", id, job.state));
+        }
+        // A paused transfer's curl may still be on its way out
+        if job.pid.is_some() {
+            return Err(format!("Conflict: download '{}' is still stopping", id));
+        }
+        job.state = "downloading".into();
+        job.error = None;
+    }
+    let table = Arc::clone(&downloads.0);
+    std::thread::spawn(move || run_download(app, table, id));
+    Ok(())
+}
+
+/// Every download started with `start_download` and how far it got.
+#[tauri::command]
+fn list_downloads(downloads: tauri::State<'_, Downloads>) -> Result<Vec<DownloadProgress>, String> {
+    let table = downloads.0.lock().map_err(|e| e.to_string())?;
+    let mut listed: Vec<DownloadProgress> = table
+        .iter()
+        .map(|(id, job)| download_progress(id, job))
+        .collect();
+    listed.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(listed)
+}
+
+/// Minimum time between `health-status` events for an unchanged endpoint.
+const HEALTH_HEARTBEAT_SECS: u64 = 60;
+
+/// Running health polls, keyed by caller-chosen id. Dropping a sender stops
+/// the corresponding poll thread.
+#[derive(Default)]
+struct HealthPolls(Mutex<HashMap<String, mpsc::Sender<()>>>);
+
+#[derive(serde::Serialize, Clone)]
+struct HealthStatus {
+    id: String,
+    reachable: bool,
+    status: Option<u16>,
+    latency_ms: Option<u64>,
+}
+
+/// Polls `url` every `interval_secs` in the background and emits
+/// `health-status` events when reachability or status changes, plus a
+/// heartbeat at least once a minute. Reusing an id replaces that poll.
+#[tauri::command]
+fn start_health_poll(
+    app: tauri::AppHandle,
+    polls: tauri::State<'_, HealthPolls>,
+    url: String,
+    interval_secs: u64,
+    id: String,
+) -> Result<(), String> {
+    if interval_secs == 0 {
+        return Err("Poll interval must be at least 1 second".into());
+    }
+    let url = parse_http_url(&url)?.to_string();
+
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    polls
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(id.clone(), stop_tx);
+
+    std::thread::spawn(move || {
+        let interval = Duration::from_secs(interval_secs);
+        let mut last: Option<Reachability> = None;
+        let mut last_emit = Instant::now();
+
+        loop {
+            let result =
+                probe_url(&url, interval_secs.clamp(1, 10), None).unwrap_or(Reachability {
+                    reachable: false,
+                    status: None,
+                    latency_ms: None,
+                });
+
+            let changed = last.as_ref().map_or(true, |prev| {
+                prev.reachable != result.reachable || prev.status != result.status
+            });
+            if changed || last_emit.elapsed() >= Duration::from_secs(HEALTH_HEARTBEAT_SECS) {
+                let _ = app.emit(
+                    "health-status",
+                    HealthStatus {
+                        id: id.clone(),
+                        reachable: result.reachable,
+                        status: result.status,
+                        latency_ms: result.latency_ms,
+                    },
+                );
+                last_emit = Instant::now();
+            }
+            last = Some(result);
+
+            // A message or a dropped sender both mean the poll was stopped
+            if !matches!(
+                stop_rx.recv_timeout(interval),
+                Err(mpsc::RecvTimeoutError::Timeout)
+            ) {
+                break;
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Stops a health poll started with `start_health_poll`.
+#[tauri::command]
+fn stop_health_poll(polls: tauri::State<'_, HealthPolls>, id: String) -> Result<(), String> {
+    polls
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&id)
+        .map(|_| ())
+        .ok_or_else(|| format!("No health poll running with id '{}'", id))
+}
+
+/// Longest wait between SSE reconnection attempts.
+const SSE_MAX_BACKOFF_MS: u64 = 30_000;
+
+/// An active SSE subscription: dropping `stop` ends the reconnect loop and
+/// killing `child` unblocks the reader.
+struct SseHandle {
+    stop: mpsc::Sender<()>,
+    child: Arc<Mutex<Option<std::process::Child>>>,
+}
+
+/// Running SSE subscriptions, keyed by caller-chosen id.
+#[derive(Default)]
+struct SseSubscriptions(Mutex<HashMap<String, SseHandle>>);
+
+impl SseHandle {
+    fn close(self) {
+        drop(self.stop);
+        if let Ok(mut child) = self.child.lock() {
+            if let Some(child) = child.as_mut() {
+                let _ = child.kill();
+            }
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone)]
+struct SseEvent {
+    id: String,
+    event: String,
+    data: String,
+}
+
+/// Accumulates `event:`/`data:` lines until a blank line dispatches them,
+/// per the EventSource framing rules.
+#[derive(Default)]
+struct SseParser {
+    event: String,
+    data: Vec<String>,
+    last_event_id: Option<String>,
+    retry_ms: Option<u64>,
+}
+
+impl SseParser {
+    /// Feeds one line; returns `(event, data)` when an event is complete.
+    fn feed(&mut self, line: &str) -> Option<(String, String)> {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.is_empty() {
+            let event = std::mem::take(&mut self.event);
+            let data = std::mem::take(&mut self.data);
+            if data.is_empty() {
+                return None;
+            }
+            let event = if event.is_empty() {
+                "message".to_string()
+            } else {
+                event
+            };
+            return Some((event, data.join("\n")));
+        }
+        if line.starts_with(':') {
+            return None; // comment / keep-alive
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => self.data.push(value.to_string()),
+            "id" if !value.contains('\0') => self.last_event_id = Some(value.to_string()),
+            "retry" => {
+                if let Ok(ms) = value.parse() {
+                    self.retry_ms = Some(ms);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+/// Opens a Server-Sent Events stream and emits each event to the frontend as
+/// `sse-event` `{ id, event, data }`. Dropped connections are retried with
+/// exponential backoff (or the server's `retry:` hint), resuming from the
+/// last event id. Reusing an id replaces that subscription.
+#[tauri::command]
+fn subscribe_sse(
+    app: tauri::AppHandle,
+    subscriptions: tauri::State<'_, SseSubscriptions>,
+    url: String,
+    id: String,
+) -> Result<(), String> {
+    use std::io::BufRead;
+
+    let url = parse_http_url(&url)?.to_string();
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    let child_slot: Arc<Mutex<Option<std::process::Child>>> = Arc::new(Mutex::new(None));
+    let previous = subscriptions.0.lock().map_err(|e| e.to_string())?.insert(
+        id.clone(),
+        SseHandle {
+            stop: stop_tx,
+            child: child_slot.clone(),
+        },
+    );
+    if let Some(previous) = previous {
+        previous.close();
+    }
+
+    std::thread::spawn(move || {
+        let mut parser = SseParser::default();
+        let mut backoff_ms = 1_000;
+
+        loop {
+            let mut cmd = curl_command();
+            cmd.args(["-sN", "-H", "Accept: text/event-stream"]);
+            if let Some(last_id) = &parser.last_event_id {
+                cmd.args(["-H", &format!("Last-Event-ID: {}", last_id)]);
+            }
+            // Each (re)connect counts against the fetch rate limit
+            let spawned = throttle_fetch().and_then(|()| {
+                cmd.arg(&url)
+                    .stdout(std::process::Stdio::piped())
+                    .stderr(std::process::Stdio::null())
+                    .spawn()
+                    .map_err(|e| e.to_string())
+            });
+
+            match spawned {
+                Ok(mut child) => {
+                    let stdout = child.stdout.take();
+                    if let Ok(mut slot) = child_slot.lock() {
+                        *slot = Some(child);
+                    }
+                    // Unsubscribed while connecting
+                    if matches!(stop_rx.try_recv(), Err(mpsc::TryRecvError::Disconnected)) {
+                        break;
+                    }
+                    if let Some(stdout) = stdout {
+                        for line in std::io::BufReader::new(stdout).lines() {
+                            let Ok(line) = line else { break };
+                            if let Some((event, data)) = parser.feed(&line) {
+                                backoff_ms = 1_000;
+                                let _ = app.emit(
+                                    "sse-event",
+                                    SseEvent {
+                                        id: id.clone(),
+                                        event,
+                                        data,
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    if let Ok(mut slot) = child_slot.lock() {
+                        if let Some(mut child) = slot.take() {
+                            let _ = child.kill();
+                            let _ = child.wait();
+                        }
+                    }
+                }
+                Err(e) => log::warn!("SSE subscription {} couldn't connect: {}", id, e),
+            }
+
+            let wait = parser.retry_ms.unwrap_or(backoff_ms);
+            if !matches!(
+                stop_rx.recv_timeout(Duration::from_millis(wait)),
+                Err(mpsc::RecvTimeoutError::Timeout)
+            ) {
+                break;
+            }
+            log::info!("Reconnecting SSE subscription {} to {}", id, url);
+            backoff_ms = (backoff_ms * 2).min(SSE_MAX_BACKOFF_MS);
+        }
+    });
+
+    Ok(())
+}
+
+/// Closes an SSE subscription opened with `subscribe_sse`.
+#[tauri::command]
+fn unsubscribe_sse(
+    subscriptions: tauri::State<'_, SseSubscriptions>,
+    id: String,
+) -> Result<(), String> {
+    let handle = subscriptions
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&id)
+        .ok_or_else(|| format!("No SSE subscription with id '{}'", id))?;
+    handle.close();
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct SystemInfo {
+    os: String,
+    os_version: String,
+    arch: String,
+    family: String,
+    app_version: String,
+    default_shell: ShellInfo,
+}
+
+/// Describes the OS AUI is running on, for bug reports and diagnostics.
+#[tauri::command]
+fn get_system_info() -> SystemInfo {
+    #[cfg(target_os = "windows")]
+    let version = hidden_command("cmd.exe").args(["/c", "ver"]).output();
+    #[cfg(target_os = "macos")]
+    let version = StdCommand::new("sw_vers").arg("-productVersion").output();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let version = StdCommand::new("uname").args(["-sr"]).output();
+
+    SystemInfo {
+        os: std::env::consts::OS.to_string(),
+        os_version: version
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .unwrap_or_default(),
+        arch: std::env::consts::ARCH.to_string(),
+        family: std::env::consts::FAMILY.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        default_shell: detect_default_shell(),
+    }
+}
+
+/// The registry key Windows reads per-user login programs from, and the
+/// one Task Manager records them being disabled in.
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(target_os = "windows")]
+const STARTUP_APPROVED_KEY: &str =
+    r"HKCU\Software\Microsoft\Windows\CurrentVersion\Explorer\StartupApproved\Run";
+
+/// The executable to launch at login. An AppImage runs from a temporary
+/// mount, so the image itself is what has to be started.
+fn autostart_executable() -> Result<String, String> {
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    if let Some(image) = std::env::var("APPIMAGE").ok().filter(|p| !p.is_empty()) {
+        return Ok(image);
+    }
+    std::env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| format!("Failed to locate the AUI executable: {}", e))
+}
+
+/// Where the login entry lives: a LaunchAgent plist on macOS, an XDG
+/// autostart `.desktop` file elsewhere on Unix.
+#[cfg(not(target_os = "windows"))]
+fn autostart_file(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+
+    let home = std::env::var("HOME")
+        .ok()
+        .filter(|h| !h.is_empty())
+        .ok_or_else(|| "HOME is not set".to_string())?;
+    let identifier = &app.config().identifier;
+    #[cfg(target_os = "macos")]
+    return Ok(std::path::Path::new(&home)
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", identifier)));
+    #[cfg(not(target_os = "macos"))]
+    {
+        let config = std::env::var("XDG_CONFIG_HOME")
+            .ok()
+            .filter(|c| !c.is_empty())
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|| std::path::Path::new(&home).join(".config"));
+        Ok(config
+            .join("autostart")
+            .join(format!("{}.desktop", identifier)))
+    }
+}
+
+/// Whether AUI starts at login, read from the OS each time so an entry
+/// added, removed or disabled elsewhere (Task Manager's Startup tab, the
+/// desktop's startup settings) is reported as it is.
+#[tauri::command]
+fn get_autostart(app: tauri::AppHandle) -> Result<bool, String> {
+    #[cfg(target_os = "windows")]
+    use tauri::Manager;
+
+    #[cfg(target_os = "windows")]
+    {
+        let name = &app.package_info().name;
+        let query = |key: &str| {
+            hidden_command("reg.exe")
+                .args(["query", key, "/v", name])
+                .output()
+                .map_err(|e| format!("Failed to run reg: {}", e))
+        };
+        if !query(RUN_KEY)?.status.success() {
+            return Ok(false);
+        }
+        // A disabled entry's REG_BINARY value starts with an odd byte (03)
+        let approved = query(STARTUP_APPROVED_KEY)?;
+        let disabled = approved.status.success()
+            && String::from_utf8_lossy(&approved.stdout)
+                .lines()
+                .find_map(|line| line.split_once("REG_BINARY"))
+                .and_then(|(_, data)| u8::from_str_radix(data.trim().get(..2)?, 16).ok())
+                .is_some_and(|flag| flag % 2 == 1);
+        Ok(!disabled)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let Ok(plist) = std::fs::read_to_string(autostart_file(&app)?) else {
+            return Ok(false);
+        };
+        let compact: String = plist.split_whitespace().collect();
+        Ok(!compact.contains("<key>Disabled</key><true/>"))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let Ok(entry) = std::fs::read_to_string(autostart_file(&app)?) else {
+            return Ok(false);
+        };
+        let disabled = entry.lines().any(|line| {
+            let line = line.replace(' ', "");
+            line.eq_ignore_ascii_case("Hidden=true")
+                || line.eq_ignore_ascii_case("X-GNOME-Autostart-enabled=false")
+        });
+        Ok(!disabled)
+    }
+}
+
+/// Makes AUI start (or stop starting) at login: a value under the
+/// registry Run key on Windows, a LaunchAgent on macOS, an XDG autostart
+/// entry on Linux. Returns the state afterwards, as `get_autostart` reads it.
+#[tauri::command]
+fn set_autostart(app: tauri::AppHandle, enabled: bool) -> Result<bool, String> {
+    use tauri::Manager;
+
+    #[cfg(target_os = "windows")]
+    {
+        let name = app.package_info().name.clone();
+        let reg = |args: &[&str]| {
+            hidden_command("reg.exe")
+                .args(args)
+                .output()
+                .map_err(|e| format!("Failed to run reg: {}", e))
+        };
+        // Also clear any Task Manager "disabled" flag, which outranks the Run key
+        let _ = reg(&["delete", STARTUP_APPROVED_KEY, "/v", &name, "/f"]);
+        if enabled {
+            let command = format!("\"{}\"", autostart_executable()?);
+            let output = reg(&[
+                "add", RUN_KEY, "/v", &name, "/t", "REG_SZ", "/d", &command, "/f",
+            ])?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!("Failed to enable autostart: {}", stderr.trim()));
+            }
+        } else {
+            let _ = reg(&["delete", RUN_KEY, "/v", &name, "/f"]);
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let path = autostart_file(&app)?;
+        if enabled {
+            let executable = autostart_executable()?;
+            #[cfg(target_os = "macos")]
+            let contents = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \
+                 \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+                 <plist version=\"1.0\">\n<dict>\n  <key>Label</key>\n  <string>{}</string>\n  \
+                 <key>ProgramArguments</key>\n  <array>\n    <string>{}</string>\n  </array>\n  \
+                 <key>RunAtLoad</key>\n  <true/>\n</dict>\n</plist>\n",
+                xml_escape(&app.config().identifier),
+                xml_escape(&executable)
+            );
+            // Exec is quoted per the Desktop Entry spec, whose string
+            // escaping then doubles every backslash again
+            #[cfg(not(target_os = "macos"))]
+            let contents = format!(
+                "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\"\n\
+                 X-GNOME-Autostart-enabled=true\n",
+                app.package_info().name,
+                executable
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('`', "\\`")
+                    .replace('$', "\\$")
+                    .replace('\\', "\\\\")
+                    .replace('%', "%%")
+            );
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            std::fs::write(&path, contents)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        } else if let Err(e) = std::fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return Err(format!("Failed to remove {}: {}", path.display(), e));
+            }
+        }
+    }
+
+    get_autostart(app)
+}
+
+#[derive(serde::Serialize)]
+struct ScreenCapture {
+    path: String,
+    width: u32,
+    height: u32,
+}
+
+/// Width and height from a PNG file's IHDR header.
+fn png_dimensions(path: &str) -> Result<(u32, u32), String> {
+    use std::io::Read;
+
+    let mut header = [0u8; 24];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    if &header[..8] != b"\x89PNG\r\n\x1a\n" || &header[12..16] != b"IHDR" {
+        return Err(format!("{} is not a PNG image", path));
+    }
+    let be = |b: &[u8]| u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+    Ok((be(&header[16..20]), be(&header[20..24])))
+}
+
+/// Saves a PNG screenshot to `dest_path`, e.g. for a deployment script to
+/// confirm a GUI came up. Captures the primary display on Windows and macOS
+/// (`screencapture`); on Linux the whole desktop through the first of
+/// `grim` (Wayland), `import` (ImageMagick), `scrot` or `gnome-screenshot`
+/// found. Fails clearly when there is no display to capture.
+#[tauri::command]
+fn capture_screen(dest_path: String) -> Result<ScreenCapture, String> {
+    let dest = expand_user_path(&dest_path);
+    if let Some(parent) = std::path::Path::new(&dest)
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = hidden_command(&resolve_powershell()?);
+        command.args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "Add-Type -AssemblyName System.Windows.Forms, System.Drawing; \
+                 $screen = [System.Windows.Forms.Screen]::PrimaryScreen.Bounds; \
+                 $bitmap = New-Object System.Drawing.Bitmap $screen.Width, $screen.Height; \
+                 $graphics = [System.Drawing.Graphics]::FromImage($bitmap); \
+                 $graphics.CopyFromScreen($screen.Location, [System.Drawing.Point]::Empty, $screen.Size); \
+                 $bitmap.Save('{}', [System.Drawing.Imaging.ImageFormat]::Png)",
+                dest.replace('\'', "''")
+            ),
+        ]);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = StdCommand::new("screencapture");
+        command.args(["-x", "-m", "-t", "png", &dest]);
+        command
+    };
+    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+    let mut command = {
+        let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+        if !wayland && std::env::var_os("DISPLAY").is_none() {
+            return Err("No display to capture: neither DISPLAY nor WAYLAND_DISPLAY is set".into());
+        }
+        let tools: [(&str, &[&str]); 4] = [
+            ("grim", &[]),
+            ("import", &["-window", "root"]),
+            ("scrot", &["-o"]),
+            ("gnome-screenshot", &["-f"]),
+        ];
+        let (tool, args) = tools
+            .iter()
+            .filter(|(tool, _)| wayland || *tool != "grim")
+            .find(|(tool, _)| find_in_path(tool).is_some())
+            .ok_or(
+                "DependencyMissing: no screenshot tool found. \
+                 Install grim (Wayland), ImageMagick, scrot or gnome-screenshot.",
+            )?;
+        let mut command = StdCommand::new(tool);
+        command.args(*args).arg(&dest);
+        command
+    };
 
-        let output = StdCommand::new("schtasks.exe")
-            .args(&args)
-            .creation_flags(0x08000000) // CREATE_NO_WINDOW
-            .output()
-            .map_err(|e| format!("Failed to run schtasks: {}", e))?;
+    let output = command
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to capture screen: {}", e))?;
+    if !output.status.success() || !std::path::Path::new(&dest).is_file() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Screen capture failed: {}", stderr.trim()));
+    }
+    let (width, height) = png_dimensions(&dest)?;
+    Ok(ScreenCapture {
+        path: dest,
+        width,
+        height,
+    })
+}
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("schtasks failed: {}", stderr));
-        }
+/// An external program AUI shells out to, and whether it is on `PATH`.
+#[derive(serde::Serialize)]
+struct Dependency {
+    name: String,
+    found: bool,
+    path: Option<String>,
+    needed_for: String,
+}
 
-        Ok(format!("Created scheduled task: {}", tn))
+/// Checks for the external programs AUI relies on on this platform.
+#[tauri::command]
+fn check_dependencies() -> Vec<Dependency> {
+    let mut programs = vec![("curl", "fetching URLs and health checks")];
+    if cfg!(target_os = "windows") {
+        programs.push(("schtasks.exe", "scheduling tasks"));
+        programs.push(("powershell.exe", "running PowerShell scripts"));
+    } else {
+        programs.push(("crontab", "scheduling tasks"));
+        programs.push(("at", "one-shot tasks"));
+        programs.push(("bash", "running shell scripts"));
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        // macOS/Linux: append a crontab entry
-        // Convert our repeat + time into a cron expression
-        let cron_line = match repeat.as_str() {
-            "hourly" => format!("0 * * * *"),
-            "daily" => {
-                let parts: Vec<&str> = start_time.split(':').collect();
-                let hour = parts.get(0).unwrap_or(&"9");
-                let min = parts.get(1).unwrap_or(&"0");
-                format!("{} {} * * *", min, hour)
-            }
-            "weekly" => {
-                let parts: Vec<&str> = start_time.split(':').collect();
-                let hour = parts.get(0).unwrap_or(&"9");
-                let min = parts.get(1).unwrap_or(&"0");
-                format!("{} {} * * 1", min, hour)
-            }
-            "monthly" => {
-                let parts: Vec<&str> = start_time.split(':').collect();
-                let hour = parts.get(0).unwrap_or(&"9");
-                let min = parts.get(1).unwrap_or(&"0");
-                format!("{} {} 1 * *", min, hour)
-            }
-            _ => {
-                // ONCE: use `at` or a one-shot cron
-                let parts: Vec<&str> = start_time.split(':').collect();
-                let hour = parts.get(0).unwrap_or(&"9");
-                let min = parts.get(1).unwrap_or(&"0");
-                format!("{} {} * * *", min, hour)
+    programs
+        .into_iter()
+        .map(|(name, needed_for)| {
+            let path = find_in_path(name);
+            Dependency {
+                name: name.to_string(),
+                found: path.is_some(),
+                path: path.map(|p| p.to_string_lossy().to_string()),
+                needed_for: needed_for.to_string(),
             }
-        };
-
-        let entry = format!(
-            "{} /bin/bash '{}' # AUI:{}\n",
-            cron_line, script_path, task_name
-        );
+        })
+        .collect()
+}
 
-        // Read existing crontab, append new entry
-        let existing = StdCommand::new("crontab")
-            .arg("-l")
-            .output()
-            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
-            .unwrap_or_default();
+/// Whether the service that fires scheduled tasks is up.
+#[derive(serde::Serialize)]
+struct SchedulerHealth {
+    running: bool,
+    service_name: String,
+    detail: String,
+}
 
-        let new_crontab = format!("{}{}", existing, entry);
+/// Whether a process with exactly this name is running.
+#[cfg(not(target_os = "windows"))]
+fn process_running(name: &str) -> bool {
+    StdCommand::new("pgrep")
+        .args(["-x", name])
+        .stdout(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
 
-        let mut child = StdCommand::new("crontab")
-            .arg("-")
-            .stdin(std::process::Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to set crontab: {}", e))?;
+/// Checks that the platform scheduler will actually fire tasks: the
+/// Schedule service on Windows, the cron daemon on Linux (its systemd unit,
+/// or the process on systems without systemd). On macOS cron is started on
+/// demand by launchd, which is always running.
+#[tauri::command]
+fn scheduler_health() -> SchedulerHealth {
+    #[cfg(target_os = "windows")]
+    {
+        let output = hidden_command("sc.exe")
+            .args(["query", "Schedule"])
+            .output();
+        let (running, detail) = match output {
+            Ok(o) => {
+                let stdout = String::from_utf8_lossy(&o.stdout);
+                let state = stdout
+                    .lines()
+                    .find(|l| l.trim_start().starts_with("STATE"))
+                    .and_then(|l| l.split_whitespace().last())
+                    .unwrap_or("UNKNOWN")
+                    .to_string();
+                (
+                    state == "RUNNING",
+                    format!("Task Scheduler service is {}", state.to_lowercase()),
+                )
+            }
+            Err(e) => (
+                false,
+                format!("Failed to query the Schedule service: {}", e),
+            ),
+        };
+        SchedulerHealth {
+            running,
+            service_name: "Schedule".into(),
+            detail,
+        }
+    }
 
-        use std::io::Write;
-        if let Some(ref mut stdin) = child.stdin {
-            stdin
-                .write_all(new_crontab.as_bytes())
-                .map_err(|e| format!("Failed to write crontab: {}", e))?;
+    #[cfg(target_os = "macos")]
+    {
+        let detail = if process_running("cron") {
+            "cron is running under launchd"
+        } else {
+            "cron is started by launchd when a crontab entry is due"
+        };
+        SchedulerHealth {
+            running: true,
+            service_name: "com.vix.cron".into(),
+            detail: detail.into(),
         }
+    }
 
-        child
-            .wait()
-            .map_err(|e| format!("crontab process error: {}", e))?;
+    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+    {
+        // Debian names the unit cron, Fedora/Arch crond, some cronie
+        for unit in ["cron", "crond", "cronie"] {
+            let Ok(output) = StdCommand::new("systemctl")
+                .args(["show", "-p", "LoadState", "-p", "ActiveState", unit])
+                .output()
+            else {
+                break;
+            };
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let property = |key: &str| {
+                stdout
+                    .lines()
+                    .find_map(|l| l.strip_prefix(key)?.strip_prefix('='))
+                    .unwrap_or("")
+                    .to_string()
+            };
+            if property("LoadState") != "loaded" {
+                continue;
+            }
+            let state = property("ActiveState");
+            return SchedulerHealth {
+                running: state == "active",
+                service_name: format!("{}.service", unit),
+                detail: format!("systemd unit {}.service is {}", unit, state),
+            };
+        }
 
-        Ok(format!("Created cron job: AUI:{}", task_name))
+        // No systemd (containers, WSL without it): look for the daemon itself
+        match ["cron", "crond"]
+            .into_iter()
+            .find(|name| process_running(name))
+        {
+            Some(name) => SchedulerHealth {
+                running: true,
+                service_name: name.into(),
+                detail: format!("{} process is running", name),
+            },
+            None => SchedulerHealth {
+                running: false,
+                service_name: "cron".into(),
+                detail: "No cron daemon is running, so scheduled tasks will not fire".into(),
+            },
+        }
     }
 }
 
-/// Lists all AUI scheduled tasks.
-/// On Windows, queries schtasks under the AUI\ folder.
-/// On macOS/Linux, searches crontab for AUI: markers.
-#[tauri::command]
-fn list_scheduled_tasks() -> Result<String, String> {
+/// A mechanism that can run tasks on this machine. `can_create` is false
+/// for those AUI lists tasks from but doesn't create them with.
+#[derive(serde::Serialize)]
+struct BackendOption {
+    name: String,
+    available: bool,
+    can_create: bool,
+    detail: String,
+}
+
+/// The scheduler `create_scheduled_task` will use here, see
+/// `get_scheduling_backend`.
+#[derive(serde::Serialize)]
+struct SchedulingBackend {
+    backend: String,
+    reason: String,
+    one_shot_backend: String,
+    backends: Vec<BackendOption>,
+}
+
+/// The scheduling mechanisms present on this machine.
+fn scheduling_backends() -> Vec<BackendOption> {
+    let option = |name: &str, available: bool, can_create: bool, detail: String| BackendOption {
+        name: name.into(),
+        available,
+        can_create,
+        detail,
+    };
+
     #[cfg(target_os = "windows")]
     {
-        let output = StdCommand::new("schtasks.exe")
-            .args(&["/Query", "/FO", "CSV", "/NH", "/TN", "AUI\\*"])
-            .creation_flags(0x08000000)
-            .output()
-            .map_err(|e| format!("Failed to query schtasks: {}", e))?;
-
-        // schtasks returns non-zero if no tasks found — that's OK
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        Ok(stdout)
+        let health = scheduler_health();
+        vec![option("schtasks", health.running, true, health.detail)]
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        let output = StdCommand::new("crontab")
-            .arg("-l")
-            .output()
-            .map_err(|e| format!("Failed to read crontab: {}", e))?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        // Filter to only AUI entries
-        let aui_entries: Vec<&str> = stdout
-            .lines()
-            .filter(|line| line.contains("# AUI:"))
-            .collect();
-        Ok(aui_entries.join("\n"))
+        let health = scheduler_health();
+        let mut backends = vec![if find_in_path("crontab").is_some() {
+            option("cron", health.running, true, health.detail)
+        } else {
+            option("cron", false, true, "crontab isn't installed".into())
+        }];
+        if cfg!(target_os = "macos") {
+            backends.push(option(
+                "at",
+                false,
+                false,
+                "atrun is disabled on macOS, so at jobs never run".into(),
+            ));
+            backends.push(option(
+                "launchd",
+                true,
+                false,
+                "runs cron; AUI doesn't create launchd agents itself".into(),
+            ));
+        } else {
+            backends.push(option(
+                "at",
+                at_available(),
+                at_available(),
+                if at_available() {
+                    "one-shot tasks only".into()
+                } else {
+                    "at isn't installed".into()
+                },
+            ));
+            let user_systemd = StdCommand::new("systemctl")
+                .args(["--user", "show-environment"])
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .is_ok_and(|s| s.success());
+            backends.push(option(
+                "systemd",
+                user_systemd,
+                false,
+                if user_systemd {
+                    "aui-* user timers are listed, not created".into()
+                } else {
+                    "no systemd user manager".into()
+                },
+            ));
+        }
+        backends
     }
 }
 
-/// Deletes a scheduled task by name.
-/// On Windows, removes from Task Scheduler. On macOS/Linux, removes from crontab.
+/// The scheduler AUI will create tasks with on this machine and why, plus
+/// every mechanism found, so the platform-specific choice isn't a surprise.
+/// Recurring tasks go to Task Scheduler on Windows and the crontab
+/// elsewhere; `one_shot_backend` is where run-once tasks go, `at` on Linux
+/// when it's installed. `create_scheduled_task` takes a `backend` to
+/// override this.
 #[tauri::command]
-fn delete_scheduled_task(task_name: String) -> Result<String, String> {
-    #[cfg(target_os = "windows")]
-    {
-        let tn = format!("AUI\\{}", task_name);
-        let output = StdCommand::new("schtasks.exe")
-            .args(&["/Delete", "/TN", &tn, "/F"])
-            .creation_flags(0x08000000)
-            .output()
-            .map_err(|e| format!("Failed to run schtasks: {}", e))?;
+fn get_scheduling_backend() -> SchedulingBackend {
+    let backends = scheduling_backends();
+    let primary = &backends[0];
+    let mut reason = if cfg!(target_os = "windows") {
+        "Windows Task Scheduler".to_string()
+    } else {
+        "the user's crontab".to_string()
+    };
+    if !primary.available {
+        reason.push_str(&format!(
+            " (currently unusable: {})",
+            primary.detail.trim_end_matches('.')
+        ));
+    }
+    let one_shot_backend = if backends.iter().any(|b| b.name == "at" && b.can_create) {
+        "at"
+    } else {
+        primary.name.as_str()
+    }
+    .to_string();
+    SchedulingBackend {
+        backend: primary.name.clone(),
+        reason,
+        one_shot_backend,
+        backends,
+    }
+}
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("schtasks delete failed: {}", stderr));
+/// Checks a `backend` override for `create_scheduled_task`: "schtasks" on
+/// Windows, "cron" elsewhere (also for one-shot tasks, which would
+/// otherwise go to `at`), or "at" for a one-shot task on Linux. Returns
+/// the normalized name, `None` for AUI's default choice.
+fn validate_backend(backend: Option<&str>, one_shot: bool) -> Result<Option<String>, String> {
+    let Some(backend) = backend
+        .map(|b| b.trim().to_ascii_lowercase())
+        .filter(|b| !b.is_empty())
+    else {
+        return Ok(None);
+    };
+    let native = if cfg!(target_os = "windows") {
+        "schtasks"
+    } else {
+        "cron"
+    };
+    match backend.as_str() {
+        name if name == native => Ok(Some(backend)),
+        "at" if !cfg!(target_os = "linux") => {
+            Err("Unsupported: at jobs are only used on Linux".into())
         }
-
-        Ok(format!("Deleted scheduled task: {}", tn))
+        "at" if !one_shot => Err("The at backend only runs one-shot tasks".into()),
+        #[cfg(not(target_os = "windows"))]
+        "at" if !at_available() => {
+            Err("DependencyMissing: at isn't installed; install it or use the cron backend".into())
+        }
+        "at" => Ok(Some(backend)),
+        "schtasks" | "cron" | "systemd" | "launchd" => Err(format!(
+            "Unsupported: AUI can't create {} tasks on this platform",
+            backend
+        )),
+        _ => Err(format!("Unknown scheduling backend '{}'", backend)),
     }
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let marker = format!("# AUI:{}", task_name);
+/// Which cron implementation will read the crontab, and what in an
+/// expression it may not understand.
+#[derive(serde::Serialize)]
+struct CronCompatibility {
+    cron_expr: String,
+    variant: String,
+    version: Option<String>,
+    compatible: bool,
+    warnings: Vec<String>,
+}
 
-        let existing = StdCommand::new("crontab")
-            .arg("-l")
-            .output()
-            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
-            .unwrap_or_default();
+/// Identifies the installed cron daemon from its binary: BusyBox (Alpine,
+/// embedded), cronie (Fedora/Arch), fcron, or Vixie-derived cron (Debian,
+/// Ubuntu, macOS). Returns the variant name and version when one is known.
+#[cfg(not(target_os = "windows"))]
+fn detect_cron_variant() -> (String, Option<String>) {
+    if cfg!(target_os = "macos") {
+        return ("vixie".into(), None);
+    }
 
-        let filtered: Vec<&str> = existing
+    // sbin directories are often missing from a desktop user's PATH
+    let locate = |name: &str| {
+        find_in_path(name).or_else(|| {
+            ["/usr/sbin", "/sbin", "/usr/bin"]
+                .iter()
+                .map(|dir| std::path::Path::new(dir).join(name))
+                .find(|p| p.is_file())
+        })
+    };
+    let first_line = |program: &std::path::Path, args: &[&str]| {
+        let output = StdCommand::new(program).args(args).output().ok()?;
+        let text = if output.stdout.is_empty() {
+            output.stderr
+        } else {
+            output.stdout
+        };
+        String::from_utf8_lossy(&text)
             .lines()
-            .filter(|line| !line.contains(&marker))
-            .collect();
-        let new_crontab = format!("{}\n", filtered.join("\n"));
-
-        let mut child = StdCommand::new("crontab")
-            .arg("-")
-            .stdin(std::process::Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to set crontab: {}", e))?;
+            .next()
+            .map(|l| l.trim().to_string())
+    };
 
-        use std::io::Write;
-        if let Some(ref mut stdin) = child.stdin {
-            stdin
-                .write_all(new_crontab.as_bytes())
-                .map_err(|e| format!("Failed to write crontab: {}", e))?;
+    for name in ["crond", "cron"] {
+        let Some(path) = locate(name) else {
+            continue;
+        };
+        let target = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if target
+            .file_name()
+            .is_some_and(|f| f.to_string_lossy().starts_with("busybox"))
+        {
+            // "BusyBox v1.36.1 (2023-07-27 10:12:11 UTC) multi-call binary."
+            let version = first_line(&target, &[]).and_then(|l| {
+                l.strip_prefix("BusyBox v")?
+                    .split_whitespace()
+                    .next()
+                    .map(str::to_string)
+            });
+            return ("busybox".into(), version);
         }
+        if name == "crond" {
+            if let Some(line) = first_line(&path, &["-V"]) {
+                if let Some(version) = line.strip_prefix("cronie ") {
+                    return ("cronie".into(), Some(version.trim().to_string()));
+                }
+            }
+        } else {
+            return ("vixie".into(), None);
+        }
+    }
 
-        child
-            .wait()
-            .map_err(|e| format!("crontab process error: {}", e))?;
-
-        Ok(format!("Deleted cron job: AUI:{}", task_name))
+    if locate("fcron").is_some() {
+        return ("fcron".into(), None);
     }
+    ("unknown".into(), None)
 }
 
-/// Opens a visible terminal window running the given script.
-/// On Windows, uses CREATE_NEW_CONSOLE to bypass Tauri's CREATE_NO_WINDOW flag.
+/// Checks a cron expression against the quirks of the installed cron
+/// daemon. Problems come back as warnings rather than errors, since the
+/// entry may still install fine and only fail to fire. On Windows the
+/// expression is checked against what can be translated for schtasks.
 #[tauri::command]
-fn open_terminal(script_path: String) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        // Use `cmd /c start` to launch PowerShell as a fully independent process.
-        // Direct spawning via CREATE_NEW_CONSOLE gets killed in the Tauri context.
-        // `start` uses ShellExecuteEx internally which fully detaches the process.
-        // We use raw_arg to control the exact command line (no Rust auto-quoting).
-        let raw = format!(
-            "/c start \"Deploy\" powershell.exe -NoExit -ExecutionPolicy Bypass -File \"{}\"",
-            script_path
-        );
-        StdCommand::new("cmd.exe")
-            .raw_arg(raw)
-            .creation_flags(0x08000000) // CREATE_NO_WINDOW for cmd.exe itself
-            .spawn()
-            .map_err(|e| format!("Failed to open terminal: {}", e))?;
-    }
+fn check_cron_compatibility(cron_expr: String) -> Result<CronCompatibility, String> {
+    let cron = parse_cron(&cron_expr)?;
+    let mut warnings = Vec::new();
 
-    #[cfg(target_os = "macos")]
-    {
-        let apple_script = format!(
-            r#"tell application "Terminal"
-            activate
-            do script "bash '{}'"
-        end tell"#,
-            script_path.replace("'", "'\\''")
-        );
-        StdCommand::new("osascript")
-            .args(&["-e", &apple_script])
-            .spawn()
-            .map_err(|e| format!("Failed to open terminal: {}", e))?;
-    }
+    #[cfg(target_os = "windows")]
+    let (variant, version) = {
+        if let Err(e) = cron_to_schtasks(&cron) {
+            warnings.push(e);
+        }
+        ("schtasks".to_string(), None)
+    };
 
-    #[cfg(target_os = "linux")]
-    {
-        // Try common terminal emulators in order
-        let terminals = [
-            ("x-terminal-emulator", vec!["-e", &script_path]),
-            ("gnome-terminal", vec!["--", &script_path]),
-            ("xterm", vec!["-e", &script_path]),
+    #[cfg(not(target_os = "windows"))]
+    let (variant, version) = {
+        let (variant, version) = detect_cron_variant();
+        let raw = cron_expr.trim();
+        let fields = [
+            (&cron.minute, 59),
+            (&cron.hour, 23),
+            (&cron.day_of_month, 31),
+            (&cron.month, 12),
+            (&cron.day_of_week, 7),
         ];
-        let mut launched = false;
-        for (term, args) in &terminals {
-            if StdCommand::new(term).args(args).spawn().is_ok() {
-                launched = true;
-                break;
+
+        // `5/15` (a start with a step but no range) is a Quartz-ism
+        for (field, max) in fields {
+            for part in field.raw.split(',') {
+                if let Some((start, step)) = part.split_once('/') {
+                    if start != "*" && !start.contains('-') {
+                        warnings.push(format!(
+                            "'{}' has a step without a range, which standard cron rejects; write it as '{}-{}/{}'",
+                            part, start, max, step
+                        ));
+                    }
+                }
             }
         }
-        if !launched {
-            return Err("No terminal emulator found".into());
+
+        match variant.as_str() {
+            "busybox" => {
+                if raw.starts_with('@') {
+                    warnings.push(format!(
+                        "BusyBox crond may not understand '{}'; use '{}' instead",
+                        raw, cron
+                    ));
+                }
+                for (field, _) in fields.iter().filter(|(f, _)| f.raw.contains('/')) {
+                    let list: Vec<String> = field.values.iter().map(|v| v.to_string()).collect();
+                    warnings.push(format!(
+                        "Older BusyBox crond releases handle step values differently; '{}' can be written as '{}'",
+                        field.raw,
+                        list.join(",")
+                    ));
+                }
+            }
+            "vixie" => {
+                for field in [&cron.month, &cron.day_of_week] {
+                    let named = field.raw.chars().any(|c| c.is_ascii_alphabetic());
+                    if named && field.raw.contains([',', '-', '/']) {
+                        warnings.push(format!(
+                            "Vixie cron does not accept names in ranges or lists ('{}'); use numbers",
+                            field.raw
+                        ));
+                    }
+                }
+            }
+            "fcron" => warnings.push(
+                "fcron reads fcrontab files, so entries installed with crontab may not run".into(),
+            ),
+            "unknown" => warnings.push(
+                "No cron daemon was found, so this schedule's compatibility could not be checked"
+                    .into(),
+            ),
+            _ => {}
         }
-    }
+        (variant, version)
+    };
 
-    Ok(())
+    Ok(CronCompatibility {
+        cron_expr: cron.to_string(),
+        variant,
+        version,
+        compatible: warnings.is_empty(),
+        warnings,
+    })
 }
 
-/// Fetches a URL and returns its body as a string.
-/// Bypasses webview CORS/CSP restrictions by running in Rust.
-#[tauri::command]
-fn fetch_url(url: String) -> Result<String, String> {
-    let output = StdCommand::new("curl")
-        .args(&["-sL", "--max-time", "15", &url])
-        .output()
-        .map_err(|e| format!("Failed to run curl: {}", e))?;
+/// Key names whose values are masked in support bundles.
+const SECRET_KEYS: [&str; 8] = [
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "api-key",
+    "authorization",
+];
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("HTTP request failed: {}", stderr));
+/// Masks the values of `key=value` / `key: value` pairs with secret-looking
+/// keys, Authorization headers and `Bearer` tokens.
+fn redact_secrets(text: &str) -> String {
+    let is_value_char = |c: char| !c.is_whitespace() && !matches!(c, '"' | '\'' | ',' | '&' | ';');
+
+    text.lines()
+        .map(|line| {
+            // ASCII-only, so byte offsets in `lower` are offsets in `line`
+            let lower = line.to_ascii_lowercase();
+            let mut out = String::new();
+            let mut pos = 0;
+            while pos < line.len() {
+                let rest = &lower[pos..];
+                let key_end = SECRET_KEYS
+                    .iter()
+                    .filter(|k| rest.starts_with(*k))
+                    .map(|k| pos + k.len())
+                    .chain(rest.starts_with("bearer ").then_some(pos + 6))
+                    .max();
+                let Some(key_end) = key_end else {
+                    let c = line[pos..].chars().next().unwrap();
+                    out.push(c);
+                    pos += c.len_utf8();
+                    continue;
+                };
+
+                // Skip the rest of the key name, quotes and the separator
+                let mut value_start = key_end;
+                let bytes = line.as_bytes();
+                while value_start < line.len()
+                    && (bytes[value_start].is_ascii_alphanumeric() || bytes[value_start] == b'_')
+                {
+                    value_start += 1;
+                }
+                let mut separated = lower[pos..].starts_with("bearer");
+                while value_start < line.len()
+                    && matches!(bytes[value_start], b'"' | b'\'' | b' ' | b'=' | b':')
+                {
+                    separated |= matches!(bytes[value_start], b'=' | b':');
+                    value_start += 1;
+                }
+                // An Authorization value ("Basic ...", "Bearer ...") runs to
+                // the closing quote or the end of the line
+                let value_end = if lower[pos..].starts_with("authorization") {
+                    line[value_start..].find(['"', '\''])
+                } else {
+                    line[value_start..].find(|c: char| !is_value_char(c))
+                }
+                .map_or(line.len(), |i| value_start + i);
+
+                out.push_str(&line[pos..value_start]);
+                if separated && value_end > value_start {
+                    out.push_str("[REDACTED]");
+                    pos = value_end;
+                } else {
+                    pos = value_start;
+                }
+            }
+            out
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Zips the app logs, system info, dependency check and scheduled task list
+/// into `dest_zip`, with secrets redacted, and returns the written path.
+#[tauri::command(async)]
+fn create_support_bundle(app: tauri::AppHandle, dest_zip: String) -> Result<String, String> {
+    use std::io::Write;
+    use tauri::Manager;
+
+    let dest_zip = expand_user_path(&dest_zip);
+    let file = std::fs::File::create(&dest_zip)
+        .map_err(|e| format!("Failed to create {}: {}", dest_zip, e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut add = |name: &str, contents: &str| -> Result<(), String> {
+        zip.start_file(name, options)
+            .and_then(|_| Ok(zip.write_all(redact_secrets(contents).as_bytes())?))
+            .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))
+    };
+
+    add(
+        "system_info.json",
+        &serde_json::to_string_pretty(&get_system_info()).map_err(|e| e.to_string())?,
+    )?;
+    add(
+        "dependencies.json",
+        &serde_json::to_string_pretty(&check_dependencies()).map_err(|e| e.to_string())?,
+    )?;
+    add(
+        "scheduled_tasks.json",
+        &list_scheduled_tasks()
+            .and_then(|tasks| serde_json::to_string_pretty(&tasks).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| format!("Failed to list tasks: {}", e)),
+    )?;
+
+    if let Ok(log_dir) = app.path().app_log_dir() {
+        let mut pending = vec![log_dir.clone()];
+        while let Some(dir) = pending.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else if let Ok(bytes) = std::fs::read(&path) {
+                    let relative = path.strip_prefix(&log_dir).unwrap_or(&path);
+                    let name = format!("logs/{}", relative.to_string_lossy().replace('\\', "/"));
+                    add(&name, &String::from_utf8_lossy(&bytes))?;
+                }
+            }
+        }
     }
 
-    String::from_utf8(output.stdout)
-        .map_err(|e| format!("Invalid UTF-8 in response: {}", e))
+    zip.finish()
+        .map_err(|e| format!("Failed to finish {}: {}", dest_zip, e))?;
+    Ok(dest_zip)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -307,15 +13537,142 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
+        .manage(HealthPolls::default())
+        .manage(Downloads::default())
+        .manage(Terminals::default())
+        .manage(SseSubscriptions::default())
+        .manage(Runs::default())
+        .manage(TaskWatcher::default())
+        .manage(FetchCache::default())
         .invoke_handler(tauri::generate_handler![
             open_terminal,
+            list_terminals,
+            list_available_terminals,
+            edit_file,
+            close_terminal,
+            run_script,
+            run_sandboxed,
+            save_env_profile,
+            send_email,
+            save_smtp_config,
+            load_env_profile,
+            list_env_profiles,
+            delete_env_profile,
+            check_script_signature,
+            analyze_script_privileges,
+            run_inline,
+            list_temp_files,
+            clear_temp_files,
+            run_and_schedule,
+            deploy_with_verification,
+            run_and_report,
+            run_script_json,
+            run_in_wsl,
+            deploy_to_hosts,
+            run_script_streaming,
+            list_runs,
+            kill_process,
+            suspend_process,
+            resume_process,
+            stop_process,
+            list_orphaned_processes,
+            list_process_tree,
+            get_default_shell,
+            detect_interpreter,
+            set_debug_show_windows,
+            expand_path,
+            get_permissions,
+            set_permissions,
+            update_script,
+            ensure_scripts_dir,
+            validate_url,
             fetch_url,
+            clear_fetch_cache,
+            set_fetch_rate_limit,
+            fetch_urls,
+            fetch_json,
+            diagnose_endpoint,
+            check_tcp_port,
+            check_for_update,
             create_scheduled_task,
+            create_one_shot_task,
+            schedule_once_in,
+            create_scheduled_task_cron,
+            create_scheduled_task_multi,
+            describe_schedule,
+            schedule_node,
+            diff_graphs,
+            reconcile_schedules,
             list_scheduled_tasks,
+            list_all_scheduled_tasks,
+            refresh_scheduled_tasks,
+            start_task_watcher,
+            stop_task_watcher,
+            get_schedule_timezone,
+            get_task_metadata,
+            is_task_running,
+            export_crontab_fragment,
+            validate_windows_task_name,
+            ensure_task_folder,
+            query_scheduled_tasks,
+            scheduled_tasks_overview,
+            decode_task_result,
+            detect_schedule_conflicts,
+            test_run_task,
+            get_task_metrics,
+            create_monitor,
+            get_monitor_results,
+            set_all_tasks_enabled,
+            get_maintenance_mode,
+            preview_task_environment,
+            get_scheduled_path,
             delete_scheduled_task,
+            export_task_xml,
+            import_task_xml,
+            create_scheduled_tasks_batch,
+            backup_crontab,
+            restore_crontab,
+            audit_aui_crontab,
+            repair_aui_crontab,
+            can_modify_crontab,
+            preview_crontab_change,
+            check_url_reachable,
+            measure_download_speed,
+            download_file,
+            start_download,
+            get_url_metadata,
+            pause_download,
+            resume_download,
+            list_downloads,
+            file_sha256,
+            download_and_verify,
+            start_health_poll,
+            stop_health_poll,
+            subscribe_sse,
+            unsubscribe_sse,
+            get_system_info,
+            get_autostart,
+            set_autostart,
+            detect_runtime_environment,
+            check_dependencies,
+            capture_screen,
+            scheduler_health,
+            get_scheduling_backend,
+            check_cron_compatibility,
+            create_support_bundle,
         ])
         .setup(|app| {
+            use tauri::Manager;
+
+            // Pick up scripts still running from a previous session
+            if let Ok(dir) = app.path().app_data_dir() {
+                app.state::<Runs>().restore(dir.join("processes.json"));
+            }
             if cfg!(debug_assertions) {
+                DEBUG_SHOW_WINDOWS.store(
+                    std::env::var("AUI_DEBUG_SHOW_WINDOWS").is_ok_and(|v| v == "1"),
+                    Ordering::Relaxed,
+                );
                 app.handle().plugin(
                     tauri_plugin_log::Builder::default()
                         .level(log::LevelFilter::Info)
@@ -327,3 +13684,405 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A crontab reader that returns `reads` in turn, repeating the last.
+    #[cfg(not(target_os = "windows"))]
+    fn scripted_reads(reads: &[&str]) -> impl FnMut() -> Result<String, String> {
+        let reads: Vec<String> = reads.iter().map(|r| r.to_string()).collect();
+        let mut next = 0;
+        move || {
+            let read = reads[next.min(reads.len() - 1)].clone();
+            next += 1;
+            Ok(read)
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn modify_crontab_retries_when_the_crontab_changes_mid_edit() {
+        let mut written = Vec::new();
+        // The check read after the first edit sees another process's line
+        let result = modify_crontab_with(
+            |c| format!("{}mine\n", c),
+            scripted_reads(&["a\n", "a\nother\n", "a\nother\n", "a\nother\n"]),
+            |c| {
+                written.push(c.to_string());
+                Ok(())
+            },
+        );
+        assert_eq!(result.unwrap(), "a\nother\nmine\n");
+        assert_eq!(written, ["a\nother\nmine\n"]);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn modify_crontab_refuses_to_write_a_crontab_that_keeps_changing() {
+        let mut written = Vec::new();
+        let reads: Vec<String> = (0..CRONTAB_EDIT_ATTEMPTS * 2)
+            .map(|n| format!("line {}\n", n))
+            .collect();
+        let reads: Vec<&str> = reads.iter().map(String::as_str).collect();
+        let result = modify_crontab_with(
+            |c| format!("{}mine\n", c),
+            scripted_reads(&reads),
+            |c| {
+                written.push(c.to_string());
+                Ok(())
+            },
+        );
+        assert!(result.unwrap_err().starts_with("Conflict:"));
+        assert!(written.is_empty());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn task_folder_listing_is_empty_on_first_run() {
+        let no_match = "ERROR: The system cannot find the file specified.";
+        assert_eq!(
+            task_folder_listing(false, "", no_match, || Ok(0)),
+            Ok(String::new())
+        );
+        assert!(task_folder_listing(false, "", no_match, || Ok(2))
+            .unwrap_err()
+            .contains("cannot find the file"));
+        assert_eq!(
+            task_folder_listing(true, "\"row\"\r\n", "", || unreachable!()),
+            Ok("\"row\"\r\n".to_string())
+        );
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn schtasks_tasks_is_empty_without_aui_tasks() {
+        let none = HashMap::new();
+        for listing in [
+            "",
+            " \r\n\t\r\n",
+            "INFO: There are no scheduled tasks presently available at your access level.\r\n",
+        ] {
+            assert!(schtasks_tasks(listing, &none).is_empty(), "{:?}", listing);
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn crontab_tasks_is_empty_without_aui_tasks() {
+        let now = chrono::NaiveDate::from_ymd_opt(2026, 1, 1)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .unwrap();
+        for crontab in [
+            "",
+            "  \n\t\n",
+            "# m h dom mon dow command\nMAILTO=me\n0 9 * * * /home/u/backup.sh\n",
+        ] {
+            assert!(crontab_tasks(crontab, now).is_empty(), "{:?}", crontab);
+        }
+        assert_eq!(
+            crontab_tasks("0 9 * * * /bin/bash '/a.sh' # AUI:daily\n", now).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn redact_secrets_handles_non_ascii_lines() {
+        assert_eq!(
+            redact_secrets("İİ password=hunter2"),
+            "İİ password=[REDACTED]"
+        );
+        assert_eq!(
+            redact_secrets("\u{212a}elvin TOKEN: abc123 ok"),
+            "\u{212a}elvin TOKEN: [REDACTED] ok"
+        );
+        assert_eq!(redact_secrets("naïve café"), "naïve café");
+    }
+
+    #[test]
+    fn validate_task_name_refuses_paths_and_line_breaks() {
+        for name in ["backup", "nightly db-dump", "v1.2_report", "Überwachung"] {
+            assert_eq!(validate_task_name(name), Ok(()), "{}", name);
+        }
+        for name in [
+            "",
+            "  ",
+            "../../.ssh/authorized_keys",
+            "a/b",
+            "a\\b",
+            "..",
+            "x..y",
+            "ok\n* * * * * rm -rf ~",
+            "ok\r\nBcc: someone@example.com",
+            "tab\there",
+        ] {
+            assert!(validate_task_name(name).is_err(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn task_file_stem_keeps_only_file_safe_characters() {
+        assert_eq!(task_file_stem("nightly db-dump"), "nightly-db-dump");
+        assert_eq!(task_file_stem("v1.2_report"), "v1.2_report");
+        assert_eq!(task_file_stem("a/b\\c:d"), "a-b-c-d");
+        assert_eq!(task_file_stem("café"), "caf-");
+    }
+    #[test]
+    fn semver_orders_prereleases_before_their_release() {
+        let ordered = [
+            "0.9.12",
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "v1.0.1",
+            "1.10",
+        ];
+        let versions: Vec<SemVer> = ordered.iter().map(|v| SemVer::parse(v).unwrap()).collect();
+        for (pair, names) in versions.windows(2).zip(ordered.windows(2)) {
+            assert!(pair[0] < pair[1], "{} < {}", names[0], names[1]);
+        }
+        assert!(SemVer::parse("1.2.3+build.5") == SemVer::parse("V1.2.3"));
+        assert!(SemVer::parse("1.2.3-rc.1").unwrap().is_prerelease());
+        for bad in ["", "x", "1..2", "1.2.3.4", "1.-2", "latest-1"] {
+            assert!(SemVer::parse(bad).is_none(), "{:?}", bad);
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn parse_ps_cpu_time_reads_every_ps_format() {
+        assert_eq!(parse_ps_cpu_time("00:01.50"), Some(1_500));
+        assert_eq!(parse_ps_cpu_time("0:07"), Some(7_000));
+        assert_eq!(parse_ps_cpu_time("1:02:03"), Some(3_723_000));
+        assert_eq!(parse_ps_cpu_time("2-00:00:01"), Some(172_801_000));
+        for bad in ["", "-", "x:01", "1:xx", "a-00:00:01"] {
+            assert_eq!(parse_ps_cpu_time(bad), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn sse_parser_follows_event_source_framing() {
+        let mut parser = SseParser::default();
+        let feed = |parser: &mut SseParser, lines: &[&str]| -> Vec<(String, String)> {
+            lines.iter().filter_map(|line| parser.feed(line)).collect()
+        };
+
+        assert_eq!(
+            feed(&mut parser, &["data: hello", "data:world\r", ""]),
+            [("message".to_string(), "hello\nworld".to_string())]
+        );
+        assert_eq!(
+            feed(
+                &mut parser,
+                &["event: update", "id: 7", "retry: 2500", "data:  x", ""]
+            ),
+            [("update".to_string(), " x".to_string())]
+        );
+        assert_eq!(parser.last_event_id.as_deref(), Some("7"));
+        assert_eq!(parser.retry_ms, Some(2500));
+
+        // Comments, unknown fields and an event without data dispatch nothing,
+        // and the event name doesn't leak into the next one
+        assert!(feed(
+            &mut parser,
+            &[": keep-alive", "bogus: 1", "event: lone", "", ""]
+        )
+        .is_empty());
+        assert_eq!(
+            feed(&mut parser, &["data", ""]),
+            [("message".to_string(), String::new())]
+        );
+
+        // Bad ids and retries are ignored
+        feed(&mut parser, &["id: a\0b", "retry: soon", "retry: -1"]);
+        assert_eq!(parser.last_event_id.as_deref(), Some("7"));
+        assert_eq!(parser.retry_ms, Some(2500));
+    }
+
+    #[test]
+    fn bounded_output_keeps_the_head_and_tail() {
+        let mut output = BoundedOutput::new(10);
+        output.push(b"hello");
+        assert_eq!(output.text(), "hello");
+
+        let expected = format!("01234{}bcdef", OUTPUT_TRUNCATED_MARKER);
+        let mut output = BoundedOutput::new(10);
+        output.push(b"0123456789abcdef");
+        assert_eq!(output.text(), expected);
+
+        let mut output = BoundedOutput::new(10);
+        for byte in b"0123456789abcdef" {
+            output.push(&[*byte]);
+        }
+        assert_eq!(output.text(), expected);
+
+        let mut output = BoundedOutput::new(10);
+        output.push(b"0123456789");
+        assert_eq!(output.text(), "0123456789");
+    }
+
+    #[test]
+    fn split_response_headers_takes_the_last_header_block() {
+        let raw = "HTTP/1.1 100 Continue\r\n\r\n\
+                   HTTP/1.1 301 Moved\r\nLocation: /b\r\nX-Hop: 1\r\n\r\n\
+                   HTTP/2 200\r\nContent-Type: text/plain\r\nX-Time: 10:30\r\nbroken line\r\n\r\n\
+                   body\r\n\r\nmore";
+        let (headers, body) = split_response_headers(raw);
+        assert_eq!(
+            headers,
+            [
+                ("content-type".to_string(), "text/plain".to_string()),
+                ("x-time".to_string(), "10:30".to_string()),
+            ]
+        );
+        assert_eq!(body, "body\r\n\r\nmore");
+
+        assert_eq!(
+            split_response_headers("plain body"),
+            (Vec::new(), "plain body")
+        );
+        // Headers cut off before the blank line are left as the body
+        let cut = "HTTP/1.1 200 OK\r\nContent-Length: 3";
+        assert_eq!(split_response_headers(cut), (Vec::new(), cut));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn missing_path_dirs_lists_interactive_dirs_in_order() {
+        assert_eq!(
+            missing_path_dirs(
+                "/usr/bin:/bin",
+                "/opt/tool/bin:/usr/bin/:/bin::/home/u/.cargo/bin:/opt/tool/bin"
+            ),
+            ["/opt/tool/bin", "/home/u/.cargo/bin"]
+        );
+        assert!(missing_path_dirs("/usr/bin:/bin", "/bin:/usr/bin").is_empty());
+        assert!(missing_path_dirs("/usr/bin", "").is_empty());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn missing_path_dirs_lists_interactive_dirs_in_order() {
+        assert_eq!(
+            missing_path_dirs(
+                "C:\\Windows\\system32;C:\\Windows",
+                "C:\\Tools;c:\\windows\\System32\\;;C:\\Windows;C:\\Tools"
+            ),
+            ["C:\\Tools"]
+        );
+    }
+
+    #[test]
+    fn validate_backend_accepts_only_the_native_scheduler() {
+        let native = if cfg!(target_os = "windows") {
+            "schtasks"
+        } else {
+            "cron"
+        };
+        assert_eq!(validate_backend(None, false), Ok(None));
+        assert_eq!(validate_backend(Some("  "), false), Ok(None));
+        assert_eq!(
+            validate_backend(Some(&format!(" {} ", native.to_uppercase())), false),
+            Ok(Some(native.to_string()))
+        );
+        for other in ["systemd", "launchd"] {
+            assert!(validate_backend(Some(other), false)
+                .unwrap_err()
+                .starts_with("Unsupported:"));
+        }
+        assert!(validate_backend(Some("at"), false).is_err());
+        assert!(validate_backend(Some("anacron"), false)
+            .unwrap_err()
+            .starts_with("Unknown scheduling backend"));
+    }
+
+    #[test]
+    fn privileged_lines_flags_admin_commands_outside_comments() {
+        let shell = "#!/bin/sh\n\
+                     # sudo is only mentioned here\n\
+                     echo pseudo-random\n\
+                     ls | sudo tee /tmp/out\n\
+                     cat /etc/hosts\n\
+                     echo 127.0.0.1 box >> /etc/hosts\n\
+                     systemctl restart nginx\n";
+        let found: Vec<(usize, String)> = privileged_lines(shell, false)
+            .into_iter()
+            .map(|f| (f.line, f.reason))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (4, "runs commands with sudo".to_string()),
+                (6, "writes to a system directory".to_string()),
+                (7, "restarts a system service".to_string()),
+            ]
+        );
+
+        let powershell = "#Requires -RunAsAdministrator\r\n\
+                          # Stop-Service is only mentioned here\r\n\
+                          rem net stop spooler\r\n\
+                          Get-Content 'C:\\Windows\\win.ini'\r\n\
+                          Copy-Item tool.exe 'C:\\Program Files\\Tool'\r\n\
+                          Restart-Service Spooler\r\n";
+        let lines: Vec<usize> = privileged_lines(powershell, true)
+            .iter()
+            .map(|f| f.line)
+            .collect();
+        assert_eq!(lines, [1, 5, 6]);
+    }
+
+    #[test]
+    fn encode_header_encodes_non_ascii_and_refuses_line_breaks() {
+        assert_eq!(
+            encode_header("Weekly report"),
+            Ok("Weekly report".to_string())
+        );
+        assert_eq!(
+            encode_header("Wöchentlich"),
+            Ok("=?UTF-8?B?V8O2Y2hlbnRsaWNo?=".to_string())
+        );
+        // One and two bytes short of a full base64 group
+        assert_eq!(encode_header("é"), Ok("=?UTF-8?B?w6k=?=".to_string()));
+        assert_eq!(encode_header("€"), Ok("=?UTF-8?B?4oKs?=".to_string()));
+        for bad in [
+            "done\r\nBcc: someone@example.com",
+            "done\nX: y",
+            "tab\there",
+        ] {
+            assert!(encode_header(bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn parse_task_timing_reads_timing_lines() {
+        let run = parse_task_timing(
+            "started=2026-01-01T10:00:00 duration_ms=1500 exit=2 source=scheduled",
+        )
+        .unwrap();
+        assert_eq!(run.started, "2026-01-01T10:00:00");
+        assert_eq!(run.duration_ms, 1500);
+        assert_eq!(run.exit_code, Some(2));
+        assert_eq!(run.source, "scheduled");
+
+        let run = parse_task_timing("started=x duration_ms=0 exit=none").unwrap();
+        assert_eq!(run.exit_code, None);
+        assert_eq!(run.source, "");
+
+        for bad in [
+            "",
+            "started=2026-01-01T10:00:00 exit=0",
+            "duration_ms=15 exit=0",
+            "started=x duration_ms=-5",
+            "started=x duration_ms=1.5",
+            "started=x duration_ms==5",
+        ] {
+            assert!(parse_task_timing(bad).is_none(), "{:?}", bad);
+        }
+    }
+}
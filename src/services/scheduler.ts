@@ -291,7 +291,48 @@ export async function toggleSchedule(
   return records;
 }
 
-/** Query the OS to see which AUI tasks actually exist. */
-export async function listOsTasks(): Promise<string> {
-  return await invoke<string>("list_scheduled_tasks");
+/** How a task was created, recorded alongside its crontab entry (macOS/Linux). */
+export interface OsTaskMetadata {
+  task_name: string;
+  script_path: string;
+  spec: {
+    cron: string | null;
+    run_at: string | null;
+    repeat: string | null;
+    start_time: string | null;
+    start_date: string | null;
+    shell: string | null;
+  };
+  start_times?: string[];
+  priority: number | null;
+  created_at: string;
+}
+
+/** The system timezone the OS scheduler interprets task times in. */
+export interface ScheduleTimezone {
+  name: string;
+  utc_offset: string;
+}
+
+/** An AUI task as the OS scheduler reports it (see `list_scheduled_tasks`). */
+export interface OsScheduledTask {
+  backend: string;
+  task_name: string;
+  schedule_spec: string;
+  schedule_kind: string;
+  command: string;
+  script_path: string;
+  node_id: string | null;
+  enabled: boolean;
+  next_run: string | null;
+  last_result: string | null;
+  last_result_text: string | null;
+  priority: number | null;
+  metadata: OsTaskMetadata | null;
+  timezone: ScheduleTimezone | null;
+}
+
+/** Query the OS to see which AUI tasks actually exist. Empty when there are none. */
+export async function listOsTasks(): Promise<OsScheduledTask[]> {
+  return await invoke<OsScheduledTask[]>("list_scheduled_tasks");
 }